@@ -98,15 +98,9 @@ export function useBuyTokens(agentId: string) {
 
       setLoading(true)
       try {
-        // Calculate minimum tokens with slippage
-        const agent = await sdk.getAgent(agentId)
-        const tokensOut = sdk.calculateTokensOut(
-          usdtAmount,
-          agent.bondingCurve.basePrice.toNumber(),
-          agent.circulatingSupply.toNumber(),
-          agent.bondingCurve.maxSupply.toNumber(),
-        )
-        const minTokensOut = tokensOut * (1 - slippage / 100)
+        // Calculate minimum tokens with slippage from the program's own quote
+        const quote = await sdk.quoteBuy(agentId, usdtAmount)
+        const minTokensOut = quote.tokensOut.toNumber() * (1 - slippage / 100)
 
         const usdtMint = new PublicKey("USDT_MINT_ADDRESS") // Replace with actual USDT mint
         const tx = await sdk.buyFromCurve(publicKey, agentId, usdtAmount, Math.floor(minTokensOut), usdtMint)
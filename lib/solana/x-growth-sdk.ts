@@ -1,16 +1,74 @@
 import { type Program, type AnchorProvider, BN } from "@coral-xyz/anchor"
-import { Connection, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from "@solana/web3.js"
+import {
+  Connection,
+  Ed25519Program,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  SYSVAR_RENT_PUBKEY,
+  type AccountMeta,
+} from "@solana/web3.js"
 import { TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddress } from "@solana/spl-token"
 
 // Program ID - replace with your deployed program ID
-export const PROGRAM_ID = new PublicKey("XGrowth11111111111111111111111111111111111")
+export const PROGRAM_ID = new PublicKey("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS")
 
-export interface BondingCurveParams {
+// Curve families. Prices are USDT base units per 1_000_000 token units; shape factors
+// (curveFactor, growthRate, steepness) are fixed point with 1_000_000 = 1.0
+export interface QuadraticCurve {
   basePrice: BN
   curveFactor: BN
   maxSupply: BN
 }
 
+export interface LinearCurve {
+  basePrice: BN
+  finalPrice: BN
+  maxSupply: BN
+}
+
+export interface ExponentialCurve {
+  basePrice: BN
+  growthRate: BN
+  maxSupply: BN
+}
+
+export interface SigmoidCurve {
+  basePrice: BN
+  maxPrice: BN
+  midpoint: BN
+  steepness: BN
+  maxSupply: BN
+}
+
+export interface ConstantProductCurve {
+  virtualUsdtReserve: BN
+  virtualTokenReserve: BN
+  maxSupply: BN
+}
+
+// The program's BondingCurveParams enum; Anchor encodes a tuple variant as { variant: { 0: fields } }
+export type BondingCurveParams =
+  | { quadratic: { 0: QuadraticCurve } }
+  | { linear: { 0: LinearCurve } }
+  | { exponential: { 0: ExponentialCurve } }
+  | { sigmoid: { 0: SigmoidCurve } }
+  | { constantProduct: { 0: ConstantProductCurve } }
+
+// Returned by quote_buy / quote_sell; amounts in USDT and token base units
+export interface TradeQuote {
+  usdtIn: BN
+  tokensOut: BN
+  tokensIn: BN
+  usdtOut: BN
+  fee: BN
+  protocolFee: BN
+  creatorFee: BN
+  referrerFee: BN
+  priceImpactBps: BN
+  spotPriceAfter: BN
+}
+
 export interface PerformanceMetrics {
   totalLikes: BN
   totalViews: BN
@@ -55,6 +113,46 @@ export class XGrowthSDK {
     return PublicKey.findProgramAddressSync([Buffer.from("reserve"), agentPubkey.toBuffer()], PROGRAM_ID)
   }
 
+  getTreasuryPDA(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("treasury")], PROGRAM_ID)
+  }
+
+  getRewardPoolPDA(): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("reward_pool")], PROGRAM_ID)
+  }
+
+  getStakeVaultPDA(agentPubkey: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("stake_vault"), agentPubkey.toBuffer()], PROGRAM_ID)
+  }
+
+  getUserStakePDA(user: PublicKey, agent: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("stake"), user.toBuffer(), agent.toBuffer()], PROGRAM_ID)
+  }
+
+  getRewardStreamPDA(agent: PublicKey, index: number): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("reward_stream"), agent.toBuffer(), Buffer.from([index])],
+      PROGRAM_ID,
+    )
+  }
+
+  getStreamVaultPDA(stream: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("stream_vault"), stream.toBuffer()], PROGRAM_ID)
+  }
+
+  // Every reward stream of the agent, in index order, as stake and unstake expect them
+  async getRewardStreamAccounts(agentPDA: PublicKey): Promise<AccountMeta[]> {
+    if (!this.program) throw new Error("Provider not set")
+
+    const agent = await this.program.account.agent.fetch(agentPDA)
+    const count = agent.rewardStreamCount as number
+    return Array.from({ length: count }, (_, index) => ({
+      pubkey: this.getRewardStreamPDA(agentPDA, index)[0],
+      isSigner: false,
+      isWritable: true,
+    }))
+  }
+
   getUserRewardsPDA(user: PublicKey, agent: PublicKey): [PublicKey, number] {
     return PublicKey.findProgramAddressSync(
       [Buffer.from("user_rewards"), user.toBuffer(), agent.toBuffer()],
@@ -67,6 +165,8 @@ export class XGrowthSDK {
     if (!this.program) throw new Error("Provider not set")
 
     const [platformPDA] = this.getPlatformPDA()
+    const [treasuryPDA] = this.getTreasuryPDA()
+    const [rewardPoolPDA] = this.getRewardPoolPDA()
 
     const tx = await this.program.methods
       .initializePlatform(new BN(dailyRewardPool))
@@ -74,8 +174,12 @@ export class XGrowthSDK {
         platform: platformPDA,
         authority,
         usdtMint,
+        treasury: treasuryPDA,
+        rewardPool: rewardPoolPDA,
         oracle,
+        tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
       })
       .rpc()
 
@@ -91,12 +195,15 @@ export class XGrowthSDK {
     uri: string,
     initialSupply: number,
     bondingCurveParams: BondingCurveParams,
+    usdtMint: PublicKey,
   ) {
     if (!this.program) throw new Error("Provider not set")
 
     const [platformPDA] = this.getPlatformPDA()
     const [agentPDA] = this.getAgentPDA(agentId)
     const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [reservePDA] = this.getReservePDA(agentPDA)
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
 
     const tx = await this.program.methods
       .createAgent(agentId, name, symbol, uri, new BN(initialSupply), bondingCurveParams)
@@ -104,6 +211,9 @@ export class XGrowthSDK {
         platform: platformPDA,
         agent: agentPDA,
         tokenMint: tokenMintPDA,
+        usdtMint,
+        reserveUsdtAccount: reservePDA,
+        stakeVault: stakeVaultPDA,
         authority,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -122,13 +232,12 @@ export class XGrowthSDK {
     const [agentPDA] = this.getAgentPDA(agentId)
     const [tokenMintPDA] = this.getTokenMintPDA(agentId)
     const [reservePDA] = this.getReservePDA(agentPDA)
+    const [treasuryPDA] = this.getTreasuryPDA()
 
     const buyerTokenAccount = await getAssociatedTokenAddress(tokenMintPDA, buyer)
 
     const buyerUsdtAccount = await getAssociatedTokenAddress(usdtMint, buyer)
 
-    const reserveUsdtAccount = await getAssociatedTokenAddress(usdtMint, reservePDA, true)
-
     const tx = await this.program.methods
       .buyFromCurve(new BN(usdtAmount), new BN(minTokensOut))
       .accounts({
@@ -136,9 +245,11 @@ export class XGrowthSDK {
         tokenMint: tokenMintPDA,
         buyerTokenAccount,
         buyerUsdtAccount,
-        reserveUsdtAccount,
+        reserveUsdtAccount: reservePDA,
         platform: platformPDA,
+        treasury: treasuryPDA,
         buyer,
+        referrerUsdtAccount: null,
         tokenProgram: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
@@ -148,47 +259,293 @@ export class XGrowthSDK {
     return tx
   }
 
-  // Calculate price from bonding curve
-  calculatePrice(basePrice: number, currentSupply: number, maxSupply: number): number {
-    const supplyRatio = currentSupply / maxSupply
-    const priceMultiplier = 1 + supplyRatio
-    return basePrice * Math.pow(priceMultiplier, 2)
+  // Quote a buy against the live curve and fee schedule. The read-only quote_buy instruction is
+  // simulated and its TradeQuote decoded from the return data, so the numbers are the program's own.
+  async quoteBuy(agentId: string, usdtAmount: number, withReferrer = false): Promise<TradeQuote> {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+
+    return (await this.program.methods
+      .quoteBuy(new BN(usdtAmount), withReferrer)
+      .accounts({ agent: agentPDA, platform: platformPDA })
+      .view()) as TradeQuote
+  }
+
+  // Quote a sell of `tokenAmount` the same way, through quote_sell
+  async quoteSell(agentId: string, tokenAmount: number, withReferrer = false): Promise<TradeQuote> {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+
+    return (await this.program.methods
+      .quoteSell(new BN(tokenAmount), withReferrer)
+      .accounts({ agent: agentPDA, platform: platformPDA })
+      .view()) as TradeQuote
+  }
+
+  // Update performance (oracle only). `values` are deltas in the order of the report version:
+  // v1 likes, views, comments, newFollowers; v2 adds reposts, quotes, bookmarks, impressions, followersLost
+  async updatePerformance(oracle: PublicKey, agentId: string, sequence: number, version: number, values: number[]) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+
+    const tx = await this.program.methods
+      .updatePerformance({ version, values: values.map((value) => new BN(value)) }, new BN(sequence), null)
+      .accounts({
+        agent: agentPDA,
+        platform: platformPDA,
+        reportSequence: this.getReportSequencePDA(agentPDA)[0],
+        oracle,
+        payer: oracle,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Tracks the sequence number of the agent's last applied report; each report needs a higher one
+  getReportSequencePDA(agentPDA: PublicKey): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync([Buffer.from("report_sequence"), agentPDA.toBuffer()], PROGRAM_ID)
   }
 
-  // Calculate tokens out for USDT amount
-  calculateTokensOut(usdtAmount: number, basePrice: number, currentSupply: number, maxSupply: number): number {
-    const avgPrice = this.calculatePrice(basePrice, currentSupply, maxSupply)
-    return usdtAmount / avgPrice
+  // The bytes an oracle signs off-chain so that anyone can relay its report
+  attestationMessage(agentPDA: PublicKey, epoch: number, sequence: number, version: number, values: number[]): Buffer {
+    const report = Buffer.alloc(5 + 8 * values.length)
+    report.writeUInt8(version, 0)
+    report.writeUInt32LE(values.length, 1)
+    values.forEach((value, i) => new BN(value).toArrayLike(Buffer, "le", 8).copy(report, 5 + 8 * i))
+    return Buffer.concat([
+      Buffer.from("x-growth:metric-attestation:v1"),
+      PROGRAM_ID.toBuffer(),
+      agentPDA.toBuffer(),
+      new BN(epoch).toArrayLike(Buffer, "le", 8),
+      new BN(sequence).toArrayLike(Buffer, "le", 8),
+      report,
+    ])
   }
 
-  // Update performance (oracle only)
-  async updatePerformance(
+  // Relay an oracle-signed report; the relayer pays, the Ed25519 precompile checks the signature
+  async relayPerformance(
+    relayer: PublicKey,
     oracle: PublicKey,
     agentId: string,
-    likes: number,
-    views: number,
-    comments: number,
-    newFollowers: number,
+    epoch: number,
+    sequence: number,
+    version: number,
+    values: number[],
+    signature: Uint8Array,
   ) {
     if (!this.program) throw new Error("Provider not set")
 
     const [platformPDA] = this.getPlatformPDA()
     const [agentPDA] = this.getAgentPDA(agentId)
+    const verify = Ed25519Program.createInstructionWithPublicKey({
+      publicKey: oracle.toBytes(),
+      message: this.attestationMessage(agentPDA, epoch, sequence, version, values),
+      signature,
+    })
+
+    const tx = await this.program.methods
+      .updatePerformance(
+        { version, values: values.map((value) => new BN(value)) },
+        new BN(sequence),
+        { epoch: new BN(epoch) },
+      )
+      .accounts({
+        agent: agentPDA,
+        platform: platformPDA,
+        reportSequence: this.getReportSequencePDA(agentPDA)[0],
+        oracle,
+        payer: relayer,
+        instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        systemProgram: SystemProgram.programId,
+      })
+      .preInstructions([verify])
+      .rpc()
+
+    return tx
+  }
+
+  // Observation PDA of an agent and epoch, where oracle submissions await the quorum
+  getObservationPDA(agentPDA: PublicKey, epoch: number): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("observation"), agentPDA.toBuffer(), new BN(epoch).toArrayLike(Buffer, "le", 8)],
+      PROGRAM_ID,
+    )
+  }
+
+  // Submit one oracle's metrics for an agent and epoch (oracle set only); the median applies at the quorum
+  async submitObservation(oracle: PublicKey, agentId: string, epoch: number, version: number, values: number[]) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [epochPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("epoch"), new BN(epoch).toArrayLike(Buffer, "le", 8)],
+      PROGRAM_ID,
+    )
+    const [observationPDA] = this.getObservationPDA(agentPDA, epoch)
 
     const tx = await this.program.methods
-      .updatePerformance(new BN(likes), new BN(views), new BN(comments), new BN(newFollowers))
+      .submitObservation({ version, values: values.map((value) => new BN(value)) })
       .accounts({
         agent: agentPDA,
         platform: platformPDA,
+        epoch: epochPDA,
+        observation: observationPDA,
         oracle,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Dispute PDA of an agent and epoch, created when its recorded score is challenged
+  getDisputePDA(agentPDA: PublicKey, epoch: number): [PublicKey, number] {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from("dispute"), agentPDA.toBuffer(), new BN(epoch).toArrayLike(Buffer, "le", 8)],
+      PROGRAM_ID,
+    )
+  }
+
+  // Flag an agent's recorded score within the epoch's dispute window (authority or guardian only)
+  async disputeAgentScore(challenger: PublicKey, agentId: string, epoch: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [epochPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("epoch"), new BN(epoch).toArrayLike(Buffer, "le", 8)],
+      PROGRAM_ID,
+    )
+
+    const tx = await this.program.methods
+      .disputeAgentScore()
+      .accounts({
+        agent: agentPDA,
+        epoch: epochPDA,
+        platform: platformPDA,
+        dispute: this.getDisputePDA(agentPDA, epoch)[0],
+        challenger,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Post the corrected daily metrics of a disputed agent (authority or guardian only); the agent
+  // is credited on the lower of the corrected and recorded scores
+  async resolveDispute(resolver: PublicKey, agentId: string, epoch: number, version: number, values: number[]) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [epochPDA] = PublicKey.findProgramAddressSync(
+      [Buffer.from("epoch"), new BN(epoch).toArrayLike(Buffer, "le", 8)],
+      PROGRAM_ID,
+    )
+
+    const tx = await this.program.methods
+      .resolveDispute({ version, values: values.map((value) => new BN(value)) })
+      .accounts({
+        agent: agentPDA,
+        epoch: epochPDA,
+        platform: platformPDA,
+        dispute: this.getDisputePDA(agentPDA, epoch)[0],
+        resolver,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Move the platform written by the original program to the current layout (platform authority only)
+  async migratePlatform(authority: PublicKey, usdtMint: PublicKey) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [treasuryPDA] = this.getTreasuryPDA()
+    const [rewardPoolPDA] = this.getRewardPoolPDA()
+
+    const tx = await this.program.methods
+      .migratePlatform()
+      .accounts({
+        platform: platformPDA,
+        authority,
+        usdtMint,
+        treasury: treasuryPDA,
+        rewardPool: rewardPoolPDA,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Move an agent written by the original program to the current layout (anyone, payer covers the rent)
+  async migrateAgent(payer: PublicKey, agentId: string, usdtMint: PublicKey) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [reservePDA] = this.getReservePDA(agentPDA)
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
+
+    const tx = await this.program.methods
+      .migrateAgent()
+      .accounts({
+        platform: platformPDA,
+        agent: agentPDA,
+        tokenMint: tokenMintPDA,
+        usdtMint,
+        reserveUsdtAccount: reservePDA,
+        stakeVault: stakeVaultPDA,
+        payer,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
       })
       .rpc()
 
     return tx
   }
 
-  // Claim rewards
-  async claimRewards(user: PublicKey, agentId: string, usdtMint: PublicKey) {
+  // Deposit USDT into the reward pool (anyone)
+  async fundRewardPool(funder: PublicKey, amount: number, usdtMint: PublicKey) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [rewardPoolPDA] = this.getRewardPoolPDA()
+    const funderUsdtAccount = await getAssociatedTokenAddress(usdtMint, funder)
+
+    const tx = await this.program.methods
+      .fundRewardPool(new BN(amount))
+      .accounts({
+        platform: platformPDA,
+        rewardPool: rewardPoolPDA,
+        funderUsdtAccount,
+        funder,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Claim USDT rewards plus the balances of the given reward stream indexes, paid to the user's ATAs
+  async claimRewards(user: PublicKey, agentId: string, usdtMint: PublicKey, streamIndexes: number[] = []) {
     if (!this.program) throw new Error("Provider not set")
 
     const [platformPDA] = this.getPlatformPDA()
@@ -197,7 +554,21 @@ export class XGrowthSDK {
 
     const userUsdtAccount = await getAssociatedTokenAddress(usdtMint, user)
 
-    const [rewardPoolPDA] = PublicKey.findProgramAddressSync([Buffer.from("reward_pool")], PROGRAM_ID)
+    const [rewardPoolPDA] = this.getRewardPoolPDA()
+
+    // (stream, stream vault, destination) triples
+    const streamAccounts: AccountMeta[] = []
+    for (const index of streamIndexes) {
+      const [streamPDA] = this.getRewardStreamPDA(agentPDA, index)
+      const [streamVaultPDA] = this.getStreamVaultPDA(streamPDA)
+      const stream = await this.program.account.rewardStream.fetch(streamPDA)
+      const destination = await getAssociatedTokenAddress(stream.mint as PublicKey, user)
+      streamAccounts.push(
+        { pubkey: streamPDA, isSigner: false, isWritable: true },
+        { pubkey: streamVaultPDA, isSigner: false, isWritable: true },
+        { pubkey: destination, isSigner: false, isWritable: true },
+      )
+    }
 
     const tx = await this.program.methods
       .claimRewards()
@@ -210,6 +581,262 @@ export class XGrowthSDK {
         user,
         tokenProgram: TOKEN_PROGRAM_ID,
       })
+      .remainingAccounts(streamAccounts)
+      .rpc()
+
+    return tx
+  }
+
+  // Buy more agent tokens with pending USDT rewards, optionally staking them
+  async compoundRewards(user: PublicKey, agentId: string, minTokensOut: number, stake: boolean) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [userRewardsPDA] = this.getUserRewardsPDA(user, agentPDA)
+    const [rewardPoolPDA] = this.getRewardPoolPDA()
+    const [reservePDA] = this.getReservePDA(agentPDA)
+    const [treasuryPDA] = this.getTreasuryPDA()
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
+    const [userStakePDA] = this.getUserStakePDA(user, agentPDA)
+    const streamAccounts = stake ? await this.getRewardStreamAccounts(agentPDA) : []
+
+    const userTokenAccount = await getAssociatedTokenAddress(tokenMintPDA, user)
+
+    const tx = await this.program.methods
+      .compoundRewards(new BN(minTokensOut), stake)
+      .accounts({
+        agent: agentPDA,
+        tokenMint: tokenMintPDA,
+        userRewards: userRewardsPDA,
+        rewardPool: rewardPoolPDA,
+        reserveUsdtAccount: reservePDA,
+        treasury: treasuryPDA,
+        platform: platformPDA,
+        // Only needed when the bought tokens are not staked
+        userTokenAccount: stake ? null : userTokenAccount,
+        // Only needed when staking the bought tokens
+        stakeVault: stake ? stakeVaultPDA : null,
+        userStake: stake ? userStakePDA : null,
+        user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(streamAccounts)
+      .rpc()
+
+    return tx
+  }
+
+  // Stake agent tokens to earn the agent's rewards
+  async stake(user: PublicKey, agentId: string, amount: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
+    const [userStakePDA] = this.getUserStakePDA(user, agentPDA)
+    const [userRewardsPDA] = this.getUserRewardsPDA(user, agentPDA)
+    const streamAccounts = await this.getRewardStreamAccounts(agentPDA)
+
+    const userTokenAccount = await getAssociatedTokenAddress(tokenMintPDA, user)
+
+    const tx = await this.program.methods
+      .stake(new BN(amount))
+      .accounts({
+        agent: agentPDA,
+        platform: platformPDA,
+        stakeVault: stakeVaultPDA,
+        userTokenAccount,
+        userStake: userStakePDA,
+        userRewards: userRewardsPDA,
+        user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(streamAccounts)
+      .rpc()
+
+    return tx
+  }
+
+  // Unstake agent tokens; with an unbonding period they are withdrawn later via withdrawStake
+  async unstake(user: PublicKey, agentId: string, amount: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [platformPDA] = this.getPlatformPDA()
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
+    const [userStakePDA] = this.getUserStakePDA(user, agentPDA)
+    const [userRewardsPDA] = this.getUserRewardsPDA(user, agentPDA)
+    const streamAccounts = await this.getRewardStreamAccounts(agentPDA)
+
+    const userTokenAccount = await getAssociatedTokenAddress(tokenMintPDA, user)
+
+    const tx = await this.program.methods
+      .unstake(new BN(amount))
+      .accounts({
+        agent: agentPDA,
+        platform: platformPDA,
+        stakeVault: stakeVaultPDA,
+        userTokenAccount,
+        userStake: userStakePDA,
+        userRewards: userRewardsPDA,
+        user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(streamAccounts)
+      .rpc()
+
+    return tx
+  }
+
+  // Start a user's stake earning once the agent is credited for the epoch it was staked in (anyone can call)
+  async activateStake(user: PublicKey, agentId: string) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [userStakePDA] = this.getUserStakePDA(user, agentPDA)
+    const [userRewardsPDA] = this.getUserRewardsPDA(user, agentPDA)
+    const streamAccounts = await this.getRewardStreamAccounts(agentPDA)
+
+    const tx = await this.program.methods
+      .activateStake()
+      .accounts({
+        agent: agentPDA,
+        userStake: userStakePDA,
+        userRewards: userRewardsPDA,
+      })
+      .remainingAccounts(streamAccounts)
+      .rpc()
+
+    return tx
+  }
+
+  // Withdraw unstaked tokens after the unbonding period
+  async withdrawStake(user: PublicKey, agentId: string) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [tokenMintPDA] = this.getTokenMintPDA(agentId)
+    const [stakeVaultPDA] = this.getStakeVaultPDA(agentPDA)
+    const [userStakePDA] = this.getUserStakePDA(user, agentPDA)
+
+    const userTokenAccount = await getAssociatedTokenAddress(tokenMintPDA, user)
+
+    const tx = await this.program.methods
+      .withdrawStake()
+      .accounts({
+        agent: agentPDA,
+        stakeVault: stakeVaultPDA,
+        userTokenAccount,
+        userStake: userStakePDA,
+        user,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Open the agent's next reward stream in `rewardMint` (agent creator only)
+  async createRewardStream(authority: PublicKey, agentId: string, rewardMint: PublicKey, emissionRate: number, endTime: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const agent = await this.program.account.agent.fetch(agentPDA)
+    const [streamPDA] = this.getRewardStreamPDA(agentPDA, agent.rewardStreamCount as number)
+    const [streamVaultPDA] = this.getStreamVaultPDA(streamPDA)
+
+    const tx = await this.program.methods
+      .createRewardStream(new BN(emissionRate), new BN(endTime))
+      .accounts({
+        agent: agentPDA,
+        stream: streamPDA,
+        streamVault: streamVaultPDA,
+        rewardMint,
+        authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Deposit reward tokens into a stream from the creator's ATA (agent creator only)
+  async fundRewardStream(authority: PublicKey, agentId: string, index: number, amount: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [streamPDA] = this.getRewardStreamPDA(agentPDA, index)
+    const [streamVaultPDA] = this.getStreamVaultPDA(streamPDA)
+    const stream = await this.program.account.rewardStream.fetch(streamPDA)
+    const funderTokenAccount = await getAssociatedTokenAddress(stream.mint as PublicKey, authority)
+
+    const tx = await this.program.methods
+      .fundRewardStream(new BN(amount))
+      .accounts({
+        agent: agentPDA,
+        stream: streamPDA,
+        streamVault: streamVaultPDA,
+        funderTokenAccount,
+        authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // End a stream and refund what stakers cannot claim to the creator's ATA (agent creator only)
+  async endRewardStream(authority: PublicKey, agentId: string, index: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [streamPDA] = this.getRewardStreamPDA(agentPDA, index)
+    const [streamVaultPDA] = this.getStreamVaultPDA(streamPDA)
+    const stream = await this.program.account.rewardStream.fetch(streamPDA)
+    const creatorTokenAccount = await getAssociatedTokenAddress(stream.mint as PublicKey, authority)
+
+    const tx = await this.program.methods
+      .endRewardStream()
+      .accounts({
+        agent: agentPDA,
+        stream: streamPDA,
+        streamVault: streamVaultPDA,
+        creatorTokenAccount,
+        authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .rpc()
+
+    return tx
+  }
+
+  // Refund what an ended stream's stakers can no longer claim to the creator's ATA (agent creator only)
+  async sweepRewardStream(authority: PublicKey, agentId: string, index: number) {
+    if (!this.program) throw new Error("Provider not set")
+
+    const [agentPDA] = this.getAgentPDA(agentId)
+    const [streamPDA] = this.getRewardStreamPDA(agentPDA, index)
+    const [streamVaultPDA] = this.getStreamVaultPDA(streamPDA)
+    const stream = await this.program.account.rewardStream.fetch(streamPDA)
+    const creatorTokenAccount = await getAssociatedTokenAddress(stream.mint as PublicKey, authority)
+
+    const tx = await this.program.methods
+      .sweepRewardStream()
+      .accounts({
+        agent: agentPDA,
+        stream: streamPDA,
+        streamVault: streamVaultPDA,
+        creatorTokenAccount,
+        authority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
       .rpc()
 
     return tx
@@ -0,0 +1,510 @@
+use anchor_lang::prelude::*;
+use crate::errors::XGrowthError;
+use crate::math::{self, U256, WAD};
+
+// Prices are quoted in USDT per PRICE_PRECISION token units
+pub const PRICE_PRECISION: u64 = 1_000_000;
+
+// Shape factors (curve_factor, growth_rate, steepness) are fixed point with FACTOR_PRECISION = 1.0
+pub const FACTOR_PRECISION: u64 = 1_000_000;
+
+pub trait Curve {
+    fn max_supply(&self) -> u64;
+
+    // Shape-specific parameter checks
+    fn validate(&self) -> Result<()>;
+
+    // Spot price at `supply`
+    fn calculate_price(&self, supply: u64) -> Result<u64>;
+
+    // USDT the reserve must hold to back `supply` tokens, i.e. the integral of the
+    // price from 0 to supply, rounded up
+    fn calculate_reserve(&self, supply: u64) -> Result<u64>;
+
+    // Supply backed by `reserve` USDT. Only needs to be close: callers settle the
+    // rounding against calculate_reserve.
+    fn estimate_supply(&self, reserve: u64) -> Result<u64>;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum BondingCurveParams {
+    Quadratic(QuadraticCurve),
+    Linear(LinearCurve),
+    Exponential(ExponentialCurve),
+    Sigmoid(SigmoidCurve),
+    ConstantProduct(ConstantProductCurve),
+}
+
+impl BondingCurveParams {
+    pub const LEN: usize = 1 + SigmoidCurve::LEN;
+
+    fn curve(&self) -> &dyn Curve {
+        match self {
+            BondingCurveParams::Quadratic(curve) => curve,
+            BondingCurveParams::Linear(curve) => curve,
+            BondingCurveParams::Exponential(curve) => curve,
+            BondingCurveParams::Sigmoid(curve) => curve,
+            BondingCurveParams::ConstantProduct(curve) => curve,
+        }
+    }
+
+    pub fn max_supply(&self) -> u64 {
+        self.curve().max_supply()
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(self.max_supply() > 0, XGrowthError::InvalidBondingCurve);
+        self.curve().validate()?;
+        // The fully sold-out reserve must be representable
+        self.calculate_reserve(self.max_supply())
+            .map_err(|_| error!(XGrowthError::InvalidBondingCurve))?;
+        Ok(())
+    }
+
+    pub fn calculate_price(&self, supply: u64) -> Result<u64> {
+        self.curve().calculate_price(supply)
+    }
+
+    pub fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        self.curve().calculate_reserve(supply)
+    }
+
+    // USDT required to mint `token_amount` on top of `current_supply`
+    pub fn calculate_buy_cost(&self, current_supply: u64, token_amount: u64) -> Result<u64> {
+        let new_supply = current_supply
+            .checked_add(token_amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.calculate_reserve(new_supply)?
+            .checked_sub(self.calculate_reserve(current_supply)?)
+            .ok_or_else(|| error!(XGrowthError::MathOverflow))
+    }
+
+    // USDT released by burning `token_amount` from `current_supply`
+    pub fn calculate_sell_return(&self, current_supply: u64, token_amount: u64) -> Result<u64> {
+        let new_supply = current_supply
+            .checked_sub(token_amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.calculate_reserve(current_supply)?
+            .checked_sub(self.calculate_reserve(new_supply)?)
+            .ok_or_else(|| error!(XGrowthError::MathOverflow))
+    }
+
+    // Calculate tokens received for USDT amount: the largest amount whose buy cost
+    // fits in `usdt_amount`. The curve's inverse gives the starting point and the
+    // exact integral settles the rounding.
+    pub fn calculate_tokens_out(&self, usdt_amount: u64, current_supply: u64) -> Result<u64> {
+        let remaining = self
+            .max_supply()
+            .checked_sub(current_supply)
+            .ok_or(XGrowthError::MaxSupplyReached)?;
+        require!(
+            usdt_amount <= self.calculate_buy_cost(current_supply, remaining)?,
+            XGrowthError::MaxSupplyReached
+        );
+
+        let target_reserve = self
+            .calculate_reserve(current_supply)?
+            .checked_add(usdt_amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        let estimate = self
+            .curve()
+            .estimate_supply(target_reserve)?
+            .saturating_sub(current_supply);
+        math::search_max_fitting(estimate, remaining, |tokens| {
+            Ok(self.calculate_buy_cost(current_supply, tokens)? <= usdt_amount)
+        })
+    }
+
+    // Calculate tokens to burn for a USDT amount: the smallest amount whose sell
+    // return covers `usdt_amount`. Solved as the largest supply left behind whose
+    // reserve still releases enough.
+    pub fn calculate_tokens_in(&self, usdt_amount: u64, current_supply: u64) -> Result<u64> {
+        let current_reserve = self.calculate_reserve(current_supply)?;
+        let target_reserve = current_reserve
+            .checked_sub(usdt_amount)
+            .ok_or(XGrowthError::InsufficientReserve)?;
+
+        let estimate = self.curve().estimate_supply(target_reserve)?;
+        let supply_left = math::search_max_fitting(estimate, current_supply, |supply| {
+            Ok(self.calculate_reserve(supply)? <= target_reserve)
+        })?;
+        Ok(current_supply - supply_left)
+    }
+}
+
+// Price = base_price * (1 + curve_factor * supply / max_supply)^2
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct QuadraticCurve {
+    pub base_price: u64,      // Base price in USDT (with decimals)
+    pub curve_factor: u64,    // Curve steepness, FACTOR_PRECISION = 1.0
+    pub max_supply: u64,      // Maximum tokens that can be sold
+}
+
+impl QuadraticCurve {
+    pub const LEN: usize = 8 + 8 + 8;
+    pub const MAX_CURVE_FACTOR: u64 = 100 * FACTOR_PRECISION;
+
+    // (1 + curve_factor * supply / max_supply)^3 - 1 in WAD, rounded up
+    fn cube_growth(&self, supply: u64) -> Result<U256> {
+        let wad = math::wad();
+        let ratio = math::checked_add(
+            wad,
+            math::mul_div_ceil(
+                U256::from(supply) * U256::from(self.curve_factor),
+                wad,
+                U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+            )?,
+        )?;
+        let cubed = math::checked_mul(math::checked_mul(ratio, ratio)?, ratio)?;
+        math::mul_div_ceil(cubed - wad * wad * wad, U256::one(), wad * wad)
+    }
+}
+
+impl Curve for QuadraticCurve {
+    fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(self.base_price > 0, XGrowthError::InvalidBondingCurve);
+        require!(
+            self.curve_factor > 0 && self.curve_factor <= Self::MAX_CURVE_FACTOR,
+            XGrowthError::InvalidBondingCurve
+        );
+        Ok(())
+    }
+
+    fn calculate_price(&self, supply: u64) -> Result<u64> {
+        let precision = FACTOR_PRECISION as u128;
+        let supply_ratio = (supply as u128 * self.curve_factor as u128) / self.max_supply as u128;
+        let price_multiplier = precision + supply_ratio;
+        let squared = (price_multiplier * price_multiplier) / precision;
+        math::to_u64(U256::from(self.base_price) * U256::from(squared) / U256::from(precision))
+    }
+
+    // Reserve = base_price * max_supply / (3 * curve_factor) * ((1 + curve_factor * supply / max_supply)^3 - 1)
+    fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        let growth = self.cube_growth(supply)?;
+        let numerator = math::checked_mul(
+            U256::from(self.base_price),
+            U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+        )?;
+        let denominator = math::checked_mul(
+            U256::from(3 * PRICE_PRECISION) * U256::from(self.curve_factor),
+            math::wad(),
+        )?;
+        math::to_u64(math::mul_div_ceil(numerator, growth, denominator)?)
+    }
+
+    // supply = max_supply / k * (cbrt(1 + 3 * k * reserve / (base_price * max_supply)) - 1)
+    fn estimate_supply(&self, reserve: u64) -> Result<u64> {
+        let wad = math::wad();
+        let growth = math::mul_div_floor(
+            U256::from(reserve) * U256::from(3 * PRICE_PRECISION) * U256::from(self.curve_factor),
+            wad,
+            math::checked_mul(
+                U256::from(self.base_price),
+                U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+            )?,
+        )?;
+        let cubed = math::checked_add(
+            math::checked_mul(growth, wad * wad)?,
+            wad * wad * wad,
+        )?;
+        let ratio = math::integer_root(cubed, 3)?;
+        math::to_u64(math::mul_div_floor(
+            ratio - wad,
+            U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+            U256::from(self.curve_factor) * wad,
+        )?)
+    }
+}
+
+// Price rises in a straight line from base_price at zero supply to final_price at max_supply
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct LinearCurve {
+    pub base_price: u64,
+    pub final_price: u64,
+    pub max_supply: u64,
+}
+
+impl LinearCurve {
+    pub const LEN: usize = 8 + 8 + 8;
+}
+
+impl Curve for LinearCurve {
+    fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(self.base_price > 0, XGrowthError::InvalidBondingCurve);
+        require!(self.final_price >= self.base_price, XGrowthError::InvalidBondingCurve);
+        Ok(())
+    }
+
+    // Price = base_price + (final_price - base_price) * supply / max_supply
+    fn calculate_price(&self, supply: u64) -> Result<u64> {
+        let slope = (self.final_price - self.base_price) as u128;
+        let increase = slope * supply as u128 / self.max_supply as u128;
+        math::to_u64(U256::from(self.base_price as u128 + increase))
+    }
+
+    // Reserve = (2 * base_price * max_supply * supply + (final_price - base_price) * supply^2) / (2 * max_supply)
+    fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        let supply = U256::from(supply);
+        let max_supply = U256::from(self.max_supply);
+        let flat = U256::from(2) * U256::from(self.base_price) * max_supply * supply;
+        let rise = U256::from(self.final_price - self.base_price) * supply * supply;
+        let numerator = math::checked_add(flat, rise)?;
+        let denominator = U256::from(2 * PRICE_PRECISION) * max_supply;
+        math::to_u64(math::mul_div_ceil(numerator, U256::one(), denominator)?)
+    }
+
+    // Positive root of (final_price - base_price) * s^2 + 2 * base_price * max_supply * s
+    //     = 2 * max_supply * reserve
+    fn estimate_supply(&self, reserve: u64) -> Result<u64> {
+        let a = U256::from(self.final_price - self.base_price);
+        let b = U256::from(2) * U256::from(self.base_price) * U256::from(self.max_supply);
+        let c = U256::from(2 * PRICE_PRECISION) * U256::from(self.max_supply) * U256::from(reserve);
+        if a.is_zero() {
+            return math::to_u64(c / b);
+        }
+        let discriminant = math::checked_add(
+            math::checked_mul(b, b)?,
+            math::checked_mul(U256::from(4) * a, c)?,
+        )?;
+        math::to_u64((discriminant.integer_sqrt() - b) / (U256::from(2) * a))
+    }
+}
+
+// Price = base_price * e^(growth_rate * supply / max_supply)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ExponentialCurve {
+    pub base_price: u64,
+    pub growth_rate: u64,     // FACTOR_PRECISION = 1.0
+    pub max_supply: u64,
+}
+
+impl ExponentialCurve {
+    pub const LEN: usize = 8 + 8 + 8;
+    pub const MAX_GROWTH_RATE: u64 = 40 * FACTOR_PRECISION;
+
+    // growth_rate * supply / max_supply in WAD, rounded up
+    fn exponent(&self, supply: u64) -> Result<u128> {
+        let exponent = math::mul_div_ceil(
+            U256::from(self.growth_rate) * U256::from(supply),
+            math::wad(),
+            U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+        )?;
+        u128::try_from(exponent).map_err(|_| error!(XGrowthError::MathOverflow))
+    }
+}
+
+impl Curve for ExponentialCurve {
+    fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(self.base_price > 0, XGrowthError::InvalidBondingCurve);
+        require!(
+            self.growth_rate > 0 && self.growth_rate <= Self::MAX_GROWTH_RATE,
+            XGrowthError::InvalidBondingCurve
+        );
+        Ok(())
+    }
+
+    fn calculate_price(&self, supply: u64) -> Result<u64> {
+        let growth = math::exp_wad(self.exponent(supply)?)?;
+        math::to_u64(math::mul_div_floor(U256::from(self.base_price), U256::from(growth), math::wad())?)
+    }
+
+    // Reserve = base_price * max_supply / growth_rate * (e^(growth_rate * supply / max_supply) - 1)
+    fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        let growth = math::exp_wad(self.exponent(supply)?)? - WAD;
+        let numerator = math::checked_mul(
+            U256::from(self.base_price) * U256::from(self.max_supply),
+            U256::from(FACTOR_PRECISION),
+        )?;
+        let denominator = U256::from(self.growth_rate) * U256::from(PRICE_PRECISION) * math::wad();
+        math::to_u64(math::mul_div_ceil(numerator, U256::from(growth), denominator)?)
+    }
+
+    // supply = max_supply / growth_rate * ln(1 + growth_rate * reserve / (base_price * max_supply))
+    fn estimate_supply(&self, reserve: u64) -> Result<u64> {
+        let ratio = math::mul_div_floor(
+            U256::from(reserve) * U256::from(self.growth_rate) * U256::from(PRICE_PRECISION),
+            math::wad(),
+            U256::from(self.base_price) * U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+        )?;
+        let ratio = u128::try_from(ratio).map_err(|_| error!(XGrowthError::MathOverflow))?;
+        let log = math::ln_wad(ratio.checked_add(WAD).ok_or(XGrowthError::MathOverflow)?)?;
+        math::to_u64(math::mul_div_floor(
+            U256::from(log),
+            U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+            U256::from(self.growth_rate) * math::wad(),
+        )?)
+    }
+}
+
+// S-shaped price: starts near base_price, rises fastest around `midpoint` and levels off at max_price
+// Price = base_price + (max_price - base_price) / (1 + e^(-steepness * (supply - midpoint) / max_supply))
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct SigmoidCurve {
+    pub base_price: u64,
+    pub max_price: u64,
+    pub midpoint: u64,        // Supply at which the price is halfway between base and max
+    pub steepness: u64,       // FACTOR_PRECISION = 1.0
+    pub max_supply: u64,
+}
+
+impl SigmoidCurve {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8;
+    pub const MAX_STEEPNESS: u64 = 40 * FACTOR_PRECISION;
+
+    // Newton steps taken by estimate_supply before the exact search takes over
+    const ESTIMATE_ITERATIONS: usize = 4;
+
+    // steepness * (supply - midpoint) / max_supply in signed WAD
+    fn exponent(&self, supply: u64) -> Result<i128> {
+        let distance = (supply as i128) - (self.midpoint as i128);
+        let magnitude = math::mul_div_floor(
+            U256::from(self.steepness) * U256::from(distance.unsigned_abs()),
+            math::wad(),
+            U256::from(self.max_supply) * U256::from(FACTOR_PRECISION),
+        )?;
+        let magnitude = i128::try_from(magnitude).map_err(|_| error!(XGrowthError::MathOverflow))?;
+        Ok(if distance < 0 { -magnitude } else { magnitude })
+    }
+}
+
+impl Curve for SigmoidCurve {
+    fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(self.base_price > 0, XGrowthError::InvalidBondingCurve);
+        require!(self.max_price > self.base_price, XGrowthError::InvalidBondingCurve);
+        require!(self.midpoint <= self.max_supply, XGrowthError::InvalidBondingCurve);
+        require!(
+            self.steepness > 0 && self.steepness <= Self::MAX_STEEPNESS,
+            XGrowthError::InvalidBondingCurve
+        );
+        Ok(())
+    }
+
+    fn calculate_price(&self, supply: u64) -> Result<u64> {
+        let exponent = self.exponent(supply)?;
+        let exp_neg = WAD * WAD / math::exp_wad(exponent.unsigned_abs())?;
+        let sigmoid = if exponent >= 0 {
+            WAD * WAD / (WAD + exp_neg)
+        } else {
+            exp_neg * WAD / (WAD + exp_neg)
+        };
+        let increase = math::mul_div_floor(
+            U256::from(self.max_price - self.base_price),
+            U256::from(sigmoid),
+            math::wad(),
+        )?;
+        math::to_u64(U256::from(self.base_price) + increase)
+    }
+
+    // Reserve = base_price * supply
+    //     + (max_price - base_price) * max_supply / steepness * (softplus(x(supply)) - softplus(x(0)))
+    // where x(s) = steepness * (s - midpoint) / max_supply and softplus(x) = ln(1 + e^x)
+    fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        let start = math::softplus_wad(self.exponent(0)?)?;
+        let end = math::softplus_wad(self.exponent(supply)?)?;
+        let steepness = U256::from(self.steepness);
+
+        let flat = U256::from(self.base_price) * U256::from(supply) * math::wad() * steepness;
+        let rise = math::checked_mul(
+            U256::from(self.max_price - self.base_price) * U256::from(self.max_supply),
+            U256::from(FACTOR_PRECISION) * U256::from(end.saturating_sub(start)),
+        )?;
+        let numerator = math::checked_add(flat, rise)?;
+        let denominator = U256::from(PRICE_PRECISION) * math::wad() * steepness;
+        math::to_u64(math::mul_div_ceil(numerator, U256::one(), denominator)?)
+    }
+
+    // No closed form: Newton's method from the flat-price bound reserve / base_price
+    fn estimate_supply(&self, reserve: u64) -> Result<u64> {
+        let upper = (reserve as u128 * PRICE_PRECISION as u128 / self.base_price as u128)
+            .min(self.max_supply as u128) as u64;
+        let mut supply = upper;
+        for _ in 0..Self::ESTIMATE_ITERATIONS {
+            let error = self.calculate_reserve(supply)? as i128 - reserve as i128;
+            let price = self.calculate_price(supply)?.max(1) as i128;
+            let step = error * PRICE_PRECISION as i128 / price;
+            supply = (supply as i128 - step).clamp(0, upper as i128) as u64;
+        }
+        Ok(supply)
+    }
+}
+
+// Pump-style constant product against virtual reserves:
+// (virtual_usdt_reserve + reserve) * (virtual_token_reserve - supply) = virtual_usdt_reserve * virtual_token_reserve
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct ConstantProductCurve {
+    pub virtual_usdt_reserve: u64,
+    pub virtual_token_reserve: u64,
+    pub max_supply: u64,
+}
+
+impl ConstantProductCurve {
+    pub const LEN: usize = 8 + 8 + 8;
+}
+
+impl Curve for ConstantProductCurve {
+    fn max_supply(&self) -> u64 {
+        self.max_supply
+    }
+
+    fn validate(&self) -> Result<()> {
+        require!(self.virtual_usdt_reserve > 0, XGrowthError::InvalidBondingCurve);
+        require!(
+            self.virtual_token_reserve > self.max_supply,
+            XGrowthError::InvalidBondingCurve
+        );
+        Ok(())
+    }
+
+    // Price = virtual_usdt_reserve * virtual_token_reserve / (virtual_token_reserve - supply)^2
+    fn calculate_price(&self, supply: u64) -> Result<u64> {
+        let tokens_left = U256::from(
+            self.virtual_token_reserve
+                .checked_sub(supply)
+                .ok_or(XGrowthError::MaxSupplyReached)?,
+        );
+        let product = U256::from(self.virtual_usdt_reserve) * U256::from(self.virtual_token_reserve);
+        math::to_u64(math::mul_div_floor(
+            product,
+            U256::from(PRICE_PRECISION),
+            tokens_left * tokens_left,
+        )?)
+    }
+
+    // Reserve = virtual_usdt_reserve * supply / (virtual_token_reserve - supply)
+    fn calculate_reserve(&self, supply: u64) -> Result<u64> {
+        let tokens_left = self
+            .virtual_token_reserve
+            .checked_sub(supply)
+            .ok_or(XGrowthError::MaxSupplyReached)?;
+        math::to_u64(math::mul_div_ceil(
+            U256::from(self.virtual_usdt_reserve),
+            U256::from(supply),
+            U256::from(tokens_left),
+        )?)
+    }
+
+    // supply = virtual_token_reserve * reserve / (virtual_usdt_reserve + reserve)
+    fn estimate_supply(&self, reserve: u64) -> Result<u64> {
+        math::to_u64(math::mul_div_floor(
+            U256::from(self.virtual_token_reserve),
+            U256::from(reserve),
+            U256::from(self.virtual_usdt_reserve) + U256::from(reserve),
+        )?)
+    }
+}
@@ -19,4 +19,184 @@ pub enum XGrowthError {
     
     #[msg("Invalid bonding curve parameters")]
     InvalidBondingCurve,
+    
+    #[msg("Math overflow")]
+    MathOverflow,
+    
+    #[msg("Unauthorized")]
+    Unauthorized,
+    
+    #[msg("Invalid fee configuration")]
+    InvalidFeeConfig,
+    
+    #[msg("Invalid referrer account")]
+    InvalidReferrer,
+    
+    #[msg("Insufficient fee balance")]
+    InsufficientFeeBalance,
+    
+    #[msg("No fees to claim")]
+    NoFeesToClaim,
+    
+    #[msg("Token account mint does not match the platform USDT mint")]
+    InvalidMint,
+    
+    #[msg("Vault is not owned by its program authority")]
+    InvalidVaultAuthority,
+    
+    #[msg("Previous epoch is not finalized")]
+    PreviousEpochNotFinalized,
+    
+    #[msg("Epoch has not ended yet")]
+    EpochNotEnded,
+    
+    #[msg("Epoch is already finalized")]
+    EpochAlreadyFinalized,
+    
+    #[msg("Epoch is not finalized")]
+    EpochNotFinalized,
+    
+    #[msg("Agent score already recorded for this epoch")]
+    ScoreAlreadyRecorded,
+    
+    #[msg("Agent score not recorded for this epoch")]
+    ScoreNotRecorded,
+    
+    #[msg("Agent rewards for its last recorded epoch are not credited yet")]
+    RewardsNotCredited,
+    
+    #[msg("Not every agent has been recorded for this epoch")]
+    AgentsNotRecorded,
+    
+    #[msg("Agent rewards already credited for this epoch")]
+    RewardsAlreadyCredited,
+    
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    
+    #[msg("Unstake amount exceeds the staked balance")]
+    InsufficientStake,
+    
+    #[msg("Unbonding period has not elapsed")]
+    UnbondingNotComplete,
+    
+    #[msg("No unbonded stake to withdraw")]
+    NothingToWithdraw,
+    
+    #[msg("Invalid unbonding period")]
+    InvalidUnbondingPeriod,
+    
+    #[msg("Invalid merkle distribution")]
+    InvalidDistribution,
+    
+    #[msg("Invalid merkle proof")]
+    InvalidMerkleProof,
+    
+    #[msg("Leaf index is outside the distribution")]
+    InvalidLeafIndex,
+    
+    #[msg("Rewards already claimed for this leaf")]
+    AlreadyClaimed,
+    
+    #[msg("Claim exceeds the distribution total")]
+    DistributionExceeded,
+    
+    #[msg("Reward pool is not funded enough to cover the allocation")]
+    RewardPoolUnderfunded,
+    
+    #[msg("Invalid claim window")]
+    InvalidClaimWindow,
+    
+    #[msg("Rewards are still inside the claim window")]
+    RewardsNotExpired,
+    
+    #[msg("Agent already has the maximum number of reward streams")]
+    TooManyRewardStreams,
+    
+    #[msg("Invalid reward stream schedule")]
+    InvalidStreamSchedule,
+    
+    #[msg("Reward stream accounts are missing or out of order")]
+    InvalidRewardStream,
+    
+    #[msg("Reward stream has ended")]
+    RewardStreamEnded,
+    
+    #[msg("Reward vesting period out of range")]
+    InvalidVestingPeriod,
+    
+    #[msg("Invalid score weights")]
+    InvalidScoreWeights,
+    
+    #[msg("Unsupported metric report version or length")]
+    InvalidMetricReport,
+    
+    #[msg("Agent account already uses the current layout")]
+    AgentAlreadyMigrated,
+    
+    #[msg("Invalid score transforms")]
+    InvalidScoreTransforms,
+    
+    #[msg("Invalid oracle set")]
+    InvalidOracleSet,
+    
+    #[msg("Performance updates need the oracle quorum")]
+    QuorumRequired,
+    
+    #[msg("Oracle already submitted an observation for this agent and epoch")]
+    DuplicateObservation,
+    
+    #[msg("Observation already accepted for this agent and epoch")]
+    ObservationAlreadyAccepted,
+    
+    #[msg("Report attestation does not match an Ed25519 verification of the oracle's signature")]
+    InvalidAttestation,
+    
+    #[msg("Report sequence number is not above the agent's last applied report")]
+    StaleReport,
+    
+    #[msg("Dispute window out of range")]
+    InvalidDisputeWindow,
+    
+    #[msg("Epoch scores can still be disputed")]
+    DisputeWindowOpen,
+    
+    #[msg("Dispute window for this epoch has closed")]
+    DisputeWindowClosed,
+    
+    #[msg("Agent score is disputed")]
+    ScoreDisputed,
+    
+    #[msg("Dispute already resolved")]
+    DisputeAlreadyResolved,
+    
+    #[msg("Attestation is not for the epoch the agent's metrics count towards")]
+    AttestationEpochMismatch,
+    
+    #[msg("Staking needs the user's stake account and the agent's stake vault")]
+    StakeAccountsRequired,
+    
+    #[msg("Stake is still sitting out the epoch it was staked in")]
+    StakeWarmingUp,
+    
+    #[msg("Dispute was not resolved in time and has lapsed")]
+    DisputeLapsed,
+    
+    #[msg("Agent is stored in an older layout; call migrate_agent first")]
+    AgentNotMigrated,
+    
+    #[msg("Platform has already been migrated")]
+    PlatformAlreadyMigrated,
+    
+    #[msg("Receiving the bought tokens needs the user's token account")]
+    UserTokenAccountRequired,
+    
+    #[msg("Reward stream has not ended yet")]
+    RewardStreamActive,
+    
+    #[msg("Nothing in the stream vault is left to sweep")]
+    NothingToSweep,
+    
+    #[msg("Trade is too small to mint any tokens")]
+    TradeTooSmall,
 }
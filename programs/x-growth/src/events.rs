@@ -0,0 +1,323 @@
+use anchor_lang::prelude::*;
+use crate::state::ScoreTransforms;
+
+#[event]
+pub struct TradeFeesCharged {
+    pub agent: Pubkey,
+    pub trader: Pubkey,
+    pub is_buy: bool,
+    pub fee: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer: Option<Pubkey>,
+    pub referrer_fee: u64,
+}
+
+#[event]
+pub struct ScoreWeightsUpdated {
+    pub likes: u64,
+    pub views: u64,
+    pub comments: u64,
+    pub followers: u64,
+    pub effective_epoch: u64,
+}
+
+#[event]
+pub struct ScoreTransformsUpdated {
+    pub score_transforms: ScoreTransforms,
+    pub effective_epoch: u64,
+}
+
+#[event]
+pub struct FeeConfigUpdated {
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+    pub protocol_share_bps: u16,
+    pub creator_share_bps: u16,
+    pub referrer_share_bps: u16,
+    pub max_agent_fee_bps: u16,
+}
+
+#[event]
+pub struct AgentFeesUpdated {
+    pub agent: Pubkey,
+    pub buy_fee_bps: Option<u16>,
+    pub sell_fee_bps: Option<u16>,
+}
+
+#[event]
+pub struct ProtocolFeesWithdrawn {
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_withdrawn: u64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub agent: Pubkey,
+    pub authority: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub total_claimed: u64,
+}
+
+#[event]
+pub struct EpochStarted {
+    pub epoch: u64,
+    pub reward_pool: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct PerformanceUpdated {
+    pub agent: Pubkey,
+    pub version: u8,
+    pub engagement_rate_bps: u64, // daily, after this report
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AttestationRelayed {
+    pub agent: Pubkey,
+    pub oracle: Pubkey,
+    pub relayer: Pubkey,
+    pub epoch: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct OracleSetUpdated {
+    pub oracles: Vec<Pubkey>,
+    pub threshold: u8,
+    pub outlier_tolerance_bps: u16,
+    pub version: u32,
+}
+
+#[event]
+pub struct ObservationSubmitted {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub oracle: Pubkey,
+    pub submissions: u8,
+    pub threshold: u8,
+}
+
+#[event]
+pub struct ObservationAccepted {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub values: Vec<u64>, // per-metric median, as a MetricReport::V2 vector
+    pub submissions: u8,
+}
+
+#[event]
+pub struct ObservationOutlier {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub oracle: Pubkey,
+    pub metric: u8, // index in the MetricReport::V2 vector
+    pub value: u64,
+    pub median: u64,
+}
+
+#[event]
+pub struct AgentMigrated {
+    pub agent: Pubkey,
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[event]
+pub struct PlatformMigrated {
+    pub old_len: u64,
+    pub new_len: u64,
+}
+
+#[event]
+pub struct AgentScoreRecorded {
+    pub epoch: u64,
+    pub agent: Pubkey,
+    pub score: u64,
+    pub total_score: u64,
+}
+
+#[event]
+pub struct EpochFinalized {
+    pub epoch: u64,
+    pub total_score: u64,
+    pub agents_recorded: u64,
+    pub capped_agents: u64, // agents held to share_cap by the share limit
+    pub share_cap: u64,
+    pub dispute_end: i64,
+}
+
+#[event]
+pub struct AgentScoreDisputed {
+    pub epoch: u64,
+    pub agent: Pubkey,
+    pub challenger: Pubkey,
+    pub recorded_score: u64,
+}
+
+#[event]
+pub struct ScoreDisputeResolved {
+    pub epoch: u64,
+    pub agent: Pubkey,
+    pub recorded_score: u64,
+    pub corrected_score: u64,
+    pub credited_score: u64, // the lower of the two
+}
+
+#[event]
+pub struct ScoreDisputeLapsed {
+    pub epoch: u64,
+    pub agent: Pubkey,
+    pub recorded_score: u64,
+}
+
+#[event]
+pub struct AgentRewardsCredited {
+    pub epoch: u64,
+    pub agent: Pubkey,
+    pub score: u64,
+    pub amount: u64,
+    pub vesting_end: i64,
+}
+
+#[event]
+pub struct Staked {
+    pub agent: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct StakeActivated {
+    pub agent: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct Unstaked {
+    pub agent: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+    pub unbonding_end: i64,
+}
+
+#[event]
+pub struct StakeWithdrawn {
+    pub agent: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct UnbondingPeriodUpdated {
+    pub unbonding_period: i64,
+}
+
+#[event]
+pub struct RewardVestingPeriodUpdated {
+    pub reward_vesting_period: i64,
+}
+
+#[event]
+pub struct DisputeConfigUpdated {
+    pub guardian: Option<Pubkey>,
+    pub dispute_window: i64,
+}
+
+#[event]
+pub struct MerkleRootPosted {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub root: [u8; 32],
+    pub total_amount: u64,
+    pub num_leaves: u32,
+}
+
+#[event]
+pub struct MerkleRewardsClaimed {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub user: Pubkey,
+    pub index: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardPoolFunded {
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub total_funded: u64,
+    pub unallocated: u64,
+}
+
+#[event]
+pub struct ClaimWindowUpdated {
+    pub claim_window: i64,
+    pub sweep_to_treasury: bool,
+}
+
+#[event]
+pub struct RewardsExpired {
+    pub user: Pubkey,
+    pub agent: Pubkey,
+    pub amount: u64,
+    pub last_accrual: i64,
+    pub to_treasury: bool,
+}
+
+#[event]
+pub struct RewardStreamCreated {
+    pub agent: Pubkey,
+    pub stream: Pubkey,
+    pub index: u8,
+    pub mint: Pubkey,
+    pub emission_rate: u64,
+    pub end_time: i64,
+}
+
+#[event]
+pub struct RewardStreamFunded {
+    pub stream: Pubkey,
+    pub amount: u64,
+    pub total_funded: u64,
+}
+
+#[event]
+pub struct RewardStreamEnded {
+    pub stream: Pubkey,
+    pub total_emitted: u64,
+    pub refunded: u64,
+}
+
+#[event]
+pub struct RewardStreamSwept {
+    pub stream: Pubkey,
+    pub refunded: u64,
+    pub total_refunded: u64,
+}
+
+#[event]
+pub struct StreamRewardsClaimed {
+    pub stream: Pubkey,
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct RewardsCompounded {
+    pub agent: Pubkey,
+    pub user: Pubkey,
+    pub usdt_amount: u64,
+    pub tokens_out: u64,
+    pub staked: bool,
+}
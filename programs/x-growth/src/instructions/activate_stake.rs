@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use super::{load_reward_streams, update_stake_shares, warmed_up_shares};
+
+#[derive(Accounts)]
+pub struct ActivateStake<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", user_stake.user.as_ref(), agent.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_rewards", user_stake.user.as_ref(), agent.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+}
+
+// Permissionless: start a user's warmed-up stake earning without waiting for their next stake
+// or unstake. Remaining accounts: every reward stream of the agent, writable, in index order.
+pub fn activate_stake<'info>(ctx: Context<'_, '_, 'info, 'info, ActivateStake<'info>>) -> Result<()> {
+    require!(
+        ctx.accounts.user_stake.warmed_up(&ctx.accounts.agent),
+        XGrowthError::StakeWarmingUp
+    );
+    
+    let mut streams = load_reward_streams(&ctx.accounts.agent, ctx.remaining_accounts)?;
+    let agent = &mut ctx.accounts.agent;
+    let user_rewards = &mut ctx.accounts.user_rewards;
+    let shares = warmed_up_shares(agent, &mut ctx.accounts.user_stake, user_rewards)?;
+    update_stake_shares(agent, user_rewards, &mut streams, shares)?;
+    
+    msg!("Activated stake, total staked: {}", agent.total_shares);
+    
+    Ok(())
+}
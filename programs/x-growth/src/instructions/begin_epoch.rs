@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct BeginEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = RewardEpoch::LEN,
+        seeds = [b"epoch", (platform.current_epoch + 1).to_le_bytes().as_ref()],
+        bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    // Anyone can crank the epoch
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn begin_epoch(ctx: Context<BeginEpoch>) -> Result<()> {
+    let platform = &mut ctx.accounts.platform;
+    let epoch = &mut ctx.accounts.epoch;
+    let clock = Clock::get()?;
+    
+    // Epochs run one after another
+    require!(
+        platform.last_finalized_epoch == platform.current_epoch,
+        XGrowthError::PreviousEpochNotFinalized
+    );
+    
+    platform.current_epoch += 1;
+    
+    epoch.epoch = platform.current_epoch;
+    epoch.reward_pool = platform.daily_reward_pool;
+    epoch.start_time = clock.unix_timestamp;
+    epoch.end_time = clock.unix_timestamp + Platform::EPOCH_DURATION;
+    epoch.total_score = 0;
+    epoch.agents_recorded = 0;
+    epoch.finalized = false;
+    epoch.agents_credited = 0;
+    epoch.total_credited = 0;
+    epoch.bump = ctx.bumps.epoch;
+    epoch.score_weights = platform.score_weights;
+    epoch.score_transforms = platform.score_transforms;
+    epoch.top_scores = [0; RewardEpoch::TOP_SCORES];
+    epoch.open_disputes = 0;
+    
+    emit!(EpochStarted {
+        epoch: epoch.epoch,
+        reward_pool: epoch.reward_pool,
+        start_time: epoch.start_time,
+        end_time: epoch.end_time,
+    });
+    
+    msg!("Epoch {} started, reward pool: {}", epoch.epoch, epoch.reward_pool);
+    
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use super::BuyFromCurve;
+
+// Exact-output buy: mints exactly `token_amount` and charges the curve cost.
+// Shares the BuyFromCurve accounts and settlement with buy_from_curve.
+pub fn buy_exact_tokens(
+    ctx: Context<BuyFromCurve>,
+    token_amount: u64,
+    max_usdt_in: u64,
+) -> Result<()> {
+    let has_referrer = ctx.accounts.has_referrer()?;
+    
+    // Calculate USDT owed for the requested tokens
+    let quote = ctx.accounts.agent.quote_buy_exact_tokens(
+        &ctx.accounts.platform.fee_config,
+        token_amount,
+        has_referrer,
+    )?;
+    
+    // Check slippage protection
+    require!(quote.usdt_in <= max_usdt_in, XGrowthError::SlippageExceeded);
+    
+    ctx.accounts.settle(&quote)
+}
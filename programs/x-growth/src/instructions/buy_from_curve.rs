@@ -2,11 +2,13 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Transfer};
 use crate::state::*;
 use crate::errors::*;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct BuyFromCurve<'info> {
     #[account(
         mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
         seeds = [b"agent", agent.agent_id.as_bytes()],
         bump = agent.bump
     )]
@@ -28,27 +30,45 @@ pub struct BuyFromCurve<'info> {
     
     #[account(
         mut,
-        constraint = buyer_usdt_account.mint == platform.usdt_mint
+        constraint = buyer_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
     )]
     pub buyer_usdt_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
         seeds = [b"reserve", agent.key().as_ref()],
-        bump,
-        constraint = reserve_usdt_account.mint == platform.usdt_mint
+        bump = agent.reserve_bump,
+        constraint = reserve_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        token::mint = platform.usdt_mint,
+        token::authority = agent
     )]
     pub reserve_usdt_account: Account<'info, TokenAccount>,
     
     #[account(
+        mut,
         seeds = [b"platform"],
         bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
     
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
     #[account(mut)]
     pub buyer: Signer<'info>,
     
+    // Optional referrer USDT account that receives the referrer fee leg
+    #[account(mut)]
+    pub referrer_usdt_account: Option<Account<'info, TokenAccount>>,
+    
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -59,59 +79,158 @@ pub fn buy_from_curve(
     usdt_amount: u64,
     min_tokens_out: u64,
 ) -> Result<()> {
-    let agent = &mut ctx.accounts.agent;
+    let has_referrer = ctx.accounts.has_referrer()?;
     
     // Calculate tokens to mint based on bonding curve
-    let tokens_out = agent.bonding_curve.calculate_tokens_out(
+    let quote = ctx.accounts.agent.quote_buy(
+        &ctx.accounts.platform.fee_config,
         usdt_amount,
-        agent.circulating_supply
-    );
+        has_referrer,
+    )?;
     
     // Check slippage protection
-    require!(tokens_out >= min_tokens_out, XGrowthError::SlippageExceeded);
-    
-    // Check max supply
-    require!(
-        agent.circulating_supply + tokens_out <= agent.bonding_curve.max_supply,
-        XGrowthError::MaxSupplyReached
-    );
-    
-    // Transfer USDT from buyer to reserve
-    let transfer_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.buyer_usdt_account.to_account_info(),
-            to: ctx.accounts.reserve_usdt_account.to_account_info(),
-            authority: ctx.accounts.buyer.to_account_info(),
-        },
-    );
-    token::transfer(transfer_ctx, usdt_amount)?;
-    
-    // Mint tokens to buyer
-    let agent_id = agent.agent_id.clone();
-    let seeds = &[
-        b"agent",
-        agent_id.as_bytes(),
-        &[agent.bump],
-    ];
-    let signer = &[&seeds[..]];
-    
-    let mint_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.buyer_token_account.to_account_info(),
-            authority: agent.to_account_info(),
-        },
-        signer,
-    );
-    token::mint_to(mint_ctx, tokens_out)?;
+    require!(quote.tokens_out >= min_tokens_out, XGrowthError::SlippageExceeded);
     
-    // Update agent state
-    agent.circulating_supply += tokens_out;
-    agent.reserve_balance += usdt_amount;
-    
-    msg!("Bought {} tokens for {} USDT", tokens_out, usdt_amount);
-    
-    Ok(())
+    ctx.accounts.settle(&quote)
+}
+
+// A referrer account must hold the platform USDT mint and belong to someone other than the trader
+pub fn validate_referrer(
+    referrer_usdt_account: &Option<Account<TokenAccount>>,
+    platform: &Platform,
+    trader: Pubkey,
+) -> Result<bool> {
+    match referrer_usdt_account {
+        Some(account) => {
+            require!(
+                account.mint == platform.usdt_mint && account.owner != trader,
+                XGrowthError::InvalidReferrer
+            );
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+impl<'info> BuyFromCurve<'info> {
+    pub fn has_referrer(&self) -> Result<bool> {
+        validate_referrer(&self.referrer_usdt_account, &self.platform, self.buyer.key())
+    }
+    
+    // Move funds and update the agent for a quoted buy
+    pub fn settle(&mut self, quote: &TradeQuote) -> Result<()> {
+        BuySettlement {
+            agent: &mut self.agent,
+            platform: &mut self.platform,
+            token_mint: &self.token_mint,
+            source: self.buyer_usdt_account.to_account_info(),
+            payer: self.buyer.to_account_info(),
+            payer_seeds: &[],
+            reserve: self.reserve_usdt_account.to_account_info(),
+            treasury: self.treasury.to_account_info(),
+            referrer: self.referrer_usdt_account.as_ref().map(|account| account.to_account_info()),
+            destination: self.buyer_token_account.to_account_info(),
+            trader: self.buyer.key(),
+            token_program: self.token_program.to_account_info(),
+        }
+        .settle(quote)?;
+        
+        msg!("Bought {} tokens for {} USDT", quote.tokens_out, quote.usdt_in);
+        
+        Ok(())
+    }
+}
+
+// Where a quoted buy takes its USDT from and mints its tokens to. `payer` is the authority of
+// `source`, signing with `payer_seeds` when it is a program address. buy_from_curve,
+// buy_exact_tokens and compound_rewards all settle through here.
+pub struct BuySettlement<'a, 'info> {
+    pub agent: &'a mut Account<'info, Agent>,
+    pub platform: &'a mut Account<'info, Platform>,
+    pub token_mint: &'a Account<'info, Mint>,
+    pub source: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub payer_seeds: &'a [&'a [&'a [u8]]],
+    pub reserve: AccountInfo<'info>,
+    pub treasury: AccountInfo<'info>,
+    pub referrer: Option<AccountInfo<'info>>,
+    pub destination: AccountInfo<'info>,
+    pub trader: Pubkey,
+    pub token_program: AccountInfo<'info>,
+}
+
+impl<'a, 'info> BuySettlement<'a, 'info> {
+    pub fn settle(self, quote: &TradeQuote) -> Result<()> {
+        // A buy too small to mint anything would only pay fees, whatever min_tokens_out allows
+        require!(quote.tokens_out > 0, XGrowthError::TradeTooSmall);
+        
+        let agent = self.agent;
+        let transfer = |to: &AccountInfo<'info>, amount: u64| {
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.token_program.clone(),
+                Transfer {
+                    from: self.source.clone(),
+                    to: to.clone(),
+                    authority: self.payer.clone(),
+                },
+                self.payer_seeds,
+            );
+            token::transfer(transfer_ctx, amount)
+        };
+        
+        // USDT into the reserve, net of fees
+        transfer(&self.reserve, quote.usdt_in - quote.fee)?;
+        
+        // Pay the protocol and creator legs into the treasury
+        let treasury_fee = quote.protocol_fee + quote.creator_fee;
+        if treasury_fee > 0 {
+            transfer(&self.treasury, treasury_fee)?;
+        }
+        
+        // Pay the referrer leg straight from the source
+        if let Some(referrer) = &self.referrer {
+            if quote.referrer_fee > 0 {
+                transfer(referrer, quote.referrer_fee)?;
+            }
+        }
+        
+        // Mint the tokens, signed by the agent
+        let agent_id = agent.agent_id.clone();
+        let seeds = &[
+            b"agent",
+            agent_id.as_bytes(),
+            &[agent.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let mint_ctx = CpiContext::new_with_signer(
+            self.token_program.clone(),
+            MintTo {
+                mint: self.token_mint.to_account_info(),
+                to: self.destination.clone(),
+                authority: agent.to_account_info(),
+            },
+            signer,
+        );
+        token::mint_to(mint_ctx, quote.tokens_out)?;
+        
+        // Update agent state
+        agent.circulating_supply += quote.tokens_out;
+        agent.reserve_balance += quote.usdt_in - quote.fee;
+        agent.referrer_fees_paid += quote.referrer_fee;
+        self.platform.record_fees(agent, quote.protocol_fee, quote.creator_fee)?;
+        
+        emit!(TradeFeesCharged {
+            agent: agent.key(),
+            trader: self.trader,
+            is_buy: true,
+            fee: quote.fee,
+            protocol_fee: quote.protocol_fee,
+            creator_fee: quote.creator_fee,
+            referrer: self.referrer.as_ref().map(|account| account.key()),
+            referrer_fee: quote.referrer_fee,
+        });
+        
+        Ok(())
+    }
 }
@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump,
+        constraint = authority.key() == agent.authority @ XGrowthError::Unauthorized
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = creator_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
+    )]
+    pub creator_usdt_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let amount = ctx.accounts.agent.creator_fee_balance;
+    require!(amount > 0, XGrowthError::NoFeesToClaim);
+    
+    // Transfer from the treasury, signed by the platform PDA
+    let seeds: &[&[u8]] = &[b"platform", &[ctx.accounts.platform.bump]];
+    let signer = &[seeds];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: ctx.accounts.creator_usdt_account.to_account_info(),
+            authority: ctx.accounts.platform.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+    
+    let agent = &mut ctx.accounts.agent;
+    agent.creator_fee_balance = 0;
+    agent.creator_fees_claimed = agent.creator_fees_claimed
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    let platform = &mut ctx.accounts.platform;
+    platform.total_creator_fees_claimed = platform.total_creator_fees_claimed
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    emit!(CreatorFeesClaimed {
+        agent: agent.key(),
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.creator_usdt_account.key(),
+        amount,
+        total_claimed: agent.creator_fees_claimed,
+    });
+    
+    msg!("Claimed {} USDT in creator fees for {}", amount, agent.agent_id);
+    
+    Ok(())
+}
@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct ClaimRewards<'info> {
     #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
         seeds = [b"agent", agent.agent_id.as_bytes()],
         bump = agent.bump
     )]
@@ -19,18 +23,23 @@ pub struct ClaimRewards<'info> {
     
     #[account(
         mut,
-        constraint = user_usdt_account.mint == platform.usdt_mint
+        constraint = user_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
     )]
     pub user_usdt_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
         seeds = [b"reward_pool"],
-        bump
+        bump = platform.reward_pool_bump,
+        constraint = reward_pool.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = reward_pool.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
     )]
     pub reward_pool: Account<'info, TokenAccount>,
     
     #[account(
+        mut,
         seeds = [b"platform"],
         bump = platform.bump
     )]
@@ -40,34 +49,129 @@ pub struct ClaimRewards<'info> {
     pub token_program: Program<'info, Token>,
 }
 
-pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+// Remaining accounts: (stream, stream vault, destination token account) triples for the
+// reward streams to claim from, in any order
+pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
     let user_rewards = &mut ctx.accounts.user_rewards;
     let clock = Clock::get()?;
     
-    let amount = user_rewards.pending_rewards;
-    require!(amount > 0, crate::errors::XGrowthError::NoRewardsToClaim);
+    // Pick up rewards vested and accrued since the user's last stake change
+    ctx.accounts.agent.release_vested_rewards(clock.unix_timestamp)?;
+    user_rewards.settle(&ctx.accounts.agent)?;
     
-    // Transfer USDT rewards
-    let seeds = &[b"platform", &[ctx.accounts.platform.bump]];
-    let signer = &[&seeds[..]];
+    let amount = user_rewards.pending_rewards;
+    if amount > 0 {
+        // Transfer USDT rewards, signed by the platform PDA that owns the pool
+        let seeds: &[&[u8]] = &[b"platform", &[ctx.accounts.platform.bump]];
+        let signer = &[seeds];
+        
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_pool.to_account_info(),
+                to: ctx.accounts.user_usdt_account.to_account_info(),
+                authority: ctx.accounts.platform.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+        
+        // Update user rewards state
+        user_rewards.claimed_rewards += amount;
+        user_rewards.pending_rewards = 0;
+        ctx.accounts.platform.record_reward_claim(amount)?;
+        
+        msg!("Claimed {} USDT in rewards", amount);
+    }
     
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.reward_pool.to_account_info(),
-            to: ctx.accounts.user_usdt_account.to_account_info(),
-            authority: ctx.accounts.platform.to_account_info(),
-        },
-        signer,
-    );
-    token::transfer(transfer_ctx, amount)?;
+    let streams_paid = claim_stream_rewards(
+        &ctx.accounts.agent,
+        user_rewards,
+        &ctx.accounts.token_program,
+        ctx.remaining_accounts,
+        clock.unix_timestamp,
+    )?;
+    require!(amount > 0 || streams_paid > 0, XGrowthError::NoRewardsToClaim);
     
-    // Update user rewards state
-    user_rewards.claimed_rewards += amount;
-    user_rewards.pending_rewards = 0;
     user_rewards.last_claim = clock.unix_timestamp;
     
-    msg!("Claimed {} USDT in rewards", amount);
-    
     Ok(())
 }
+
+// Pay out the user's pending balance in each stream passed as a
+// (stream, stream vault, destination) triple. Returns how many streams paid.
+fn claim_stream_rewards<'info>(
+    agent: &Account<'info, Agent>,
+    user_rewards: &mut UserRewards,
+    token_program: &Program<'info, Token>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    now: i64,
+) -> Result<u32> {
+    let triples = remaining_accounts.chunks_exact(3);
+    require!(triples.remainder().is_empty(), XGrowthError::InvalidRewardStream);
+    
+    let mut streams_paid = 0;
+    for accounts in triples {
+        require!(accounts[0].is_writable, XGrowthError::InvalidRewardStream);
+        let mut stream = Account::<RewardStream>::try_from(&accounts[0])?;
+        require_keys_eq!(stream.agent, agent.key(), XGrowthError::InvalidRewardStream);
+        
+        let stream_vault = Account::<TokenAccount>::try_from(&accounts[1])?;
+        let vault_key = Pubkey::create_program_address(
+            &[b"stream_vault", stream.key().as_ref(), &[stream.vault_bump]],
+            &crate::ID,
+        )
+        .map_err(|_| error!(XGrowthError::InvalidRewardStream))?;
+        require_keys_eq!(stream_vault.key(), vault_key, XGrowthError::InvalidRewardStream);
+        
+        let destination = Account::<TokenAccount>::try_from(&accounts[2])?;
+        require_keys_eq!(destination.mint, stream.mint, XGrowthError::InvalidMint);
+        
+        stream.update(agent.total_shares, now)?;
+        user_rewards.settle_stream(&mut stream)?;
+        
+        let position = &mut user_rewards.streams[stream.index as usize];
+        let amount = position.pending;
+        if amount > 0 {
+            let agent_key = stream.agent;
+            let seeds = &[
+                b"reward_stream",
+                agent_key.as_ref(),
+                &[stream.index],
+                &[stream.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let transfer_ctx = CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                Transfer {
+                    from: stream_vault.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: stream.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, amount)?;
+            
+            position.pending = 0;
+            position.claimed = position.claimed
+                .checked_add(amount)
+                .ok_or(XGrowthError::MathOverflow)?;
+            stream.total_claimed = stream.total_claimed
+                .checked_add(amount)
+                .ok_or(XGrowthError::MathOverflow)?;
+            streams_paid += 1;
+            
+            emit!(StreamRewardsClaimed {
+                stream: stream.key(),
+                user: user_rewards.user,
+                mint: stream.mint,
+                amount,
+            });
+            
+            msg!("Claimed {} from reward stream {}", amount, stream.index);
+        }
+        stream.exit(&crate::ID)?;
+    }
+    Ok(streams_paid)
+}
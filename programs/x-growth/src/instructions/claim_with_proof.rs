@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::merkle;
+
+#[derive(Accounts)]
+pub struct ClaimWithProof<'info> {
+    #[account(
+        mut,
+        seeds = [b"distribution", distribution.agent.as_ref(), distribution.epoch.to_le_bytes().as_ref()],
+        bump = distribution.bump
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    
+    #[account(
+        mut,
+        constraint = user_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
+    )]
+    pub user_usdt_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = platform.reward_pool_bump,
+        constraint = reward_pool.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = reward_pool.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Claim the signer's (index, amount) leaf of a posted merkle distribution
+pub fn claim_with_proof(
+    ctx: Context<ClaimWithProof>,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    let distribution = &mut ctx.accounts.distribution;
+    let user = ctx.accounts.user.key();
+    
+    require!(index < distribution.num_leaves, XGrowthError::InvalidLeafIndex);
+    require!(!distribution.is_claimed(index), XGrowthError::AlreadyClaimed);
+    
+    let leaf = merkle::leaf_hash(index, &user, amount);
+    require!(
+        merkle::verify(&proof, &distribution.root, leaf),
+        XGrowthError::InvalidMerkleProof
+    );
+    
+    // A bad root must not pay out more than it announced
+    let total_claimed = distribution.total_claimed
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    require!(total_claimed <= distribution.total_amount, XGrowthError::DistributionExceeded);
+    
+    distribution.set_claimed(index);
+    distribution.total_claimed = total_claimed;
+    
+    // Transfer USDT rewards, signed by the platform PDA that owns the pool
+    let seeds: &[&[u8]] = &[b"platform", &[ctx.accounts.platform.bump]];
+    let signer = &[seeds];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.reward_pool.to_account_info(),
+            to: ctx.accounts.user_usdt_account.to_account_info(),
+            authority: ctx.accounts.platform.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+    ctx.accounts.platform.record_reward_claim(amount)?;
+    
+    emit!(MerkleRewardsClaimed {
+        agent: distribution.agent,
+        epoch: distribution.epoch,
+        user,
+        index,
+        amount,
+    });
+    
+    msg!("Claimed {} USDT with merkle proof for epoch {}", amount, distribution.epoch);
+    
+    Ok(())
+}
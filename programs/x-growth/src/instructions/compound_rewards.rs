@@ -0,0 +1,179 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use super::{add_stake, load_reward_streams, BuySettlement};
+
+#[derive(Accounts)]
+pub struct CompoundRewards<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        address = agent.token_mint
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_rewards", user.key().as_ref(), agent.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = platform.reward_pool_bump,
+        constraint = reward_pool.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = reward_pool.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"reserve", agent.key().as_ref()],
+        bump = agent.reserve_bump,
+        constraint = reserve_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        token::mint = platform.usdt_mint,
+        token::authority = agent
+    )]
+    pub reserve_usdt_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    // Receives the bought tokens, only needed when not staking
+    #[account(
+        mut,
+        token::mint = token_mint,
+        token::authority = user
+    )]
+    pub user_token_account: Option<Account<'info, TokenAccount>>,
+    
+    // The stake vault and the user's stake, only needed when staking
+    #[account(
+        mut,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump = agent.stake_vault_bump,
+        constraint = stake_vault.owner == agent.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = agent.token_mint,
+        token::authority = agent
+    )]
+    pub stake_vault: Option<Account<'info, TokenAccount>>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", user.key().as_ref(), agent.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Option<Account<'info, UserStake>>,
+    
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Buy agent tokens on the curve with the user's pending USDT rewards, optionally staking them.
+// Remaining accounts when staking: every reward stream of the agent, writable, in index order.
+pub fn compound_rewards<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompoundRewards<'info>>,
+    min_tokens_out: u64,
+    stake: bool,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let agent = &mut ctx.accounts.agent;
+    let user_rewards = &mut ctx.accounts.user_rewards;
+    
+    // Pick up rewards vested and accrued since the user's last stake change
+    agent.release_vested_rewards(clock.unix_timestamp)?;
+    user_rewards.settle(agent)?;
+    
+    let usdt_amount = user_rewards.pending_rewards;
+    require!(usdt_amount > 0, XGrowthError::NoRewardsToClaim);
+    
+    // Same pricing and slippage bound as buy_from_curve, without a referrer
+    let quote = agent.quote_buy(&ctx.accounts.platform.fee_config, usdt_amount, false)?;
+    require!(quote.tokens_out >= min_tokens_out, XGrowthError::SlippageExceeded);
+    
+    // Mint straight into the stake vault when staking, otherwise to the user
+    let destination = match (stake, &ctx.accounts.stake_vault, &ctx.accounts.user_stake) {
+        (false, _, _) => ctx.accounts.user_token_account
+            .as_ref()
+            .ok_or(XGrowthError::UserTokenAccountRequired)?
+            .to_account_info(),
+        (true, Some(stake_vault), Some(_)) => stake_vault.to_account_info(),
+        (true, _, _) => return err!(XGrowthError::StakeAccountsRequired),
+    };
+    
+    // Pay the curve from the reward pool, signed by the platform PDA that owns it
+    let platform_info = ctx.accounts.platform.to_account_info();
+    let platform_seeds: &[&[u8]] = &[b"platform", &[ctx.accounts.platform.bump]];
+    BuySettlement {
+        agent: &mut *agent,
+        platform: &mut ctx.accounts.platform,
+        token_mint: &ctx.accounts.token_mint,
+        source: ctx.accounts.reward_pool.to_account_info(),
+        payer: platform_info,
+        payer_seeds: &[platform_seeds],
+        reserve: ctx.accounts.reserve_usdt_account.to_account_info(),
+        treasury: ctx.accounts.treasury.to_account_info(),
+        referrer: None,
+        destination,
+        trader: ctx.accounts.user.key(),
+        token_program: ctx.accounts.token_program.to_account_info(),
+    }
+    .settle(&quote)?;
+    let platform = &mut ctx.accounts.platform;
+    
+    // The pending rewards are paid out, into the curve
+    user_rewards.claimed_rewards += usdt_amount;
+    user_rewards.pending_rewards = 0;
+    user_rewards.last_claim = clock.unix_timestamp;
+    platform.record_reward_claim(usdt_amount)?;
+    
+    // Staked like any other stake, sitting out the current epoch unless it is already credited
+    if stake {
+        let mut streams = load_reward_streams(agent, ctx.remaining_accounts)?;
+        let user_stake = ctx.accounts.user_stake.as_mut().ok_or(XGrowthError::StakeAccountsRequired)?;
+        if user_stake.amount == 0 {
+            user_stake.start_time = clock.unix_timestamp;
+        }
+        add_stake(agent, user_stake, user_rewards, &mut streams, quote.tokens_out, platform.current_epoch)?;
+    }
+    
+    emit!(RewardsCompounded {
+        agent: agent.key(),
+        user: ctx.accounts.user.key(),
+        usdt_amount,
+        tokens_out: quote.tokens_out,
+        staked: stake,
+    });
+    
+    msg!("Compounded {} USDT of rewards into {} tokens", usdt_amount, quote.tokens_out);
+    
+    Ok(())
+}
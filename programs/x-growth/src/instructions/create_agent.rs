@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token};
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use crate::state::*;
+use crate::curve::BondingCurveParams;
 
 #[derive(Accounts)]
 #[instruction(agent_id: String)]
@@ -31,6 +32,31 @@ pub struct CreateAgent<'info> {
     )]
     pub token_mint: Account<'info, Mint>,
     
+    #[account(address = platform.usdt_mint)]
+    pub usdt_mint: Account<'info, Mint>,
+    
+    // USDT reserve backing the curve, owned by the agent PDA
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdt_mint,
+        token::authority = agent,
+        seeds = [b"reserve", agent.key().as_ref()],
+        bump
+    )]
+    pub reserve_usdt_account: Account<'info, TokenAccount>,
+    
+    // Vault for staked agent tokens, owned by the agent PDA
+    #[account(
+        init,
+        payer = authority,
+        token::mint = token_mint,
+        token::authority = agent,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -48,6 +74,8 @@ pub fn create_agent(
     initial_supply: u64,
     bonding_curve_params: BondingCurveParams,
 ) -> Result<()> {
+    bonding_curve_params.validate()?;
+    
     let agent = &mut ctx.accounts.agent;
     let platform = &mut ctx.accounts.platform;
     
@@ -61,9 +89,29 @@ pub fn create_agent(
     agent.total_supply = initial_supply;
     agent.circulating_supply = 0;
     agent.reserve_balance = 0;
+    agent.reserve_bump = ctx.bumps.reserve_usdt_account;
+    agent.fee_override = None;
+    agent.protocol_fees_accrued = 0;
+    agent.creator_fees_accrued = 0;
+    agent.referrer_fees_paid = 0;
+    agent.creator_fee_balance = 0;
+    agent.creator_fees_claimed = 0;
     agent.performance = PerformanceMetrics::default();
     agent.total_rewards_earned = 0;
     agent.last_reward_distribution = Clock::get()?.unix_timestamp;
+    agent.epoch_score = 0;
+    agent.last_recorded_epoch = 0;
+    agent.last_credited_epoch = 0;
+    agent.acc_reward_per_token = 0;
+    agent.total_shares = 0;
+    agent.undistributed_rewards = 0;
+    agent.stake_vault_bump = ctx.bumps.stake_vault;
+    agent.reward_stream_count = 0;
+    agent.reward_rate = 0;
+    agent.reward_vesting_start = 0;
+    agent.reward_vesting_end = 0;
+    agent.last_vesting_update = 0;
+    agent.unvested_rewards = 0;
     agent.bump = ctx.bumps.agent;
     
     platform.total_agents += 1;
@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct CreateRewardStream<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump,
+        constraint = authority.key() == agent.authority @ XGrowthError::Unauthorized
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = RewardStream::LEN,
+        seeds = [b"reward_stream", agent.key().as_ref(), &[agent.reward_stream_count]],
+        bump
+    )]
+    pub stream: Account<'info, RewardStream>,
+    
+    // Vault for the stream's reward tokens, owned by the stream PDA
+    #[account(
+        init,
+        payer = authority,
+        token::mint = reward_mint,
+        token::authority = stream,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump
+    )]
+    pub stream_vault: Account<'info, TokenAccount>,
+    
+    pub reward_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Open a stream emitting `emission_rate` reward tokens per second to stakers until `end_time`
+pub fn create_reward_stream(
+    ctx: Context<CreateRewardStream>,
+    emission_rate: u64,
+    end_time: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    let agent = &mut ctx.accounts.agent;
+    require!(
+        (agent.reward_stream_count as usize) < RewardStream::MAX_PER_AGENT,
+        XGrowthError::TooManyRewardStreams
+    );
+    require!(
+        emission_rate > 0 && end_time > clock.unix_timestamp,
+        XGrowthError::InvalidStreamSchedule
+    );
+    
+    let stream = &mut ctx.accounts.stream;
+    stream.agent = agent.key();
+    stream.index = agent.reward_stream_count;
+    stream.mint = ctx.accounts.reward_mint.key();
+    stream.vault_bump = ctx.bumps.stream_vault;
+    stream.emission_rate = emission_rate;
+    stream.start_time = clock.unix_timestamp;
+    stream.end_time = end_time;
+    stream.last_update_time = clock.unix_timestamp;
+    stream.acc_reward_per_share = 0;
+    stream.total_funded = 0;
+    stream.total_emitted = 0;
+    stream.total_settled = 0;
+    stream.total_claimed = 0;
+    stream.total_refunded = 0;
+    stream.ended = false;
+    stream.bump = ctx.bumps.stream;
+    
+    agent.reward_stream_count += 1;
+    
+    emit!(RewardStreamCreated {
+        agent: agent.key(),
+        stream: stream.key(),
+        index: stream.index,
+        mint: stream.mint,
+        emission_rate,
+        end_time,
+    });
+    
+    msg!("Reward stream {} created for {}", stream.index, agent.agent_id);
+    
+    Ok(())
+}
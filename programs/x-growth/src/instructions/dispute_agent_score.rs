@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct DisputeAgentScore<'info> {
+    #[account(
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        init,
+        payer = challenger,
+        space = ScoreDispute::LEN,
+        seeds = [b"dispute", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: Account<'info, ScoreDispute>,
+    
+    #[account(
+        mut,
+        constraint = platform.can_dispute(&challenger.key()) @ XGrowthError::Unauthorized
+    )]
+    pub challenger: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Flag an agent's recorded score during the epoch's dispute window (authority or guardian).
+// The agent's share is frozen until resolve_dispute posts corrected metrics, at the latest
+// DISPUTE_RESOLUTION_PERIOD after the window.
+pub fn dispute_agent_score(ctx: Context<DisputeAgentScore>) -> Result<()> {
+    let agent = &ctx.accounts.agent;
+    let epoch = &mut ctx.accounts.epoch;
+    let clock = Clock::get()?;
+    
+    require!(epoch.finalized, XGrowthError::EpochNotFinalized);
+    require!(clock.unix_timestamp < epoch.dispute_end, XGrowthError::DisputeWindowClosed);
+    require!(agent.last_recorded_epoch == epoch.epoch, XGrowthError::ScoreNotRecorded);
+    
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.agent = agent.key();
+    dispute.epoch = epoch.epoch;
+    dispute.challenger = ctx.accounts.challenger.key();
+    dispute.opened_at = clock.unix_timestamp;
+    dispute.recorded_score = agent.epoch_score;
+    dispute.resolved = false;
+    dispute.resolve_by = epoch.dispute_end + Platform::DISPUTE_RESOLUTION_PERIOD;
+    dispute.bump = ctx.bumps.dispute;
+    
+    epoch.open_disputes += 1;
+    
+    emit!(AgentScoreDisputed {
+        epoch: epoch.epoch,
+        agent: agent.key(),
+        challenger: dispute.challenger,
+        recorded_score: dispute.recorded_score,
+    });
+    
+    msg!("Epoch {} - score of {} disputed", epoch.epoch, agent.agent_id);
+    
+    Ok(())
+}
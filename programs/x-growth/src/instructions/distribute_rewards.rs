@@ -1,40 +1,101 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct DistributeRewards<'info> {
     #[account(
         mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
         seeds = [b"agent", agent.agent_id.as_bytes()],
         bump = agent.bump
     )]
     pub agent: Account<'info, Agent>,
     
     #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    #[account(
+        mut,
         seeds = [b"platform"],
         bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
+    
+    /// CHECK: the agent's ScoreDispute for the epoch; empty unless its score was disputed
+    #[account(
+        seeds = [b"dispute", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: AccountInfo<'info>,
 }
 
+// Crank step 3, once per agent after the dispute window, or once its own dispute is resolved:
+// credit daily_reward_pool * score / total_score, within the epoch's share limit, vesting to
+// stakers over the platform's reward_vesting_period
 pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
     let agent = &mut ctx.accounts.agent;
-    let platform = &ctx.accounts.platform;
+    let epoch = &mut ctx.accounts.epoch;
     let clock = Clock::get()?;
     
-    // Calculate performance score
-    let score = agent.performance.calculate_score();
+    require!(epoch.finalized, XGrowthError::EpochNotFinalized);
+    require!(agent.last_recorded_epoch == epoch.epoch, XGrowthError::ScoreNotRecorded);
+    require!(agent.last_credited_epoch < epoch.epoch, XGrowthError::RewardsAlreadyCredited);
     
-    // This would be called for all agents to calculate total score
-    // Then distribute proportionally from the 200 USDT daily pool
-    // For now, we just track the score
+    let amount = final_reward_share(epoch, agent, &ctx.accounts.dispute, clock.unix_timestamp)?;
     
+    agent.total_rewards_earned = agent.total_rewards_earned
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    agent.last_credited_epoch = epoch.epoch;
+    agent.schedule_rewards(amount, ctx.accounts.platform.reward_vesting_period, clock.unix_timestamp)?;
     agent.last_reward_distribution = clock.unix_timestamp;
     
-    // Reset daily metrics for next day
-    agent.performance.reset_daily_metrics();
+    let dust = epoch.credit(amount)?;
+    ctx.accounts.platform.release_rewards(dust)?;
+    
+    emit!(AgentRewardsCredited {
+        epoch: epoch.epoch,
+        agent: agent.key(),
+        score: agent.epoch_score,
+        amount,
+        vesting_end: agent.reward_vesting_end,
+    });
     
-    msg!("Rewards distributed - Performance score: {}", score);
+    msg!("Rewards distributed - Performance score: {}, credited: {}", agent.epoch_score, amount);
     
     Ok(())
 }
+
+// The agent's credit for the epoch, once its score can no longer change. Scores are final when
+// the dispute window closes; only an agent whose dispute was opened and resolved can be credited
+// before that. A disputed score waits for its correction, or until the dispute lapses to the
+// recorded score. The share limit and the other agents' shares were fixed on the recorded score,
+// so a correction can only lower the share that score was given.
+pub fn final_reward_share(epoch: &mut RewardEpoch, agent: &Agent, dispute: &AccountInfo, now: i64) -> Result<u64> {
+    let share = epoch.reward_share(agent.epoch_score)?;
+    if dispute.owner != &crate::ID || dispute.data_is_empty() {
+        require!(now >= epoch.dispute_end, XGrowthError::DisputeWindowOpen);
+        return Ok(share);
+    }
+    let dispute = ScoreDispute::try_deserialize(&mut &dispute.try_borrow_data()?[..])?;
+    if dispute.resolved {
+        return Ok(share.min(epoch.reward_share(dispute.recorded_score)?));
+    }
+    require!(now >= epoch.dispute_end, XGrowthError::DisputeWindowOpen);
+    require!(dispute.lapsed(now), XGrowthError::ScoreDisputed);
+    epoch.open_disputes -= 1;
+    
+    emit!(ScoreDisputeLapsed {
+        epoch: epoch.epoch,
+        agent: dispute.agent,
+        recorded_score: dispute.recorded_score,
+    });
+    
+    Ok(share)
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct EndRewardStream<'info> {
+    #[account(
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump,
+        constraint = authority.key() == agent.authority @ XGrowthError::Unauthorized
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_stream", agent.key().as_ref(), &[stream.index]],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, RewardStream>,
+    
+    #[account(
+        mut,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump = stream.vault_bump
+    )]
+    pub stream_vault: Account<'info, TokenAccount>,
+    
+    // Receives the funds the stream has not emitted yet
+    #[account(
+        mut,
+        constraint = creator_token_account.mint == stream.mint @ XGrowthError::InvalidMint
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> EndRewardStream<'info> {
+    // Send the creator what is in the vault beyond what stakers can still claim.
+    // Returns the amount refunded.
+    pub fn refund_unowed(&mut self) -> Result<u64> {
+        let stream = &mut self.stream;
+        let refund = self.stream_vault.amount.saturating_sub(stream.owed(self.agent.total_shares));
+        if refund > 0 {
+            let agent_key = stream.agent;
+            let seeds = &[
+                b"reward_stream",
+                agent_key.as_ref(),
+                &[stream.index],
+                &[stream.bump],
+            ];
+            let signer = &[&seeds[..]];
+            
+            let transfer_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.stream_vault.to_account_info(),
+                    to: self.creator_token_account.to_account_info(),
+                    authority: stream.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(transfer_ctx, refund)?;
+        }
+        stream.total_refunded = stream.total_refunded
+            .checked_add(refund)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(refund)
+    }
+}
+
+// Stop a stream now and refund what stakers cannot claim (agent creator only): what it has not
+// emitted, and the accumulator's rounding dust if nobody is staked. Rewards already emitted stay
+// claimable; dust left behind for stakers can be swept with sweep_reward_stream once they leave.
+pub fn end_reward_stream(ctx: Context<EndRewardStream>) -> Result<()> {
+    let clock = Clock::get()?;
+    let stream = &mut ctx.accounts.stream;
+    require!(!stream.ended, XGrowthError::RewardStreamEnded);
+    
+    stream.update(ctx.accounts.agent.total_shares, clock.unix_timestamp)?;
+    stream.end_time = stream.end_time.min(clock.unix_timestamp);
+    stream.ended = true;
+    
+    let refund = ctx.accounts.refund_unowed()?;
+    let stream = &ctx.accounts.stream;
+    
+    emit!(RewardStreamEnded {
+        stream: stream.key(),
+        total_emitted: stream.total_emitted,
+        refunded: refund,
+    });
+    
+    msg!("Reward stream {} ended, refunded {}", stream.index, refund);
+    
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct FinalizeEpoch<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+}
+
+// Crank step 2: lock the total score once every agent is recorded, allocate the pool,
+// settle the per-agent share limit and open the dispute window
+pub fn finalize_epoch(ctx: Context<FinalizeEpoch>) -> Result<()> {
+    let platform = &mut ctx.accounts.platform;
+    let epoch = &mut ctx.accounts.epoch;
+    let clock = Clock::get()?;
+    
+    require!(clock.unix_timestamp >= epoch.end_time, XGrowthError::EpochNotEnded);
+    require!(!epoch.finalized, XGrowthError::EpochAlreadyFinalized);
+    
+    // No agent may be left out of the total
+    require!(
+        epoch.agents_recorded == platform.total_agents,
+        XGrowthError::AgentsNotRecorded
+    );
+    
+    // The pool must already hold what the epoch will credit
+    platform.allocate_rewards(epoch.allocation())?;
+    
+    // Cap the top shares now that every score is known
+    epoch.settle_share_cap()?;
+    epoch.finalized = true;
+    epoch.dispute_end = clock.unix_timestamp + platform.dispute_window;
+    platform.last_finalized_epoch = epoch.epoch;
+    
+    emit!(EpochFinalized {
+        epoch: epoch.epoch,
+        total_score: epoch.total_score,
+        agents_recorded: epoch.agents_recorded,
+        capped_agents: epoch.capped_agents,
+        share_cap: epoch.share_cap,
+        dispute_end: epoch.dispute_end,
+    });
+    
+    msg!("Epoch {} finalized, total score: {}", epoch.epoch, epoch.total_score);
+    
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct FundRewardPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = platform.reward_pool_bump,
+        constraint = reward_pool.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = reward_pool.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = funder_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
+    )]
+    pub funder_usdt_account: Account<'info, TokenAccount>,
+    
+    pub funder: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Deposit USDT into the reward pool (anyone)
+pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+    require!(amount > 0, XGrowthError::InvalidAmount);
+    
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.funder_usdt_account.to_account_info(),
+            to: ctx.accounts.reward_pool.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+    
+    let platform = &mut ctx.accounts.platform;
+    platform.record_funding(amount)?;
+    
+    emit!(RewardPoolFunded {
+        funder: ctx.accounts.funder.key(),
+        amount,
+        total_funded: platform.total_rewards_funded,
+        unallocated: platform.unallocated_rewards,
+    });
+    
+    msg!("Reward pool funded with {} USDT, unallocated: {}", amount, platform.unallocated_rewards);
+    
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct FundRewardStream<'info> {
+    #[account(
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump,
+        constraint = authority.key() == agent.authority @ XGrowthError::Unauthorized
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_stream", agent.key().as_ref(), &[stream.index]],
+        bump = stream.bump
+    )]
+    pub stream: Account<'info, RewardStream>,
+    
+    #[account(
+        mut,
+        seeds = [b"stream_vault", stream.key().as_ref()],
+        bump = stream.vault_bump
+    )]
+    pub stream_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == stream.mint @ XGrowthError::InvalidMint
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+    
+    pub authority: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Deposit reward tokens into a live stream (agent creator only)
+pub fn fund_reward_stream(ctx: Context<FundRewardStream>, amount: u64) -> Result<()> {
+    require!(amount > 0, XGrowthError::InvalidAmount);
+    
+    let clock = Clock::get()?;
+    let stream = &mut ctx.accounts.stream;
+    require!(
+        !stream.ended && clock.unix_timestamp < stream.end_time,
+        XGrowthError::RewardStreamEnded
+    );
+    
+    // Emit up to now first, so new funds only pay for time still to come
+    stream.update(ctx.accounts.agent.total_shares, clock.unix_timestamp)?;
+    
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.stream_vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+    
+    stream.total_funded = stream.total_funded
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    emit!(RewardStreamFunded {
+        stream: stream.key(),
+        amount,
+        total_funded: stream.total_funded,
+    });
+    
+    msg!("Funded reward stream {} with {}", stream.index, amount);
+    
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{Mint, Token, TokenAccount};
 use crate::state::*;
 
 #[derive(Accounts)]
@@ -15,13 +16,36 @@ pub struct InitializePlatform<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
-    /// CHECK: USDT mint address
-    pub usdt_mint: AccountInfo<'info>,
+    pub usdt_mint: Account<'info, Mint>,
     
-    /// CHECK: Oracle pubkey for performance updates
+    // Fee treasury, owned by the platform PDA
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdt_mint,
+        token::authority = platform,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    // USDT reward pool, owned by the platform PDA
+    #[account(
+        init,
+        payer = authority,
+        token::mint = usdt_mint,
+        token::authority = platform,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    /// CHECK: Oracle pubkey for performance updates, the first member of the oracle set
     pub oracle: AccountInfo<'info>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn initialize_platform(
@@ -34,8 +58,26 @@ pub fn initialize_platform(
     platform.daily_reward_pool = daily_reward_pool;
     platform.total_agents = 0;
     platform.usdt_mint = ctx.accounts.usdt_mint.key();
-    platform.oracle = ctx.accounts.oracle.key();
     platform.bump = ctx.bumps.platform;
+    platform.treasury_bump = ctx.bumps.treasury;
+    platform.protocol_fee_balance = 0;
+    platform.total_protocol_fees = 0;
+    platform.total_protocol_fees_withdrawn = 0;
+    platform.total_creator_fees = 0;
+    platform.total_creator_fees_claimed = 0;
+    platform.reward_pool_bump = ctx.bumps.reward_pool;
+    platform.total_rewards_funded = 0;
+    platform.total_rewards_allocated = 0;
+    platform.total_rewards_claimed = 0;
+    platform.unallocated_rewards = 0;
+    platform.current_epoch = 0;
+    platform.last_finalized_epoch = 0;
+    platform.unbonding_period = 0;
+    platform.claim_window = 0;
+    platform.sweep_to_treasury = false;
+    platform.total_rewards_expired = 0;
+    platform.total_rewards_swept_to_treasury = 0;
+    platform.set_default_config(ctx.accounts.oracle.key())?;
     
     msg!("Platform initialized with daily reward pool: {}", daily_reward_pool);
     
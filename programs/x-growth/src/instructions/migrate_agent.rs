@@ -0,0 +1,144 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::*;
+use crate::curve::{BondingCurveParams, QuadraticCurve, FACTOR_PRECISION};
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct MigrateAgent<'info> {
+    // Migrated first, with migrate_platform
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    /// CHECK: a legacy agent no longer deserializes as Agent; discriminator, size and seeds are checked by hand
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub agent: AccountInfo<'info>,
+    
+    // Checked against the legacy agent's token_mint
+    pub token_mint: Account<'info, Mint>,
+    
+    #[account(address = platform.usdt_mint)]
+    pub usdt_mint: Account<'info, Mint>,
+    
+    // USDT reserve backing the curve, which the original program did not create
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = usdt_mint,
+        token::authority = agent,
+        seeds = [b"reserve", agent.key().as_ref()],
+        bump
+    )]
+    pub reserve_usdt_account: Account<'info, TokenAccount>,
+    
+    // Vault for staked agent tokens, which the original program did not create
+    #[account(
+        init_if_needed,
+        payer = payer,
+        token::mint = token_mint,
+        token::authority = agent,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    // Pays the rent for the larger account and the new vaults
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Move an agent written by the original program (LegacyAgent) to the current layout (anyone).
+// Identity, supplies, reserve balance, the original metrics and reward totals carry over; the
+// curve keeps its original pricing as a QuadraticCurve with curve_factor = FACTOR_PRECISION,
+// and everything else starts as on a new agent. Until then the agent fails AgentNotMigrated.
+pub fn migrate_agent(ctx: Context<MigrateAgent>) -> Result<()> {
+    let agent_info = ctx.accounts.agent.to_account_info();
+    let old_len = agent_info.data_len();
+    
+    let legacy = {
+        let data = agent_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Agent::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(old_len != Agent::LEN, XGrowthError::AgentAlreadyMigrated);
+        require!(old_len == LegacyAgent::LEN, ErrorCode::AccountDidNotDeserialize);
+        LegacyAgent::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+    };
+    
+    let expected = Pubkey::create_program_address(
+        &[b"agent", legacy.agent_id.as_bytes(), &[legacy.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ErrorCode::ConstraintSeeds)?;
+    require_keys_eq!(agent_info.key(), expected, ErrorCode::ConstraintSeeds);
+    require_keys_eq!(ctx.accounts.token_mint.key(), legacy.token_mint, XGrowthError::InvalidMint);
+    
+    // Keep the account rent exempt at its new size
+    let rent_exempt = Rent::get()?.minimum_balance(Agent::LEN);
+    let shortfall = rent_exempt.saturating_sub(agent_info.lamports());
+    if shortfall > 0 {
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.payer.to_account_info(),
+                to: agent_info.clone(),
+            },
+        );
+        system_program::transfer(transfer_ctx, shortfall)?;
+    }
+    
+    // Start from a zeroed agent, as create_agent does
+    agent_info.realloc(Agent::LEN, false)?;
+    let mut agent = {
+        let mut data = agent_info.try_borrow_mut_data()?;
+        data[8..].fill(0);
+        Agent::try_deserialize(&mut &data[..])?
+    };
+    
+    agent.agent_id = legacy.agent_id;
+    agent.authority = legacy.authority;
+    agent.token_mint = legacy.token_mint;
+    agent.name = legacy.name;
+    agent.symbol = legacy.symbol;
+    agent.uri = legacy.uri;
+    agent.bonding_curve = BondingCurveParams::Quadratic(QuadraticCurve {
+        base_price: legacy.bonding_curve.base_price,
+        curve_factor: FACTOR_PRECISION,
+        max_supply: legacy.bonding_curve.max_supply,
+    });
+    agent.total_supply = legacy.total_supply;
+    agent.circulating_supply = legacy.circulating_supply;
+    agent.reserve_balance = legacy.reserve_balance;
+    agent.reserve_bump = ctx.bumps.reserve_usdt_account;
+    agent.performance = legacy.performance.into();
+    agent.total_rewards_earned = legacy.total_rewards_earned;
+    agent.last_reward_distribution = legacy.last_reward_distribution;
+    agent.stake_vault_bump = ctx.bumps.stake_vault;
+    agent.bump = legacy.bump;
+    agent.try_serialize(&mut &mut agent_info.try_borrow_mut_data()?[..])?;
+    
+    emit!(AgentMigrated {
+        agent: agent_info.key(),
+        old_len: old_len as u64,
+        new_len: Agent::LEN as u64,
+    });
+    
+    msg!("Agent {} migrated to the current layout", agent.agent_id);
+    
+    Ok(())
+}
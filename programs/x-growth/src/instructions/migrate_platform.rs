@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_lang::Discriminator;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct MigratePlatform<'info> {
+    /// CHECK: a legacy platform no longer deserializes as Platform; discriminator and size are checked by hand
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"platform"],
+        bump
+    )]
+    pub platform: AccountInfo<'info>,
+    
+    // The legacy platform authority; pays the rent for the larger account and the new vaults
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    // Checked against the legacy platform's usdt_mint
+    pub usdt_mint: Account<'info, Mint>,
+    
+    // Fee treasury, which the original program did not have
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = usdt_mint,
+        token::authority = platform,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    // USDT reward pool, which the original program did not have
+    #[account(
+        init_if_needed,
+        payer = authority,
+        token::mint = usdt_mint,
+        token::authority = platform,
+        seeds = [b"reward_pool"],
+        bump
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Move the platform written by the original program to the current layout (platform authority
+// only). Authority, reward pool size, agent count and mint carry over, the single oracle becomes
+// a 1-of-1 oracle set, and everything else starts as on a new platform. Agents are migrated
+// afterwards with migrate_agent, which needs the current platform.
+pub fn migrate_platform(ctx: Context<MigratePlatform>) -> Result<()> {
+    let platform_info = ctx.accounts.platform.to_account_info();
+    let old_len = platform_info.data_len();
+    
+    let legacy = {
+        let data = platform_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Platform::DISCRIMINATOR,
+            ErrorCode::AccountDiscriminatorMismatch
+        );
+        require!(old_len != Platform::LEN, XGrowthError::PlatformAlreadyMigrated);
+        require!(old_len == LegacyPlatform::LEN, ErrorCode::AccountDidNotDeserialize);
+        LegacyPlatform::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::AccountDidNotDeserialize)?
+    };
+    
+    require_keys_eq!(ctx.accounts.authority.key(), legacy.authority, XGrowthError::Unauthorized);
+    require_keys_eq!(ctx.accounts.usdt_mint.key(), legacy.usdt_mint, XGrowthError::InvalidMint);
+    
+    // Keep the account rent exempt at its new size
+    let rent_exempt = Rent::get()?.minimum_balance(Platform::LEN);
+    let shortfall = rent_exempt.saturating_sub(platform_info.lamports());
+    if shortfall > 0 {
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: platform_info.clone(),
+            },
+        );
+        system_program::transfer(transfer_ctx, shortfall)?;
+    }
+    
+    // Start from a zeroed platform, as initialize_platform does
+    platform_info.realloc(Platform::LEN, false)?;
+    let mut platform = {
+        let mut data = platform_info.try_borrow_mut_data()?;
+        data[8..].fill(0);
+        Platform::try_deserialize(&mut &data[..])?
+    };
+    
+    platform.authority = legacy.authority;
+    platform.daily_reward_pool = legacy.daily_reward_pool;
+    platform.total_agents = legacy.total_agents;
+    platform.usdt_mint = legacy.usdt_mint;
+    platform.bump = legacy.bump;
+    platform.treasury_bump = ctx.bumps.treasury;
+    platform.reward_pool_bump = ctx.bumps.reward_pool;
+    platform.set_default_config(legacy.oracle)?;
+    platform.try_serialize(&mut &mut platform_info.try_borrow_mut_data()?[..])?;
+    
+    emit!(PlatformMigrated {
+        old_len: old_len as u64,
+        new_len: Platform::LEN as u64,
+    });
+    
+    msg!("Platform migrated with {} agents", platform.total_agents);
+    
+    Ok(())
+}
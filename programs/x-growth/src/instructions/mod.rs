@@ -2,14 +2,86 @@ pub mod initialize_platform;
 pub mod create_agent;
 pub mod buy_from_curve;
 pub mod sell_to_curve;
+pub mod buy_exact_tokens;
+pub mod sell_for_exact_usdt;
+pub mod quote_buy;
+pub mod quote_sell;
+pub mod update_fee_config;
+pub mod set_score_weights;
+pub mod set_score_transforms;
+pub mod set_agent_fees;
+pub mod withdraw_protocol_fees;
+pub mod claim_creator_fees;
 pub mod update_performance;
+pub mod migrate_agent;
+pub mod migrate_platform;
+pub mod set_oracle_set;
+pub mod submit_observation;
+pub mod begin_epoch;
+pub mod record_agent_score;
+pub mod finalize_epoch;
+pub mod dispute_agent_score;
+pub mod resolve_dispute;
 pub mod distribute_rewards;
+pub mod fund_reward_pool;
 pub mod claim_rewards;
+pub mod set_claim_window;
+pub mod sweep_expired_rewards;
+pub mod stake;
+pub mod unstake;
+pub mod activate_stake;
+pub mod withdraw_stake;
+pub mod set_unbonding_period;
+pub mod post_merkle_root;
+pub mod claim_with_proof;
+pub mod create_reward_stream;
+pub mod fund_reward_stream;
+pub mod end_reward_stream;
+pub mod sweep_reward_stream;
+pub mod set_reward_vesting_period;
+pub mod set_dispute_config;
+pub mod compound_rewards;
 
 pub use initialize_platform::*;
 pub use create_agent::*;
 pub use buy_from_curve::*;
 pub use sell_to_curve::*;
+pub use buy_exact_tokens::*;
+pub use sell_for_exact_usdt::*;
+pub use quote_buy::*;
+pub use quote_sell::*;
+pub use update_fee_config::*;
+pub use set_score_weights::*;
+pub use set_score_transforms::*;
+pub use set_agent_fees::*;
+pub use withdraw_protocol_fees::*;
+pub use claim_creator_fees::*;
 pub use update_performance::*;
+pub use migrate_agent::*;
+pub use migrate_platform::*;
+pub use set_oracle_set::*;
+pub use submit_observation::*;
+pub use begin_epoch::*;
+pub use record_agent_score::*;
+pub use finalize_epoch::*;
+pub use dispute_agent_score::*;
+pub use resolve_dispute::*;
 pub use distribute_rewards::*;
+pub use fund_reward_pool::*;
 pub use claim_rewards::*;
+pub use set_claim_window::*;
+pub use sweep_expired_rewards::*;
+pub use stake::*;
+pub use unstake::*;
+pub use activate_stake::*;
+pub use withdraw_stake::*;
+pub use set_unbonding_period::*;
+pub use post_merkle_root::*;
+pub use claim_with_proof::*;
+pub use create_reward_stream::*;
+pub use fund_reward_stream::*;
+pub use end_reward_stream::*;
+pub use sweep_reward_stream::*;
+pub use set_reward_vesting_period::*;
+pub use set_dispute_config::*;
+pub use compound_rewards::*;
@@ -0,0 +1,117 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use super::final_reward_share;
+
+#[derive(Accounts)]
+#[instruction(root: [u8; 32], total_amount: u64, num_leaves: u32)]
+pub struct PostMerkleRoot<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    #[account(
+        init,
+        payer = poster,
+        space = MerkleDistribution::space(num_leaves),
+        seeds = [b"distribution", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub distribution: Account<'info, MerkleDistribution>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    /// CHECK: the agent's ScoreDispute for the epoch; empty unless its score was disputed
+    #[account(
+        seeds = [b"dispute", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub dispute: AccountInfo<'info>,
+    
+    #[account(
+        mut,
+        constraint = platform.oracle_set.contains(&poster.key()) || poster.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub poster: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Publish an off-chain computed (user, amount) snapshot for one agent and epoch (oracle or authority).
+// The root takes the place of the agent's on-chain credit: it pays out of the agent's share of
+// the epoch, and the agent can no longer be credited through distribute_rewards.
+pub fn post_merkle_root(
+    ctx: Context<PostMerkleRoot>,
+    root: [u8; 32],
+    total_amount: u64,
+    num_leaves: u32,
+) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let epoch = &mut ctx.accounts.epoch;
+    require!(epoch.finalized, XGrowthError::EpochNotFinalized);
+    require!(agent.last_recorded_epoch == epoch.epoch, XGrowthError::ScoreNotRecorded);
+    require!(agent.last_credited_epoch < epoch.epoch, XGrowthError::RewardsAlreadyCredited);
+    
+    let clock = Clock::get()?;
+    require!(
+        num_leaves > 0 && num_leaves <= MerkleDistribution::MAX_LEAVES,
+        XGrowthError::InvalidDistribution
+    );
+    
+    // Bounded by what the crank would have credited, once the agent's score is final like for
+    // the crank; finalize already allocated it
+    let share = final_reward_share(epoch, agent, &ctx.accounts.dispute, clock.unix_timestamp)?;
+    require!(
+        total_amount > 0 && total_amount <= share,
+        XGrowthError::InvalidDistribution
+    );
+    
+    agent.total_rewards_earned = agent.total_rewards_earned
+        .checked_add(total_amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    agent.last_credited_epoch = epoch.epoch;
+    let dust = epoch.credit(total_amount)?;
+    ctx.accounts.platform.release_rewards(dust)?;
+    
+    let distribution = &mut ctx.accounts.distribution;
+    distribution.agent = agent.key();
+    distribution.epoch = epoch.epoch;
+    distribution.root = root;
+    distribution.total_amount = total_amount;
+    distribution.total_claimed = 0;
+    distribution.num_leaves = num_leaves;
+    distribution.bump = ctx.bumps.distribution;
+    distribution.claimed = vec![0; MerkleDistribution::bitmap_len(num_leaves)];
+    
+    emit!(MerkleRootPosted {
+        agent: distribution.agent,
+        epoch: distribution.epoch,
+        root,
+        total_amount,
+        num_leaves,
+    });
+    
+    msg!(
+        "Merkle root posted for epoch {} - {} USDT over {} leaves",
+        distribution.epoch, total_amount, num_leaves
+    );
+    
+    Ok(())
+}
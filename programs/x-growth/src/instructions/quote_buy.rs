@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+
+#[derive(Accounts)]
+pub struct QuoteBuy<'info> {
+    #[account(
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+}
+
+pub fn quote_buy(ctx: Context<QuoteBuy>, usdt_amount: u64, with_referrer: bool) -> Result<TradeQuote> {
+    let quote = ctx.accounts.agent.quote_buy(
+        &ctx.accounts.platform.fee_config,
+        usdt_amount,
+        with_referrer,
+    )?;
+    
+    msg!(
+        "Quote: {} USDT buys {} tokens, spot price after: {}",
+        usdt_amount, quote.tokens_out, quote.spot_price_after
+    );
+    
+    Ok(quote)
+}
@@ -0,0 +1,60 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct RecordAgentScore<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+}
+
+// Crank step 1, once per agent: snapshot the agent's score into the epoch
+pub fn record_agent_score(ctx: Context<RecordAgentScore>) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let epoch = &mut ctx.accounts.epoch;
+    let clock = Clock::get()?;
+    
+    require!(clock.unix_timestamp >= epoch.end_time, XGrowthError::EpochNotEnded);
+    require!(!epoch.finalized, XGrowthError::EpochAlreadyFinalized);
+    require!(agent.last_recorded_epoch < epoch.epoch, XGrowthError::ScoreAlreadyRecorded);
+    
+    // The previous score is still needed until it has been credited
+    require!(
+        agent.last_credited_epoch == agent.last_recorded_epoch,
+        XGrowthError::RewardsNotCredited
+    );
+    
+    let score = agent.performance.calculate_score(&epoch.score_weights, &epoch.score_transforms)?;
+    
+    agent.epoch_score = score;
+    agent.last_recorded_epoch = epoch.epoch;
+    
+    // Reset daily metrics for the next epoch
+    agent.performance.reset_daily_metrics();
+    
+    epoch.record_score(score)?;
+    
+    emit!(AgentScoreRecorded {
+        epoch: epoch.epoch,
+        agent: agent.key(),
+        score,
+        total_score: epoch.total_score,
+    });
+    
+    msg!("Epoch {} - {} scored {}", epoch.epoch, agent.agent_id, score);
+    
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct ResolveDispute<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"dispute", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump = dispute.bump
+    )]
+    pub dispute: Account<'info, ScoreDispute>,
+    
+    #[account(
+        constraint = platform.can_dispute(&resolver.key()) @ XGrowthError::Unauthorized
+    )]
+    pub resolver: Signer<'info>,
+}
+
+// Close a dispute with the agent's corrected metrics for the epoch (authority or guardian).
+// The epoch's total and the other shares are already fixed, so the agent is credited on the
+// lower of its recorded and corrected scores, and never more than its recorded share.
+// Corrections only ever lower a score: an under-reported agent keeps its recorded score, and a
+// dispute resolved in its favour just releases its frozen share.
+pub fn resolve_dispute(ctx: Context<ResolveDispute>, report: MetricReport) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let epoch = &mut ctx.accounts.epoch;
+    let dispute = &mut ctx.accounts.dispute;
+    let clock = Clock::get()?;
+    
+    require!(!dispute.resolved, XGrowthError::DisputeAlreadyResolved);
+    require!(clock.unix_timestamp < dispute.resolve_by, XGrowthError::DisputeLapsed);
+    
+    // Score the corrected daily metrics with the epoch's own weights and transforms
+    let mut corrected = PerformanceMetrics::default();
+    corrected.apply_report(&report)?;
+    let corrected_score = corrected.calculate_score(&epoch.score_weights, &epoch.score_transforms)?;
+    let credited_score = corrected_score.min(dispute.recorded_score);
+    
+    agent.epoch_score = credited_score;
+    
+    dispute.resolved = true;
+    dispute.resolved_at = clock.unix_timestamp;
+    dispute.corrected_values = report.values()?;
+    dispute.corrected_score = corrected_score;
+    epoch.open_disputes -= 1;
+    
+    emit!(ScoreDisputeResolved {
+        epoch: epoch.epoch,
+        agent: agent.key(),
+        recorded_score: dispute.recorded_score,
+        corrected_score,
+        credited_score,
+    });
+    
+    msg!(
+        "Epoch {} - dispute of {} resolved, score {} -> {}",
+        epoch.epoch, agent.agent_id, dispute.recorded_score, credited_score
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use super::SellToCurve;
+
+// Exact-output sell: pays out exactly `usdt_amount` after fees and burns the tokens
+// the curve needs for it. Shares the SellToCurve accounts and settlement with sell_to_curve.
+pub fn sell_for_exact_usdt(
+    ctx: Context<SellToCurve>,
+    usdt_amount: u64,
+    max_tokens_in: u64,
+) -> Result<()> {
+    let has_referrer = ctx.accounts.has_referrer()?;
+    
+    // Calculate tokens to burn for the requested USDT
+    let quote = ctx.accounts.agent.quote_sell_for_exact_usdt(
+        &ctx.accounts.platform.fee_config,
+        usdt_amount,
+        has_referrer,
+    )?;
+    
+    // Check slippage
+    require!(quote.tokens_in <= max_tokens_in, XGrowthError::SlippageExceeded);
+    
+    ctx.accounts.settle(&quote)
+}
@@ -2,11 +2,14 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Burn, Token, TokenAccount, Transfer};
 use crate::state::*;
 use crate::errors::*;
+use crate::events::*;
+use super::validate_referrer;
 
 #[derive(Accounts)]
 pub struct SellToCurve<'info> {
     #[account(
         mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
         seeds = [b"agent", agent.agent_id.as_bytes()],
         bump = agent.bump
     )]
@@ -27,26 +30,45 @@ pub struct SellToCurve<'info> {
     
     #[account(
         mut,
-        constraint = seller_usdt_account.mint == platform.usdt_mint
+        constraint = seller_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint
     )]
     pub seller_usdt_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
         seeds = [b"reserve", agent.key().as_ref()],
-        bump
+        bump = agent.reserve_bump,
+        constraint = reserve_usdt_account.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        token::mint = platform.usdt_mint,
+        token::authority = agent
     )]
     pub reserve_usdt_account: Account<'info, TokenAccount>,
     
     #[account(
+        mut,
         seeds = [b"platform"],
         bump = platform.bump
     )]
     pub platform: Account<'info, Platform>,
     
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
     #[account(mut)]
     pub seller: Signer<'info>,
     
+    // Optional referrer USDT account that receives the referrer fee leg
+    #[account(mut)]
+    pub referrer_usdt_account: Option<Account<'info, TokenAccount>>,
+    
     pub token_program: Program<'info, Token>,
 }
 
@@ -55,57 +77,111 @@ pub fn sell_to_curve(
     token_amount: u64,
     min_usdt_out: u64,
 ) -> Result<()> {
-    let agent = &mut ctx.accounts.agent;
-    
-    // Calculate USDT to return (simplified - should use integral)
-    let current_price = agent.bonding_curve.calculate_price(agent.circulating_supply);
-    let usdt_out = (token_amount * current_price) / 1_000_000;
+    let has_referrer = ctx.accounts.has_referrer()?;
     
-    // Apply 1% sell fee
-    let usdt_after_fee = (usdt_out * 99) / 100;
+    // Calculate USDT to return from the curve integral
+    let quote = ctx.accounts.agent.quote_sell(
+        &ctx.accounts.platform.fee_config,
+        token_amount,
+        has_referrer,
+    )?;
     
     // Check slippage
-    require!(usdt_after_fee >= min_usdt_out, XGrowthError::SlippageExceeded);
+    require!(quote.usdt_out >= min_usdt_out, XGrowthError::SlippageExceeded);
     
-    // Check reserve has enough
-    require!(agent.reserve_balance >= usdt_after_fee, XGrowthError::InsufficientReserve);
-    
-    // Burn tokens
-    let burn_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        Burn {
-            mint: ctx.accounts.token_mint.to_account_info(),
-            from: ctx.accounts.seller_token_account.to_account_info(),
-            authority: ctx.accounts.seller.to_account_info(),
-        },
-    );
-    token::burn(burn_ctx, token_amount)?;
-    
-    // Transfer USDT to seller
-    let agent_id = agent.agent_id.clone();
-    let seeds = &[
-        b"agent",
-        agent_id.as_bytes(),
-        &[agent.bump],
-    ];
-    let signer = &[&seeds[..]];
-    
-    let transfer_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        Transfer {
-            from: ctx.accounts.reserve_usdt_account.to_account_info(),
-            to: ctx.accounts.seller_usdt_account.to_account_info(),
-            authority: agent.to_account_info(),
-        },
-        signer,
-    );
-    token::transfer(transfer_ctx, usdt_after_fee)?;
-    
-    // Update state
-    agent.circulating_supply -= token_amount;
-    agent.reserve_balance -= usdt_after_fee;
-    
-    msg!("Sold {} tokens for {} USDT", token_amount, usdt_after_fee);
-    
-    Ok(())
+    ctx.accounts.settle(&quote)
+}
+
+impl<'info> SellToCurve<'info> {
+    pub fn has_referrer(&self) -> Result<bool> {
+        validate_referrer(&self.referrer_usdt_account, &self.platform, self.seller.key())
+    }
+    
+    // Move funds and update the agent for a quoted sell
+    pub fn settle(&mut self, quote: &TradeQuote) -> Result<()> {
+        let agent = &mut self.agent;
+        
+        // Burn tokens
+        let burn_ctx = CpiContext::new(
+            self.token_program.to_account_info(),
+            Burn {
+                mint: self.token_mint.to_account_info(),
+                from: self.seller_token_account.to_account_info(),
+                authority: self.seller.to_account_info(),
+            },
+        );
+        token::burn(burn_ctx, quote.tokens_in)?;
+        
+        // Transfer USDT to seller
+        let agent_id = agent.agent_id.clone();
+        let seeds = &[
+            b"agent",
+            agent_id.as_bytes(),
+            &[agent.bump],
+        ];
+        let signer = &[&seeds[..]];
+        
+        let transfer_ctx = CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            Transfer {
+                from: self.reserve_usdt_account.to_account_info(),
+                to: self.seller_usdt_account.to_account_info(),
+                authority: agent.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, quote.usdt_out)?;
+        
+        // Pay the protocol and creator legs into the treasury
+        let treasury_fee = quote.protocol_fee + quote.creator_fee;
+        if treasury_fee > 0 {
+            let treasury_ctx = CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                Transfer {
+                    from: self.reserve_usdt_account.to_account_info(),
+                    to: self.treasury.to_account_info(),
+                    authority: agent.to_account_info(),
+                },
+                signer,
+            );
+            token::transfer(treasury_ctx, treasury_fee)?;
+        }
+        
+        // Pay the referrer leg from the reserve
+        if let Some(referrer_usdt_account) = &self.referrer_usdt_account {
+            if quote.referrer_fee > 0 {
+                let referrer_ctx = CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    Transfer {
+                        from: self.reserve_usdt_account.to_account_info(),
+                        to: referrer_usdt_account.to_account_info(),
+                        authority: agent.to_account_info(),
+                    },
+                    signer,
+                );
+                token::transfer(referrer_ctx, quote.referrer_fee)?;
+            }
+        }
+        
+        // Update state
+        agent.circulating_supply -= quote.tokens_in;
+        agent.reserve_balance -= quote.usdt_out + quote.fee;
+        agent.referrer_fees_paid += quote.referrer_fee;
+        self.platform.record_fees(agent, quote.protocol_fee, quote.creator_fee)?;
+        
+        emit!(TradeFeesCharged {
+            agent: agent.key(),
+            trader: self.seller.key(),
+            is_buy: false,
+            fee: quote.fee,
+            protocol_fee: quote.protocol_fee,
+            creator_fee: quote.creator_fee,
+            referrer: self.referrer_usdt_account.as_ref().map(|account| account.key()),
+            referrer_fee: quote.referrer_fee,
+        });
+        
+        msg!("Sold {} tokens for {} USDT", quote.tokens_in, quote.usdt_out);
+        
+        Ok(())
+    }
 }
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetAgentFees<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump,
+        constraint = authority.key() == agent.authority @ XGrowthError::Unauthorized
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    pub authority: Signer<'info>,
+}
+
+// Set or clear (None) the agent's own fee rates, bounded by the platform config
+pub fn set_agent_fees(
+    ctx: Context<SetAgentFees>,
+    fee_override: Option<AgentFeeRates>,
+) -> Result<()> {
+    if let Some(rates) = &fee_override {
+        rates.validate(&ctx.accounts.platform.fee_config)?;
+    }
+    
+    let agent = &mut ctx.accounts.agent;
+    agent.fee_override = fee_override;
+    
+    emit!(AgentFeesUpdated {
+        agent: agent.key(),
+        buy_fee_bps: fee_override.map(|rates| rates.buy_fee_bps),
+        sell_fee_bps: fee_override.map(|rates| rates.sell_fee_bps),
+    });
+    
+    msg!("Agent fees updated: {}", agent.agent_id);
+    
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetClaimWindow<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_claim_window(
+    ctx: Context<SetClaimWindow>,
+    claim_window: i64,
+    sweep_to_treasury: bool,
+) -> Result<()> {
+    require!(
+        claim_window == 0 || claim_window >= Platform::MIN_CLAIM_WINDOW,
+        XGrowthError::InvalidClaimWindow
+    );
+    
+    let platform = &mut ctx.accounts.platform;
+    platform.claim_window = claim_window;
+    platform.sweep_to_treasury = sweep_to_treasury;
+    
+    emit!(ClaimWindowUpdated {
+        claim_window,
+        sweep_to_treasury,
+    });
+    
+    msg!(
+        "Claim window set to {} seconds, sweep to treasury: {}",
+        claim_window, sweep_to_treasury
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetDisputeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_dispute_config(
+    ctx: Context<SetDisputeConfig>,
+    guardian: Option<Pubkey>,
+    dispute_window: i64,
+) -> Result<()> {
+    require!(
+        (0..=Platform::MAX_DISPUTE_WINDOW).contains(&dispute_window),
+        XGrowthError::InvalidDisputeWindow
+    );
+    
+    // Applies to epochs finalized from now on; each epoch keeps the dispute_end it was given
+    let platform = &mut ctx.accounts.platform;
+    platform.guardian = guardian;
+    platform.dispute_window = dispute_window;
+    
+    emit!(DisputeConfigUpdated { guardian, dispute_window });
+    
+    msg!("Dispute window set to {} seconds", dispute_window);
+    
+    Ok(())
+}
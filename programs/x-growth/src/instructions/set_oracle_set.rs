@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetOracleSet<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_oracle_set(
+    ctx: Context<SetOracleSet>,
+    oracles: Vec<Pubkey>,
+    threshold: u8,
+    outlier_tolerance_bps: u16,
+) -> Result<()> {
+    let platform = &mut ctx.accounts.platform;
+    
+    // A new version discards observations still pending under the old set
+    let version = platform.oracle_set.version
+        .checked_add(1)
+        .ok_or(XGrowthError::MathOverflow)?;
+    platform.oracle_set = OracleSet::new(&oracles, threshold, outlier_tolerance_bps, version)?;
+    
+    emit!(OracleSetUpdated {
+        oracles,
+        threshold,
+        outlier_tolerance_bps,
+        version,
+    });
+    
+    msg!("Oracle set v{}: {} of {}", version, threshold, platform.oracle_set.count);
+    
+    Ok(())
+}
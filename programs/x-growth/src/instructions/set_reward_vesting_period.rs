@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetRewardVestingPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_reward_vesting_period(
+    ctx: Context<SetRewardVestingPeriod>,
+    reward_vesting_period: i64,
+) -> Result<()> {
+    require!(
+        (0..=Platform::MAX_REWARD_VESTING_PERIOD).contains(&reward_vesting_period),
+        XGrowthError::InvalidVestingPeriod
+    );
+    
+    // Applies from the next credit; rewards already vesting keep their schedule until then
+    ctx.accounts.platform.reward_vesting_period = reward_vesting_period;
+    
+    emit!(RewardVestingPeriodUpdated { reward_vesting_period });
+    
+    msg!("Reward vesting period set to {} seconds", reward_vesting_period);
+    
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetScoreTransforms<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_score_transforms(
+    ctx: Context<SetScoreTransforms>,
+    score_transforms: ScoreTransforms,
+) -> Result<()> {
+    score_transforms.validate()?;
+    
+    // Like the weights, the epoch under way keeps the transforms it began with
+    let platform = &mut ctx.accounts.platform;
+    platform.score_transforms = score_transforms;
+    
+    emit!(ScoreTransformsUpdated {
+        score_transforms,
+        effective_epoch: platform.current_epoch + 1,
+    });
+    
+    msg!(
+        "Score transforms updated from epoch {}, max agent share: {} bps",
+        platform.current_epoch + 1, score_transforms.max_agent_share_bps
+    );
+    
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetScoreWeights<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_score_weights(
+    ctx: Context<SetScoreWeights>,
+    score_weights: ScoreWeights,
+) -> Result<()> {
+    score_weights.validate()?;
+    
+    // begin_epoch snapshots the weights, so the epoch under way keeps scoring with the old ones
+    let platform = &mut ctx.accounts.platform;
+    platform.score_weights = score_weights;
+    
+    emit!(ScoreWeightsUpdated {
+        likes: score_weights.likes,
+        views: score_weights.views,
+        comments: score_weights.comments,
+        followers: score_weights.followers,
+        effective_epoch: platform.current_epoch + 1,
+    });
+    
+    msg!("Score weights updated from epoch {}", platform.current_epoch + 1);
+    
+    Ok(())
+}
@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SetUnbondingPeriod<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_unbonding_period(
+    ctx: Context<SetUnbondingPeriod>,
+    unbonding_period: i64,
+) -> Result<()> {
+    require!(
+        (0..=Platform::MAX_UNBONDING_PERIOD).contains(&unbonding_period),
+        XGrowthError::InvalidUnbondingPeriod
+    );
+    
+    // Applies to later unstakes; stake already unbonding keeps its end time
+    ctx.accounts.platform.unbonding_period = unbonding_period;
+    
+    emit!(UnbondingPeriodUpdated { unbonding_period });
+    
+    msg!("Unbonding period set to {} seconds", unbonding_period);
+    
+    Ok(())
+}
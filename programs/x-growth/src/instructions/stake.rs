@@ -0,0 +1,214 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump = agent.stake_vault_bump,
+        constraint = stake_vault.owner == agent.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = agent.token_mint,
+        token::authority = agent
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = agent.token_mint,
+        token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserStake::LEN,
+        seeds = [b"stake", user.key().as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = UserRewards::LEN,
+        seeds = [b"user_rewards", user.key().as_ref(), agent.key().as_ref()],
+        bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts: every reward stream of the agent, writable, in index order
+pub fn stake<'info>(ctx: Context<'_, '_, 'info, 'info, Stake<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, XGrowthError::InvalidAmount);
+    
+    let clock = Clock::get()?;
+    let user = ctx.accounts.user.key();
+    let agent_key = ctx.accounts.agent.key();
+    
+    // Lock the tokens in the agent's stake vault
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+    token::transfer(transfer_ctx, amount)?;
+    
+    let user_stake = &mut ctx.accounts.user_stake;
+    if user_stake.user == Pubkey::default() {
+        user_stake.user = user;
+        user_stake.agent = agent_key;
+        user_stake.bump = ctx.bumps.user_stake;
+    }
+    if user_stake.amount == 0 {
+        user_stake.start_time = clock.unix_timestamp;
+    }
+    
+    // Settle rewards earned so far; the new stake earns from the next epoch on
+    let mut streams = load_reward_streams(&ctx.accounts.agent, ctx.remaining_accounts)?;
+    let agent = &mut ctx.accounts.agent;
+    let user_rewards = &mut ctx.accounts.user_rewards;
+    user_rewards.open(user, agent_key, ctx.bumps.user_rewards);
+    add_stake(
+        agent,
+        user_stake,
+        user_rewards,
+        &mut streams,
+        amount,
+        ctx.accounts.platform.current_epoch,
+    )?;
+    
+    emit!(Staked {
+        agent: agent_key,
+        user,
+        amount,
+        total_staked: agent.total_shares,
+    });
+    
+    msg!("Staked {} tokens, total staked: {}", amount, agent.total_shares);
+    
+    Ok(())
+}
+
+// The agent's reward streams, passed as writable remaining accounts in index order
+pub fn load_reward_streams<'info>(
+    agent: &Account<'info, Agent>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<Vec<Account<'info, RewardStream>>> {
+    require!(
+        remaining_accounts.len() == agent.reward_stream_count as usize,
+        XGrowthError::InvalidRewardStream
+    );
+    
+    let mut streams = Vec::with_capacity(remaining_accounts.len());
+    for (index, info) in remaining_accounts.iter().enumerate() {
+        require!(info.is_writable, XGrowthError::InvalidRewardStream);
+        let stream = Account::<RewardStream>::try_from(info)?;
+        require!(
+            stream.agent == agent.key() && stream.index as usize == index,
+            XGrowthError::InvalidRewardStream
+        );
+        streams.push(stream);
+    }
+    Ok(streams)
+}
+
+// Add `amount` to a user's stake. Stake earns from the first epoch that begins after it was
+// staked, so that it cannot be placed just ahead of a credit: until the agent has been credited
+// for the epoch it was staked in, it waits in warming_amount. Stake still warming from an
+// earlier epoch waits along with it.
+pub fn add_stake<'info>(
+    agent: &mut Agent,
+    user_stake: &mut UserStake,
+    user_rewards: &mut UserRewards,
+    streams: &mut [Account<'info, RewardStream>],
+    amount: u64,
+    current_epoch: u64,
+) -> Result<()> {
+    let mut shares = warmed_up_shares(agent, user_stake, user_rewards)?;
+    if agent.last_credited_epoch >= current_epoch {
+        shares = shares.checked_add(amount).ok_or(XGrowthError::MathOverflow)?;
+    } else {
+        user_stake.warming_amount = user_stake.warming_amount
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        user_stake.warming_epoch = current_epoch;
+    }
+    user_stake.amount = user_stake.amount
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    update_stake_shares(agent, user_rewards, streams, shares)
+}
+
+// The user's shares once warming stake that has sat out its epoch joins them
+pub fn warmed_up_shares(agent: &Agent, user_stake: &mut UserStake, user_rewards: &UserRewards) -> Result<u64> {
+    if !user_stake.warmed_up(agent) {
+        return Ok(user_rewards.shares);
+    }
+    let amount = user_stake.warming_amount;
+    user_stake.warming_amount = 0;
+    let shares = user_rewards.shares
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    let total_staked = agent.total_shares
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    emit!(StakeActivated {
+        agent: user_stake.agent,
+        user: user_stake.user,
+        amount,
+        total_staked,
+    });
+    Ok(shares)
+}
+
+// Change a user's stake, settling the USDT rewards and every reward stream at the old stake first
+pub fn update_stake_shares<'info>(
+    agent: &mut Agent,
+    user_rewards: &mut UserRewards,
+    streams: &mut [Account<'info, RewardStream>],
+    shares: u64,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    agent.release_vested_rewards(now)?;
+    for stream in streams.iter_mut() {
+        stream.update(agent.total_shares, now)?;
+        user_rewards.settle_stream(stream)?;
+    }
+    
+    user_rewards.update_shares(agent, shares)?;
+    
+    for stream in streams.iter() {
+        user_rewards.reset_stream_debt(stream)?;
+        stream.exit(&crate::ID)?;
+    }
+    Ok(())
+}
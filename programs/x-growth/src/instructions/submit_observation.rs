@@ -0,0 +1,126 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SubmitObservation<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    // The epoch whose metrics are observed
+    #[account(
+        seeds = [b"epoch", epoch.epoch.to_le_bytes().as_ref()],
+        bump = epoch.bump
+    )]
+    pub epoch: Account<'info, RewardEpoch>,
+    
+    #[account(
+        init_if_needed,
+        payer = oracle,
+        space = PendingObservation::LEN,
+        seeds = [b"observation", agent.key().as_ref(), epoch.epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub observation: Account<'info, PendingObservation>,
+    
+    #[account(
+        mut,
+        constraint = platform.oracle_set.contains(&oracle.key()) @ XGrowthError::UnauthorizedOracle
+    )]
+    pub oracle: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+// Submit one oracle's metrics for an agent and epoch. Once `threshold` oracles of the set have
+// submitted, the per-metric median is applied to the agent like an update_performance report.
+pub fn submit_observation(ctx: Context<SubmitObservation>, report: MetricReport) -> Result<()> {
+    let agent = &mut ctx.accounts.agent;
+    let epoch = &ctx.accounts.epoch;
+    let oracle_set = &ctx.accounts.platform.oracle_set;
+    let oracle = ctx.accounts.oracle.key();
+    let clock = Clock::get()?;
+    
+    // Metrics count towards the epoch only until the agent's score is recorded
+    require!(!epoch.finalized, XGrowthError::EpochAlreadyFinalized);
+    require!(agent.last_recorded_epoch < epoch.epoch, XGrowthError::ScoreAlreadyRecorded);
+    
+    let observation = &mut ctx.accounts.observation;
+    if observation.agent == Pubkey::default() {
+        observation.agent = agent.key();
+        observation.epoch = epoch.epoch;
+        observation.bump = ctx.bumps.observation;
+    }
+    observation.submit(oracle, &report, oracle_set)?;
+    
+    emit!(ObservationSubmitted {
+        agent: agent.key(),
+        epoch: epoch.epoch,
+        oracle,
+        submissions: observation.submissions,
+        threshold: oracle_set.threshold,
+    });
+    
+    if observation.submissions < oracle_set.threshold {
+        msg!("Observation {} of {} for epoch {}", observation.submissions, oracle_set.threshold, epoch.epoch);
+        return Ok(());
+    }
+    
+    // Quorum reached: accept the median, which no minority of oracles can move
+    let median = observation.median();
+    observation.accepted = true;
+    observation.accepted_values = median;
+    
+    agent.performance.apply_report(&MetricReport {
+        version: MetricReport::V2,
+        values: median.to_vec(),
+    })?;
+    agent.performance.last_updated = clock.unix_timestamp;
+    
+    for (submitter, values) in observation.submitters.iter().zip(&observation.values)
+        .take(observation.submissions as usize)
+    {
+        for (metric, (value, median)) in values.iter().zip(&median).enumerate() {
+            if oracle_set.is_outlier(*value, *median) {
+                emit!(ObservationOutlier {
+                    agent: agent.key(),
+                    epoch: epoch.epoch,
+                    oracle: *submitter,
+                    metric: metric as u8,
+                    value: *value,
+                    median: *median,
+                });
+            }
+        }
+    }
+    
+    emit!(ObservationAccepted {
+        agent: agent.key(),
+        epoch: epoch.epoch,
+        values: median.to_vec(),
+        submissions: observation.submissions,
+    });
+    
+    emit!(PerformanceUpdated {
+        agent: agent.key(),
+        version: observation.report_version,
+        engagement_rate_bps: agent.performance.daily_engagement_rate_bps(),
+        timestamp: clock.unix_timestamp,
+    });
+    
+    msg!("Observation accepted for epoch {} from {} oracles", epoch.epoch, observation.submissions);
+    
+    Ok(())
+}
@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct SweepExpiredRewards<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_rewards", user_rewards.user.as_ref(), agent.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_pool"],
+        bump = platform.reward_pool_bump,
+        constraint = reward_pool.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = reward_pool.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub reward_pool: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+// Reclaim a user's pending rewards left unclaimed past the claim window (permissionless).
+// Rewards accrued to the stake are settled first, as for a claim, so the sweep takes the whole
+// balance; rewards accrued within the window keep all of it from expiring.
+pub fn sweep_expired_rewards(ctx: Context<SweepExpiredRewards>) -> Result<()> {
+    let user_rewards = &mut ctx.accounts.user_rewards;
+    let platform = &mut ctx.accounts.platform;
+    let clock = Clock::get()?;
+    
+    ctx.accounts.agent.release_vested_rewards(clock.unix_timestamp)?;
+    user_rewards.settle(&ctx.accounts.agent)?;
+    
+    let amount = user_rewards.pending_rewards;
+    require!(amount > 0, XGrowthError::NoRewardsToClaim);
+    require!(
+        user_rewards.rewards_expired(platform.claim_window, clock.unix_timestamp),
+        XGrowthError::RewardsNotExpired
+    );
+    
+    user_rewards.pending_rewards = 0;
+    user_rewards.expired_rewards = user_rewards.expired_rewards
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    let to_treasury = platform.expire_rewards(amount)?;
+    if to_treasury {
+        let seeds: &[&[u8]] = &[b"platform", &[platform.bump]];
+        let signer = &[seeds];
+        
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.reward_pool.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+                authority: platform.to_account_info(),
+            },
+            signer,
+        );
+        token::transfer(transfer_ctx, amount)?;
+    }
+    
+    emit!(RewardsExpired {
+        user: user_rewards.user,
+        agent: user_rewards.agent,
+        amount,
+        last_accrual: user_rewards.last_accrual,
+        to_treasury,
+    });
+    
+    msg!("Swept {} USDT of expired rewards, to treasury: {}", amount, to_treasury);
+    
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+use crate::errors::*;
+use crate::events::*;
+use super::EndRewardStream;
+
+// Return what an ended stream's stakers can no longer claim to the creator (agent creator only):
+// the accumulator's rounding dust once nobody is staked, and anything sent to the vault directly.
+// Shares the EndRewardStream accounts with end_reward_stream.
+pub fn sweep_reward_stream(ctx: Context<EndRewardStream>) -> Result<()> {
+    require!(ctx.accounts.stream.ended, XGrowthError::RewardStreamActive);
+    
+    let refund = ctx.accounts.refund_unowed()?;
+    require!(refund > 0, XGrowthError::NothingToSweep);
+    
+    let stream = &ctx.accounts.stream;
+    emit!(RewardStreamSwept {
+        stream: stream.key(),
+        refunded: refund,
+        total_refunded: stream.total_refunded,
+    });
+    
+    msg!("Reward stream {} swept, refunded {}", stream.index, refund);
+    
+    Ok(())
+}
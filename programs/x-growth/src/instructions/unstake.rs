@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use super::{load_reward_streams, update_stake_shares, warmed_up_shares};
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump = agent.stake_vault_bump,
+        constraint = stake_vault.owner == agent.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = agent.token_mint,
+        token::authority = agent
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = agent.token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", user.key().as_ref(), agent.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_rewards", user.key().as_ref(), agent.key().as_ref()],
+        bump = user_rewards.bump
+    )]
+    pub user_rewards: Account<'info, UserRewards>,
+    
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+// Remaining accounts: every reward stream of the agent, writable, in index order
+pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>, amount: u64) -> Result<()> {
+    require!(amount > 0, XGrowthError::InvalidAmount);
+    require!(amount <= ctx.accounts.user_stake.amount, XGrowthError::InsufficientStake);
+    
+    let clock = Clock::get()?;
+    let unbonding_period = ctx.accounts.platform.unbonding_period;
+    
+    // Settle rewards earned so far; the unstaked tokens stop earning now. Stake that is still
+    // warming up, and so not earning yet, is unstaked first.
+    let mut streams = load_reward_streams(&ctx.accounts.agent, ctx.remaining_accounts)?;
+    let agent = &mut ctx.accounts.agent;
+    let user_rewards = &mut ctx.accounts.user_rewards;
+    let user_stake = &mut ctx.accounts.user_stake;
+    let shares = warmed_up_shares(agent, user_stake, user_rewards)?;
+    let from_warming = amount.min(user_stake.warming_amount);
+    user_stake.warming_amount -= from_warming;
+    update_stake_shares(agent, user_rewards, &mut streams, shares - (amount - from_warming))?;
+    user_stake.amount -= amount;
+    
+    let unbonding_end = if unbonding_period == 0 {
+        release_stake(
+            &ctx.accounts.agent,
+            &ctx.accounts.stake_vault,
+            &ctx.accounts.user_token_account,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+        clock.unix_timestamp
+    } else {
+        // Later unstakes restart the unbonding of everything still waiting
+        user_stake.unbonding_amount = user_stake.unbonding_amount
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        user_stake.unbonding_end = clock.unix_timestamp + unbonding_period;
+        user_stake.unbonding_end
+    };
+    
+    emit!(Unstaked {
+        agent: ctx.accounts.agent.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+        unbonding_end,
+    });
+    
+    msg!("Unstaked {} tokens, total staked: {}", amount, ctx.accounts.agent.total_shares);
+    
+    Ok(())
+}
+
+// Pay staked tokens out of the stake vault, signed by the agent PDA that owns it
+pub fn release_stake<'info>(
+    agent: &Account<'info, Agent>,
+    stake_vault: &Account<'info, TokenAccount>,
+    destination: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let seeds = &[
+        b"agent",
+        agent.agent_id.as_bytes(),
+        &[agent.bump],
+    ];
+    let signer = &[&seeds[..]];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        Transfer {
+            from: stake_vault.to_account_info(),
+            to: destination.to_account_info(),
+            authority: agent.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)
+}
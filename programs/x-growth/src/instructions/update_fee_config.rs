@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct UpdateFeeConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn update_fee_config(
+    ctx: Context<UpdateFeeConfig>,
+    fee_config: FeeConfig,
+) -> Result<()> {
+    fee_config.validate()?;
+    
+    ctx.accounts.platform.fee_config = fee_config;
+    
+    emit!(FeeConfigUpdated {
+        buy_fee_bps: fee_config.buy_fee_bps,
+        sell_fee_bps: fee_config.sell_fee_bps,
+        protocol_share_bps: fee_config.protocol_share_bps,
+        creator_share_bps: fee_config.creator_share_bps,
+        referrer_share_bps: fee_config.referrer_share_bps,
+        max_agent_fee_bps: fee_config.max_agent_fee_bps,
+    });
+    
+    msg!(
+        "Fee config updated - Buy: {} bps, Sell: {} bps",
+        fee_config.buy_fee_bps, fee_config.sell_fee_bps
+    );
+    
+    Ok(())
+}
@@ -1,11 +1,17 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    self as instructions_sysvar, load_current_index_checked, load_instruction_at_checked,
+};
 use crate::state::*;
 use crate::errors::*;
+use crate::events::*;
 
 #[derive(Accounts)]
 pub struct UpdatePerformance<'info> {
     #[account(
         mut,
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
         seeds = [b"agent", agent.agent_id.as_bytes()],
         bump = agent.bump
     )]
@@ -18,39 +24,143 @@ pub struct UpdatePerformance<'info> {
     pub platform: Account<'info, Platform>,
     
     #[account(
-        constraint = oracle.key() == platform.oracle @ XGrowthError::UnauthorizedOracle
+        init_if_needed,
+        payer = payer,
+        space = ReportSequence::LEN,
+        seeds = [b"report_sequence", agent.key().as_ref()],
+        bump
     )]
-    pub oracle: Signer<'info>,
+    pub report_sequence: Account<'info, ReportSequence>,
+    
+    /// CHECK: the reporting oracle; signs the transaction itself unless the report carries an
+    /// attestation, whose Ed25519 signature is then checked against this key
+    #[account(
+        constraint = platform.oracle_set.contains(&oracle.key()) @ XGrowthError::UnauthorizedOracle
+    )]
+    pub oracle: AccountInfo<'info>,
+    
+    // The oracle itself, or any keeper relaying an attested report
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// CHECK: the instructions sysvar, read for the Ed25519 verification of an attestation
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: AccountInfo<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 pub fn update_performance(
     ctx: Context<UpdatePerformance>,
-    likes: u64,
-    views: u64,
-    comments: u64,
-    new_followers: u64,
+    report: MetricReport,
+    sequence: u64,
+    attestation: Option<Attestation>,
 ) -> Result<()> {
+    // One oracle can only speak for the set when the set trusts any single member
+    require!(ctx.accounts.platform.oracle_set.threshold == 1, XGrowthError::QuorumRequired);
+    
     let agent = &mut ctx.accounts.agent;
+    let oracle = ctx.accounts.oracle.key();
     let clock = Clock::get()?;
     
-    // Update cumulative metrics
-    agent.performance.total_likes += likes;
-    agent.performance.total_views += views;
-    agent.performance.total_comments += comments;
-    agent.performance.total_followers += new_followers;
+    // Deltas are added to the counters, so a report must only ever be applied once
+    let report_sequence = &mut ctx.accounts.report_sequence;
+    if report_sequence.agent == Pubkey::default() {
+        report_sequence.agent = agent.key();
+        report_sequence.bump = ctx.bumps.report_sequence;
+    }
+    require!(sequence > report_sequence.last_sequence, XGrowthError::StaleReport);
+    report_sequence.last_sequence = sequence;
     
-    // Update daily metrics
-    agent.performance.daily_likes += likes;
-    agent.performance.daily_views += views;
-    agent.performance.daily_comments += comments;
-    agent.performance.daily_new_followers += new_followers;
+    match attestation {
+        None => require!(ctx.accounts.oracle.is_signer, ErrorCode::AccountNotSigner),
+        Some(attestation) => {
+            // Signed for the epoch the metrics will count towards, so a report held back past
+            // the agent's score cannot land in a later epoch
+            require!(
+                attestation.epoch == agent.counting_epoch(ctx.accounts.platform.current_epoch),
+                XGrowthError::AttestationEpochMismatch
+            );
+            
+            let message = attestation.message(&agent.key(), sequence, &report);
+            verify_ed25519_signature(&ctx.accounts.instructions, &oracle, &message)?;
+            
+            emit!(AttestationRelayed {
+                agent: agent.key(),
+                oracle,
+                relayer: ctx.accounts.payer.key(),
+                epoch: attestation.epoch,
+                sequence,
+            });
+        }
+    }
     
+    // Update cumulative and daily metrics; older report versions leave the newer metrics untouched
+    agent.performance.apply_report(&report)?;
     agent.performance.last_updated = clock.unix_timestamp;
     
+    let engagement_rate_bps = agent.performance.daily_engagement_rate_bps();
+    
+    emit!(PerformanceUpdated {
+        agent: agent.key(),
+        version: report.version,
+        engagement_rate_bps,
+        timestamp: clock.unix_timestamp,
+    });
+    
     msg!(
-        "Performance updated - Likes: {}, Views: {}, Comments: {}, Followers: {}",
-        likes, views, comments, new_followers
+        "Performance updated - report #{} v{}: {:?}, engagement {} bps",
+        sequence, report.version, report.values, engagement_rate_bps
+    );
+    
+    Ok(())
+}
+
+// Ed25519 precompile data: signature count, padding, then per signature the offsets and
+// instruction indices of the signature, public key and message
+const ED25519_OFFSETS_START: usize = 2;
+const ED25519_OFFSETS_LEN: usize = 14;
+
+// Require the instruction right before this one to be an Ed25519 precompile verification of
+// `message` under `signer`. The runtime fails the whole transaction if that signature is bad.
+fn verify_ed25519_signature(instructions: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, XGrowthError::InvalidAttestation);
+    let verify_index = current - 1;
+    let verify = load_instruction_at_checked(verify_index as usize, instructions)?;
+    require!(
+        verify.program_id == ed25519_program::ID && verify.accounts.is_empty(),
+        XGrowthError::InvalidAttestation
     );
     
+    let data = &verify.data;
+    require!(
+        data.len() >= ED25519_OFFSETS_START + ED25519_OFFSETS_LEN && data[0] == 1,
+        XGrowthError::InvalidAttestation
+    );
+    let offset = |field: usize| {
+        let at = ED25519_OFFSETS_START + 2 * field;
+        u16::from_le_bytes([data[at], data[at + 1]])
+    };
+    
+    // Everything must be read from the precompile instruction itself (u16::MAX or its own
+    // index), or the key and message checked here may not be the ones that were verified
+    let [_, signature_ix, pubkey_offset, pubkey_ix, message_offset, message_size, message_ix] =
+        [0, 1, 2, 3, 4, 5, 6].map(offset);
+    require!(
+        [signature_ix, pubkey_ix, message_ix]
+            .iter()
+            .all(|index| *index == u16::MAX || *index == verify_index),
+        XGrowthError::InvalidAttestation
+    );
+    
+    let pubkey_start = pubkey_offset as usize;
+    let message_start = message_offset as usize;
+    let verified_key = data.get(pubkey_start..pubkey_start + 32);
+    let verified_message = data.get(message_start..message_start + message_size as usize);
+    require!(
+        verified_key == Some(signer.as_ref()) && verified_message == Some(message),
+        XGrowthError::InvalidAttestation
+    );
     Ok(())
 }
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+
+#[derive(Accounts)]
+pub struct WithdrawProtocolFees<'info> {
+    #[account(
+        mut,
+        seeds = [b"platform"],
+        bump = platform.bump
+    )]
+    pub platform: Account<'info, Platform>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = platform.treasury_bump,
+        constraint = treasury.mint == platform.usdt_mint @ XGrowthError::InvalidMint,
+        constraint = treasury.owner == platform.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = platform.usdt_mint,
+        token::authority = platform
+    )]
+    pub treasury: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = destination.mint == platform.usdt_mint @ XGrowthError::InvalidMint
+    )]
+    pub destination: Account<'info, TokenAccount>,
+    
+    #[account(
+        constraint = authority.key() == platform.authority @ XGrowthError::Unauthorized
+    )]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_protocol_fees(
+    ctx: Context<WithdrawProtocolFees>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, XGrowthError::NoFeesToClaim);
+    require!(
+        amount <= ctx.accounts.platform.protocol_fee_balance,
+        XGrowthError::InsufficientFeeBalance
+    );
+    
+    // Transfer from the treasury, signed by the platform PDA
+    let seeds: &[&[u8]] = &[b"platform", &[ctx.accounts.platform.bump]];
+    let signer = &[seeds];
+    
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.platform.to_account_info(),
+        },
+        signer,
+    );
+    token::transfer(transfer_ctx, amount)?;
+    
+    let platform = &mut ctx.accounts.platform;
+    platform.protocol_fee_balance -= amount;
+    platform.total_protocol_fees_withdrawn = platform.total_protocol_fees_withdrawn
+        .checked_add(amount)
+        .ok_or(XGrowthError::MathOverflow)?;
+    
+    emit!(ProtocolFeesWithdrawn {
+        authority: ctx.accounts.authority.key(),
+        destination: ctx.accounts.destination.key(),
+        amount,
+        total_withdrawn: platform.total_protocol_fees_withdrawn,
+    });
+    
+    msg!("Withdrew {} USDT in protocol fees", amount);
+    
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use crate::state::*;
+use crate::errors::*;
+use crate::events::*;
+use crate::instructions::unstake::release_stake;
+
+#[derive(Accounts)]
+pub struct WithdrawStake<'info> {
+    #[account(
+        constraint = Agent::is_current_layout(&agent.to_account_info()) @ XGrowthError::AgentNotMigrated,
+        seeds = [b"agent", agent.agent_id.as_bytes()],
+        bump = agent.bump
+    )]
+    pub agent: Account<'info, Agent>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake_vault", agent.key().as_ref()],
+        bump = agent.stake_vault_bump,
+        constraint = stake_vault.owner == agent.key() @ XGrowthError::InvalidVaultAuthority,
+        token::mint = agent.token_mint,
+        token::authority = agent
+    )]
+    pub stake_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = agent.token_mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"stake", user.key().as_ref(), agent.key().as_ref()],
+        bump = user_stake.bump
+    )]
+    pub user_stake: Account<'info, UserStake>,
+    
+    pub user: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+    let amount = ctx.accounts.user_stake.unbonding_amount;
+    require!(amount > 0, XGrowthError::NothingToWithdraw);
+    
+    let clock = Clock::get()?;
+    require!(
+        clock.unix_timestamp >= ctx.accounts.user_stake.unbonding_end,
+        XGrowthError::UnbondingNotComplete
+    );
+    
+    release_stake(
+        &ctx.accounts.agent,
+        &ctx.accounts.stake_vault,
+        &ctx.accounts.user_token_account,
+        &ctx.accounts.token_program,
+        amount,
+    )?;
+    ctx.accounts.user_stake.unbonding_amount = 0;
+    
+    emit!(StakeWithdrawn {
+        agent: ctx.accounts.agent.key(),
+        user: ctx.accounts.user.key(),
+        amount,
+    });
+    
+    msg!("Withdrew {} unbonded tokens", amount);
+    
+    Ok(())
+}
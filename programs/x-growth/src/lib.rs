@@ -1,15 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
 
-declare_id!("XGrowth11111111111111111111111111111111111");
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
 pub mod state;
+pub mod curve;
 pub mod instructions;
 pub mod errors;
+pub mod events;
+pub mod math;
+pub mod merkle;
 
 use state::*;
+use curve::*;
 use instructions::*;
-use errors::*;
 
 #[program]
 pub mod x_growth {
@@ -54,24 +57,274 @@ pub mod x_growth {
         instructions::sell_to_curve(ctx, token_amount, min_usdt_out)
     }
 
-    // Update agent performance metrics (oracle only)
+    // Buy an exact amount of tokens from the bonding curve
+    pub fn buy_exact_tokens(
+        ctx: Context<BuyFromCurve>,
+        token_amount: u64,
+        max_usdt_in: u64,
+    ) -> Result<()> {
+        instructions::buy_exact_tokens(ctx, token_amount, max_usdt_in)
+    }
+
+    // Sell tokens back to bonding curve for an exact USDT amount
+    pub fn sell_for_exact_usdt(
+        ctx: Context<SellToCurve>,
+        usdt_amount: u64,
+        max_tokens_in: u64,
+    ) -> Result<()> {
+        instructions::sell_for_exact_usdt(ctx, usdt_amount, max_tokens_in)
+    }
+
+    // Simulate a buy and return the quote without moving funds
+    pub fn quote_buy(
+        ctx: Context<QuoteBuy>,
+        usdt_amount: u64,
+        with_referrer: bool,
+    ) -> Result<TradeQuote> {
+        instructions::quote_buy(ctx, usdt_amount, with_referrer)
+    }
+
+    // Simulate a sell and return the quote without moving funds
+    pub fn quote_sell(
+        ctx: Context<QuoteSell>,
+        token_amount: u64,
+        with_referrer: bool,
+    ) -> Result<TradeQuote> {
+        instructions::quote_sell(ctx, token_amount, with_referrer)
+    }
+
+    // Update the platform fee schedule (platform authority only)
+    pub fn update_fee_config(
+        ctx: Context<UpdateFeeConfig>,
+        fee_config: FeeConfig,
+    ) -> Result<()> {
+        instructions::update_fee_config(ctx, fee_config)
+    }
+
+    // Set the performance score weights used from the next epoch (platform authority only)
+    pub fn set_score_weights(
+        ctx: Context<SetScoreWeights>,
+        score_weights: ScoreWeights,
+    ) -> Result<()> {
+        instructions::set_score_weights(ctx, score_weights)
+    }
+
+    // Set score damping, caps and the per-agent pool share limit from the next epoch (platform authority only)
+    pub fn set_score_transforms(
+        ctx: Context<SetScoreTransforms>,
+        score_transforms: ScoreTransforms,
+    ) -> Result<()> {
+        instructions::set_score_transforms(ctx, score_transforms)
+    }
+
+    // Set or clear an agent's fee override (agent authority only)
+    pub fn set_agent_fees(
+        ctx: Context<SetAgentFees>,
+        fee_override: Option<AgentFeeRates>,
+    ) -> Result<()> {
+        instructions::set_agent_fees(ctx, fee_override)
+    }
+
+    // Withdraw accumulated protocol fees from the treasury (platform authority only)
+    pub fn withdraw_protocol_fees(
+        ctx: Context<WithdrawProtocolFees>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_protocol_fees(ctx, amount)
+    }
+
+    // Claim an agent's creator fees from the treasury (agent authority only)
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees(ctx)
+    }
+
+    // Update agent performance metrics (oracle signed, or relayed by anyone with an attestation)
     pub fn update_performance(
         ctx: Context<UpdatePerformance>,
-        likes: u64,
-        views: u64,
-        comments: u64,
-        new_followers: u64,
+        report: MetricReport,
+        sequence: u64,
+        attestation: Option<Attestation>,
+    ) -> Result<()> {
+        instructions::update_performance(ctx, report, sequence, attestation)
+    }
+
+    // Move a platform written by the original program to the current layout (platform authority only)
+    pub fn migrate_platform(ctx: Context<MigratePlatform>) -> Result<()> {
+        instructions::migrate_platform(ctx)
+    }
+
+    // Move an agent written by the original program to the current layout (anyone)
+    pub fn migrate_agent(ctx: Context<MigrateAgent>) -> Result<()> {
+        instructions::migrate_agent(ctx)
+    }
+
+    // Replace the oracle set and its M-of-N threshold (platform authority only)
+    pub fn set_oracle_set(
+        ctx: Context<SetOracleSet>,
+        oracles: Vec<Pubkey>,
+        threshold: u8,
+        outlier_tolerance_bps: u16,
+    ) -> Result<()> {
+        instructions::set_oracle_set(ctx, oracles, threshold, outlier_tolerance_bps)
+    }
+
+    // Submit one oracle's metrics for an agent and epoch; the median applies at the quorum (oracle set only)
+    pub fn submit_observation(
+        ctx: Context<SubmitObservation>,
+        report: MetricReport,
     ) -> Result<()> {
-        instructions::update_performance(ctx, likes, views, comments, new_followers)
+        instructions::submit_observation(ctx, report)
+    }
+
+    // Open the next reward epoch (permissionless crank)
+    pub fn begin_epoch(ctx: Context<BeginEpoch>) -> Result<()> {
+        instructions::begin_epoch(ctx)
+    }
+
+    // Record one agent's score into an ended epoch (permissionless crank)
+    pub fn record_agent_score(ctx: Context<RecordAgentScore>) -> Result<()> {
+        instructions::record_agent_score(ctx)
+    }
+
+    // Finalize an epoch once every agent is recorded (permissionless crank)
+    pub fn finalize_epoch(ctx: Context<FinalizeEpoch>) -> Result<()> {
+        instructions::finalize_epoch(ctx)
+    }
+
+    // Flag an agent's score during the epoch's dispute window (authority or guardian)
+    pub fn dispute_agent_score(ctx: Context<DisputeAgentScore>) -> Result<()> {
+        instructions::dispute_agent_score(ctx)
     }
 
-    // Calculate and distribute daily rewards
+    // Post an agent's corrected metrics for a disputed epoch (authority or guardian)
+    pub fn resolve_dispute(ctx: Context<ResolveDispute>, report: MetricReport) -> Result<()> {
+        instructions::resolve_dispute(ctx, report)
+    }
+
+    // Credit one agent its share of a finalized epoch's reward pool
     pub fn distribute_rewards(ctx: Context<DistributeRewards>) -> Result<()> {
         instructions::distribute_rewards(ctx)
     }
 
-    // Claim rewards for token holders
-    pub fn claim_rewards(ctx: Context<ClaimRewards>) -> Result<()> {
+    // Deposit USDT into the reward pool (anyone)
+    pub fn fund_reward_pool(ctx: Context<FundRewardPool>, amount: u64) -> Result<()> {
+        instructions::fund_reward_pool(ctx, amount)
+    }
+
+    // Claim pending USDT rewards and any reward stream balances passed as remaining accounts
+    pub fn claim_rewards<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimRewards<'info>>) -> Result<()> {
         instructions::claim_rewards(ctx)
     }
+
+    // Set how long pending rewards stay claimable and where expired rewards go (platform authority only)
+    pub fn set_claim_window(
+        ctx: Context<SetClaimWindow>,
+        claim_window: i64,
+        sweep_to_treasury: bool,
+    ) -> Result<()> {
+        instructions::set_claim_window(ctx, claim_window, sweep_to_treasury)
+    }
+
+    // Reclaim pending rewards left unclaimed past the claim window (permissionless)
+    pub fn sweep_expired_rewards(ctx: Context<SweepExpiredRewards>) -> Result<()> {
+        instructions::sweep_expired_rewards(ctx)
+    }
+
+    // Stake agent tokens in the agent's vault to earn its rewards
+    pub fn stake<'info>(ctx: Context<'_, '_, 'info, 'info, Stake<'info>>, amount: u64) -> Result<()> {
+        instructions::stake(ctx, amount)
+    }
+
+    // Stop earning on staked tokens and start their unbonding
+    pub fn unstake<'info>(ctx: Context<'_, '_, 'info, 'info, Unstake<'info>>, amount: u64) -> Result<()> {
+        instructions::unstake(ctx, amount)
+    }
+
+    // Start stake that has sat out the epoch it was staked in earning (permissionless)
+    pub fn activate_stake<'info>(ctx: Context<'_, '_, 'info, 'info, ActivateStake<'info>>) -> Result<()> {
+        instructions::activate_stake(ctx)
+    }
+
+    // Withdraw unstaked tokens once their unbonding period has elapsed
+    pub fn withdraw_stake(ctx: Context<WithdrawStake>) -> Result<()> {
+        instructions::withdraw_stake(ctx)
+    }
+
+    // Set the delay between unstaking and withdrawing (platform authority only)
+    pub fn set_unbonding_period(
+        ctx: Context<SetUnbondingPeriod>,
+        unbonding_period: i64,
+    ) -> Result<()> {
+        instructions::set_unbonding_period(ctx, unbonding_period)
+    }
+
+    // Post a merkle root of (user, amount) rewards for an agent and epoch (oracle or authority)
+    pub fn post_merkle_root(
+        ctx: Context<PostMerkleRoot>,
+        root: [u8; 32],
+        total_amount: u64,
+        num_leaves: u32,
+    ) -> Result<()> {
+        instructions::post_merkle_root(ctx, root, total_amount, num_leaves)
+    }
+
+    // Claim rewards from a merkle distribution with a proof of the user's leaf
+    pub fn claim_with_proof(
+        ctx: Context<ClaimWithProof>,
+        index: u32,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::claim_with_proof(ctx, index, amount, proof)
+    }
+
+    // Open a reward stream in any mint for the agent's stakers (agent creator only)
+    pub fn create_reward_stream(
+        ctx: Context<CreateRewardStream>,
+        emission_rate: u64,
+        end_time: i64,
+    ) -> Result<()> {
+        instructions::create_reward_stream(ctx, emission_rate, end_time)
+    }
+
+    // Deposit reward tokens into one of the agent's streams (agent creator only)
+    pub fn fund_reward_stream(ctx: Context<FundRewardStream>, amount: u64) -> Result<()> {
+        instructions::fund_reward_stream(ctx, amount)
+    }
+
+    // End a reward stream and refund what stakers cannot claim (agent creator only)
+    pub fn end_reward_stream(ctx: Context<EndRewardStream>) -> Result<()> {
+        instructions::end_reward_stream(ctx)
+    }
+
+    // Refund what an ended stream's stakers can no longer claim (agent creator only)
+    pub fn sweep_reward_stream(ctx: Context<EndRewardStream>) -> Result<()> {
+        instructions::sweep_reward_stream(ctx)
+    }
+
+    // Set how long each credited epoch reward takes to vest to stakers (platform authority only)
+    pub fn set_reward_vesting_period(
+        ctx: Context<SetRewardVestingPeriod>,
+        reward_vesting_period: i64,
+    ) -> Result<()> {
+        instructions::set_reward_vesting_period(ctx, reward_vesting_period)
+    }
+
+    // Set the guardian and how long finalized epoch scores can be disputed (platform authority only)
+    pub fn set_dispute_config(
+        ctx: Context<SetDisputeConfig>,
+        guardian: Option<Pubkey>,
+        dispute_window: i64,
+    ) -> Result<()> {
+        instructions::set_dispute_config(ctx, guardian, dispute_window)
+    }
+
+    // Buy more of the agent's token with pending USDT rewards, optionally staking it
+    pub fn compound_rewards<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompoundRewards<'info>>,
+        min_tokens_out: u64,
+        stake: bool,
+    ) -> Result<()> {
+        instructions::compound_rewards(ctx, min_tokens_out, stake)
+    }
 }
@@ -0,0 +1,173 @@
+use anchor_lang::prelude::*;
+use crate::errors::XGrowthError;
+
+#[allow(clippy::all)]
+mod uint_types {
+    uint::construct_uint! {
+        pub struct U256(4);
+    }
+}
+
+pub use uint_types::U256;
+
+// 1.0 in the 18-decimal fixed point used for curve ratios
+pub const WAD: u128 = 1_000_000_000_000_000_000;
+
+// ln(2) in WAD
+pub const LN2_WAD: u128 = 693_147_180_559_945_309;
+
+// Largest exponent accepted by exp_wad, keeps e^x * WAD inside u128
+pub const MAX_EXP_INPUT: u128 = 42 * WAD;
+
+pub fn wad() -> U256 {
+    U256::from(WAD)
+}
+
+pub fn checked_add(a: U256, b: U256) -> Result<U256> {
+    a.checked_add(b).ok_or_else(|| error!(XGrowthError::MathOverflow))
+}
+
+pub fn checked_sub(a: U256, b: U256) -> Result<U256> {
+    a.checked_sub(b).ok_or_else(|| error!(XGrowthError::MathOverflow))
+}
+
+pub fn checked_mul(a: U256, b: U256) -> Result<U256> {
+    a.checked_mul(b).ok_or_else(|| error!(XGrowthError::MathOverflow))
+}
+
+// a * b / denominator, rounded down
+pub fn mul_div_floor(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    require!(!denominator.is_zero(), XGrowthError::MathOverflow);
+    Ok(checked_mul(a, b)? / denominator)
+}
+
+// a * b / denominator, rounded up
+pub fn mul_div_ceil(a: U256, b: U256, denominator: U256) -> Result<U256> {
+    require!(!denominator.is_zero(), XGrowthError::MathOverflow);
+    let (quotient, remainder) = checked_mul(a, b)?.div_mod(denominator);
+    if remainder.is_zero() {
+        Ok(quotient)
+    } else {
+        checked_add(quotient, U256::one())
+    }
+}
+
+pub fn to_u64(value: U256) -> Result<u64> {
+    let value = u128::try_from(value).map_err(|_| error!(XGrowthError::MathOverflow))?;
+    u64::try_from(value).map_err(|_| error!(XGrowthError::MathOverflow))
+}
+
+// Largest r such that r^n <= value (Newton's method from above)
+pub fn integer_root(value: U256, n: u32) -> Result<U256> {
+    require!(n > 0, XGrowthError::MathOverflow);
+    if value.is_zero() || n == 1 {
+        return Ok(value);
+    }
+    let n_big = U256::from(n);
+    let mut x = U256::one() << value.bits().div_ceil(n as usize);
+    loop {
+        let x_pow = x.pow(U256::from(n - 1));
+        let next = (x * (n_big - U256::one()) + value / x_pow) / n_big;
+        if next >= x {
+            return Ok(x);
+        }
+        x = next;
+    }
+}
+
+// Largest amount in [0, upper] for which `fits` holds, searching outward from `estimate`.
+// `fits` must be monotone (true up to some point, false after) and true at zero.
+pub fn search_max_fitting<F>(estimate: u64, upper: u64, mut fits: F) -> Result<u64>
+where
+    F: FnMut(u64) -> Result<bool>,
+{
+    let estimate = estimate.min(upper);
+    let (mut lo, mut hi);
+    let mut step = 1u64;
+    if fits(estimate)? {
+        lo = estimate;
+        loop {
+            if lo == upper {
+                return Ok(lo);
+            }
+            let next = lo.saturating_add(step).min(upper);
+            if fits(next)? {
+                lo = next;
+                step = step.saturating_mul(2);
+            } else {
+                hi = next;
+                break;
+            }
+        }
+    } else {
+        hi = estimate;
+        loop {
+            let next = hi.saturating_sub(step);
+            if fits(next)? {
+                lo = next;
+                break;
+            }
+            hi = next;
+            step = step.saturating_mul(2);
+        }
+    }
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if fits(mid)? {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Ok(lo)
+}
+
+// e^x for x in WAD, x <= MAX_EXP_INPUT
+// x = k * ln(2) + r, e^x = 2^k * e^r with e^r from its Taylor series
+pub fn exp_wad(x: u128) -> Result<u128> {
+    require!(x <= MAX_EXP_INPUT, XGrowthError::MathOverflow);
+    let k = x / LN2_WAD;
+    let r = x - k * LN2_WAD;
+    
+    let mut term = WAD;
+    let mut sum = WAD;
+    let mut i = 1u128;
+    while term > 0 {
+        term = term * r / (WAD * i);
+        sum += term;
+        i += 1;
+    }
+    Ok(sum << k)
+}
+
+// ln(x) for x in WAD, x >= 1.0
+// x = 2^k * y with y in [1, 2), ln(y) = 2 * atanh((y - 1) / (y + 1))
+pub fn ln_wad(x: u128) -> Result<u128> {
+    require!(x >= WAD, XGrowthError::MathOverflow);
+    let k = 127 - (x / WAD).leading_zeros();
+    let y = x >> k;
+    
+    let z = (y - WAD) * WAD / (y + WAD);
+    let z_squared = z * z / WAD;
+    let mut power = z;
+    let mut sum = 0u128;
+    let mut i = 1u128;
+    while power > 0 {
+        sum += power / i;
+        power = power * z_squared / WAD;
+        i += 2;
+    }
+    Ok(k as u128 * LN2_WAD + 2 * sum)
+}
+
+// ln(1 + e^x) for signed x in WAD, |x| <= MAX_EXP_INPUT
+pub fn softplus_wad(x: i128) -> Result<u128> {
+    let magnitude = x.unsigned_abs();
+    let exp_neg = WAD * WAD / exp_wad(magnitude)?;
+    let tail = ln_wad(WAD + exp_neg)?;
+    if x >= 0 {
+        Ok(magnitude + tail)
+    } else {
+        Ok(tail)
+    }
+}
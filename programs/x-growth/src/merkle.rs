@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+
+// Domain tags keep a leaf from ever hashing like an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+// keccak(0x00 || index u32 LE || user || amount u64 LE)
+pub fn leaf_hash(index: u32, user: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        LEAF_PREFIX,
+        &index.to_le_bytes(),
+        user.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+// keccak(0x01 || min(a, b) || max(a, b)); sorted so proofs need no left/right flags
+pub fn node_hash(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[NODE_PREFIX, first, second]).to_bytes()
+}
+
+// Fold the proof into the leaf and compare with the posted root
+pub fn verify(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |hash, sibling| node_hash(&hash, sibling));
+    computed == *root
+}
+
+// Off-chain tree builder and proof generator for (user, amount) leaves.
+// Leaf i is claimed with index i; a lone node at the end of a level moves up unchanged.
+#[cfg(not(target_os = "solana"))]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+#[cfg(not(target_os = "solana"))]
+impl MerkleTree {
+    pub fn new(leaves: &[(Pubkey, u64)]) -> Self {
+        assert!(!leaves.is_empty(), "merkle tree needs at least one leaf");
+        let mut levels = vec![leaves
+            .iter()
+            .enumerate()
+            .map(|(index, (user, amount))| leaf_hash(index as u32, user, *amount))
+            .collect::<Vec<_>>()];
+        while levels.last().unwrap().len() > 1 {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => node_hash(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree { levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    pub fn num_leaves(&self) -> u32 {
+        self.levels[0].len() as u32
+    }
+
+    // Sibling hashes from the leaf up to the root
+    pub fn proof(&self, index: u32) -> Vec<[u8; 32]> {
+        let mut index = index as usize;
+        let mut proof = Vec::new();
+        for level in &self.levels[..self.levels.len() - 1] {
+            if let Some(sibling) = level.get(index ^ 1) {
+                proof.push(*sibling);
+            }
+            index /= 2;
+        }
+        proof
+    }
+}
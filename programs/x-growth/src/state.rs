@@ -1,4 +1,6 @@
 use anchor_lang::prelude::*;
+use crate::curve::{BondingCurveParams, QuadraticCurve};
+use crate::errors::XGrowthError;
 
 #[account]
 pub struct Platform {
@@ -6,12 +8,301 @@ pub struct Platform {
     pub daily_reward_pool: u64, // 200 USDT per day
     pub total_agents: u64,
     pub usdt_mint: Pubkey,
-    pub oracle: Pubkey,
     pub bump: u8,
+    
+    // Trading fees
+    pub fee_config: FeeConfig,
+    
+    // Fee treasury, holds the protocol balance and unclaimed creator fees
+    pub treasury_bump: u8,
+    pub protocol_fee_balance: u64,
+    pub total_protocol_fees: u64,
+    pub total_protocol_fees_withdrawn: u64,
+    pub total_creator_fees: u64,
+    pub total_creator_fees_claimed: u64,
+    
+    // USDT reward pool token account, owned by the platform PDA.
+    // The pool holds unallocated_rewards plus allocated but unclaimed rewards.
+    pub reward_pool_bump: u8,
+    pub total_rewards_funded: u64,
+    pub total_rewards_allocated: u64,
+    pub total_rewards_claimed: u64,
+    pub unallocated_rewards: u64,
+    
+    // Reward epochs
+    pub current_epoch: u64, // last epoch begun, 0 before the first
+    pub last_finalized_epoch: u64,
+    
+    // Staking: delay between unstaking and withdrawing, 0 to withdraw immediately
+    pub unbonding_period: i64,
+    
+    // Unclaimed rewards expire claim_window after they were last credited (0: never).
+    // Expired rewards go back to the reward pool, or to the treasury with sweep_to_treasury.
+    pub claim_window: i64,
+    pub sweep_to_treasury: bool,
+    pub total_rewards_expired: u64,
+    pub total_rewards_swept_to_treasury: u64,
+    
+    // Each credited epoch reward vests linearly to stakers over this many seconds (0: at once)
+    pub reward_vesting_period: i64,
+    
+    // Metric weights for epochs begun from now on; each epoch keeps the weights it began with
+    pub score_weights: ScoreWeights,
+    
+    // Damping, caps and the per-agent pool share limit, snapshotted like score_weights
+    pub score_transforms: ScoreTransforms,
+    
+    // Keys that report agent metrics, and how many of them must agree
+    pub oracle_set: OracleSet,
+    
+    // Scores of a finalized epoch can be disputed for dispute_window seconds, by the authority
+    // or the guardian, before they are credited
+    pub guardian: Option<Pubkey>,
+    pub dispute_window: i64,
 }
 
 impl Platform {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 32 + 1;
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 1 + FeeConfig::LEN +
+        1 + 8 + 8 + 8 + 8 + 8 +
+        1 + 8 + 8 + 8 + 8 +
+        8 + 8 +
+        8 +
+        8 + 1 + 8 + 8 +
+        8 +
+        ScoreWeights::LEN +
+        ScoreTransforms::LEN +
+        OracleSet::LEN +
+        (1 + 32) + 8;
+    
+    // Length of the metrics window of one reward epoch
+    pub const EPOCH_DURATION: i64 = 86_400;
+    
+    // Upper bound on unbonding_period
+    pub const MAX_UNBONDING_PERIOD: i64 = 30 * 86_400;
+    
+    // Shortest claim_window that can be set, so rewards are never swept right after being credited
+    pub const MIN_CLAIM_WINDOW: i64 = 7 * 86_400;
+    
+    // Upper bound on reward_vesting_period
+    pub const MAX_REWARD_VESTING_PERIOD: i64 = 30 * 86_400;
+    
+    // dispute_window of a new platform
+    pub const DEFAULT_DISPUTE_WINDOW: i64 = 6 * 3_600;
+    
+    // Upper bound on dispute_window, so that disputes end before the next epoch is recorded
+    pub const MAX_DISPUTE_WINDOW: i64 = Self::EPOCH_DURATION;
+    
+    // Configuration a new platform starts from, with `oracle` as the only oracle
+    pub fn set_default_config(&mut self, oracle: Pubkey) -> Result<()> {
+        self.fee_config = FeeConfig::DEFAULT;
+        self.reward_vesting_period = Self::EPOCH_DURATION;
+        self.score_weights = ScoreWeights::DEFAULT;
+        self.score_transforms = ScoreTransforms::DEFAULT;
+        self.oracle_set = OracleSet::new(
+            &[oracle],
+            1,
+            OracleSet::DEFAULT_OUTLIER_TOLERANCE_BPS,
+            0,
+        )?;
+        self.guardian = None;
+        self.dispute_window = Self::DEFAULT_DISPUTE_WINDOW;
+        Ok(())
+    }
+    
+    // How long after the dispute window a dispute can still be resolved; after that it lapses to
+    // the recorded score, so an abandoned dispute cannot hold up the agent's next epoch
+    pub const DISPUTE_RESOLUTION_PERIOD: i64 = Self::EPOCH_DURATION;
+    
+    // The platform authority, and the guardian when there is one, can dispute scores
+    pub fn can_dispute(&self, key: &Pubkey) -> bool {
+        *key == self.authority || self.guardian == Some(*key)
+    }
+    
+    // Book the protocol and creator legs of a trade fee paid into the treasury
+    pub fn record_fees(&mut self, agent: &mut Agent, protocol_fee: u64, creator_fee: u64) -> Result<()> {
+        self.protocol_fee_balance = self.protocol_fee_balance
+            .checked_add(protocol_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.total_protocol_fees = self.total_protocol_fees
+            .checked_add(protocol_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.total_creator_fees = self.total_creator_fees
+            .checked_add(creator_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        
+        agent.protocol_fees_accrued = agent.protocol_fees_accrued
+            .checked_add(protocol_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        agent.creator_fees_accrued = agent.creator_fees_accrued
+            .checked_add(creator_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        agent.creator_fee_balance = agent.creator_fee_balance
+            .checked_add(creator_fee)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(())
+    }
+    
+    // Book USDT deposited into the reward pool
+    pub fn record_funding(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_funded = self.total_rewards_funded
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.unallocated_rewards = self.unallocated_rewards
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(())
+    }
+    
+    // Reserve funded rewards for payouts; never more than the pool has unallocated
+    pub fn allocate_rewards(&mut self, amount: u64) -> Result<()> {
+        require!(amount <= self.unallocated_rewards, XGrowthError::RewardPoolUnderfunded);
+        self.unallocated_rewards -= amount;
+        self.total_rewards_allocated = self.total_rewards_allocated
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(())
+    }
+    
+    // Return allocated rewards that will never be paid out
+    pub fn release_rewards(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_allocated = self.total_rewards_allocated
+            .checked_sub(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.unallocated_rewards = self.unallocated_rewards
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(())
+    }
+    
+    // Take expired rewards out of their allocation, back into the pool or out to the treasury.
+    // Returns whether the tokens must move to the treasury.
+    pub fn expire_rewards(&mut self, amount: u64) -> Result<bool> {
+        self.total_rewards_expired = self.total_rewards_expired
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        if !self.sweep_to_treasury {
+            self.release_rewards(amount)?;
+            return Ok(false);
+        }
+        self.total_rewards_allocated = self.total_rewards_allocated
+            .checked_sub(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.total_rewards_swept_to_treasury = self.total_rewards_swept_to_treasury
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.protocol_fee_balance = self.protocol_fee_balance
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(true)
+    }
+    
+    // Book allocated rewards paid out of the pool
+    pub fn record_reward_claim(&mut self, amount: u64) -> Result<()> {
+        self.total_rewards_claimed = self.total_rewards_claimed
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(())
+    }
+}
+
+// Basis points denominator for fee rates and shares
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct FeeConfig {
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+    
+    // Split of every fee, must add up to BPS_DENOMINATOR.
+    // The referrer share goes to the protocol when no referrer is passed.
+    pub protocol_share_bps: u16,
+    pub creator_share_bps: u16,
+    pub referrer_share_bps: u16,
+    
+    // Upper bound for per-agent fee overrides
+    pub max_agent_fee_bps: u16,
+}
+
+impl FeeConfig {
+    pub const LEN: usize = 2 + 2 + 2 + 2 + 2 + 2;
+    
+    // Hard cap on any fee rate
+    pub const MAX_FEE_BPS: u16 = 1_000;
+    
+    // 1% sell fee to the protocol, no buy fee
+    pub const DEFAULT: FeeConfig = FeeConfig {
+        buy_fee_bps: 0,
+        sell_fee_bps: 100,
+        protocol_share_bps: 10_000,
+        creator_share_bps: 0,
+        referrer_share_bps: 0,
+        max_agent_fee_bps: 300,
+    };
+    
+    pub fn validate(&self) -> Result<()> {
+        require!(self.buy_fee_bps <= Self::MAX_FEE_BPS, XGrowthError::InvalidFeeConfig);
+        require!(self.sell_fee_bps <= Self::MAX_FEE_BPS, XGrowthError::InvalidFeeConfig);
+        require!(self.max_agent_fee_bps <= Self::MAX_FEE_BPS, XGrowthError::InvalidFeeConfig);
+        let total_share = self.protocol_share_bps as u64
+            + self.creator_share_bps as u64
+            + self.referrer_share_bps as u64;
+        require!(total_share == BPS_DENOMINATOR, XGrowthError::InvalidFeeConfig);
+        Ok(())
+    }
+    
+    // Split a fee into its protocol, creator and referrer legs.
+    // Rounding dust goes to the protocol.
+    pub fn split(&self, fee: u64, has_referrer: bool) -> FeeSplit {
+        let creator_fee = fee * self.creator_share_bps as u64 / BPS_DENOMINATOR;
+        let referrer_fee = if has_referrer {
+            fee * self.referrer_share_bps as u64 / BPS_DENOMINATOR
+        } else {
+            0
+        };
+        FeeSplit {
+            protocol_fee: fee - creator_fee - referrer_fee,
+            creator_fee,
+            referrer_fee,
+        }
+    }
+}
+
+pub struct FeeSplit {
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer_fee: u64,
+}
+
+// Per-agent fee rates set by the agent authority, capped by FeeConfig::max_agent_fee_bps
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct AgentFeeRates {
+    pub buy_fee_bps: u16,
+    pub sell_fee_bps: u16,
+}
+
+impl AgentFeeRates {
+    pub const LEN: usize = 2 + 2;
+    
+    pub fn validate(&self, fee_config: &FeeConfig) -> Result<()> {
+        require!(
+            self.buy_fee_bps <= fee_config.max_agent_fee_bps
+                && self.sell_fee_bps <= fee_config.max_agent_fee_bps,
+            XGrowthError::InvalidFeeConfig
+        );
+        Ok(())
+    }
+}
+
+// Fee charged on `amount` at `fee_bps`, rounded up
+pub fn fee_on(amount: u64, fee_bps: u16) -> u64 {
+    ((amount as u128 * fee_bps as u128).div_ceil(BPS_DENOMINATOR as u128)) as u64
+}
+
+// Smallest gross amount that still leaves `net` once the fee is taken out
+pub fn gross_up(net: u64, fee_bps: u16) -> Result<u64> {
+    let keep_bps = BPS_DENOMINATOR as u128 - fee_bps as u128;
+    let gross = (net as u128 * BPS_DENOMINATOR as u128).div_ceil(keep_bps);
+    u64::try_from(gross).map_err(|_| error!(XGrowthError::MathOverflow))
 }
 
 #[account]
@@ -28,6 +319,15 @@ pub struct Agent {
     pub total_supply: u64,
     pub circulating_supply: u64,
     pub reserve_balance: u64, // USDT in reserve
+    pub reserve_bump: u8, // reserve token account, owned by the agent PDA
+    
+    // Fees; the protocol and creator legs are paid into the platform treasury
+    pub fee_override: Option<AgentFeeRates>,
+    pub protocol_fees_accrued: u64,
+    pub creator_fees_accrued: u64,
+    pub referrer_fees_paid: u64,
+    pub creator_fee_balance: u64, // unclaimed creator fees held by the treasury
+    pub creator_fees_claimed: u64,
     
     // Performance metrics
     pub performance: PerformanceMetrics,
@@ -36,6 +336,28 @@ pub struct Agent {
     pub total_rewards_earned: u64,
     pub last_reward_distribution: i64,
     
+    // Epoch crank: score recorded for last_recorded_epoch, credited once per epoch
+    pub epoch_score: u64,
+    pub last_recorded_epoch: u64,
+    pub last_credited_epoch: u64,
+    
+    // Staker rewards (MasterChef style): credited rewards per share, scaled by ACC_REWARD_PRECISION
+    pub acc_reward_per_token: u128,
+    pub total_shares: u64, // tokens staked and earning, excludes unbonding stake
+    pub undistributed_rewards: u64, // credited while nothing was staked
+    pub stake_vault_bump: u8, // agent token vault holding stakes, owned by the agent PDA
+    
+    // Creator reward streams, RewardStream PDAs indexed 0..reward_stream_count
+    pub reward_stream_count: u8,
+    
+    // Linear vesting of credited rewards into the staker accumulator: unvested_rewards are
+    // released at reward_rate (scaled by ACC_REWARD_PRECISION) per second until reward_vesting_end
+    pub reward_rate: u128,
+    pub reward_vesting_start: i64,
+    pub reward_vesting_end: i64,
+    pub last_vesting_update: i64,
+    pub unvested_rewards: u64,
+    
     pub bump: u8,
 }
 
@@ -49,35 +371,252 @@ impl Agent {
         (4 + 200) + // uri
         BondingCurveParams::LEN +
         8 + 8 + 8 + // supplies and balance
+        1 + // reserve_bump
+        (1 + AgentFeeRates::LEN) + // fee_override
+        8 + 8 + 8 + 8 + 8 + // fee accounting
         PerformanceMetrics::LEN +
         8 + 8 + // rewards
+        8 + 8 + 8 + // epoch crank
+        16 + 8 + 8 + 1 + // staker rewards
+        1 + // reward_stream_count
+        16 + 8 + 8 + 8 + 8 + // reward vesting
         1; // bump
+    
+    // Whether a stored agent is in the current layout. Agents created by the original program
+    // are LegacyAgent::LEN bytes and must go through migrate_agent before anything else loads them.
+    pub fn is_current_layout(info: &AccountInfo) -> bool {
+        info.data_len() == Self::LEN
+    }
+    
+    // Epoch the daily counters are counting towards: the current one until the agent is
+    // recorded for it, then the next. Every agent is recorded before an epoch is finalized.
+    pub fn counting_epoch(&self, current_epoch: u64) -> u64 {
+        if self.last_recorded_epoch == current_epoch {
+            current_epoch + 1
+        } else {
+            current_epoch
+        }
+    }
+    
+    // Fixed point scale of acc_reward_per_token
+    pub const ACC_REWARD_PRECISION: u128 = 1_000_000_000_000;
+    
+    // Spread credited rewards over current stakers; held back until there are any
+    pub fn credit_holder_rewards(&mut self, amount: u64) -> Result<()> {
+        let amount = amount
+            .checked_add(self.undistributed_rewards)
+            .ok_or(XGrowthError::MathOverflow)?;
+        if self.total_shares == 0 {
+            self.undistributed_rewards = amount;
+            return Ok(());
+        }
+        let increase = amount as u128 * Self::ACC_REWARD_PRECISION / self.total_shares as u128;
+        self.acc_reward_per_token = self.acc_reward_per_token
+            .checked_add(increase)
+            .ok_or(XGrowthError::MathOverflow)?;
+        
+        // Carry the rounding remainder into the next credit
+        let distributed = increase * self.total_shares as u128 / Self::ACC_REWARD_PRECISION;
+        self.undistributed_rewards = amount - distributed as u64;
+        Ok(())
+    }
+    
+    // Vest `amount`, together with whatever is still unvested, linearly from now over `period`.
+    // With no period everything goes to stakers at once.
+    pub fn schedule_rewards(&mut self, amount: u64, period: i64, now: i64) -> Result<()> {
+        self.release_vested_rewards(now)?;
+        if amount == 0 {
+            return Ok(());
+        }
+        let amount = amount
+            .checked_add(self.unvested_rewards)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.reward_vesting_start = now;
+        self.last_vesting_update = now;
+        if period == 0 {
+            self.reward_rate = 0;
+            self.reward_vesting_end = now;
+            self.unvested_rewards = 0;
+            return self.credit_holder_rewards(amount);
+        }
+        self.reward_rate = amount as u128 * Self::ACC_REWARD_PRECISION / period as u128;
+        self.reward_vesting_end = now + period;
+        self.unvested_rewards = amount;
+        Ok(())
+    }
+    
+    // Credit stakers with what has vested since the last touch; the rest is released at the end
+    pub fn release_vested_rewards(&mut self, now: i64) -> Result<()> {
+        let until = now.min(self.reward_vesting_end);
+        if until <= self.last_vesting_update {
+            return Ok(());
+        }
+        let vested = if until == self.reward_vesting_end {
+            self.unvested_rewards
+        } else {
+            let elapsed = (until - self.last_vesting_update) as u128;
+            let streamed = self.reward_rate
+                .checked_mul(elapsed)
+                .ok_or(XGrowthError::MathOverflow)?
+                / Self::ACC_REWARD_PRECISION;
+            (streamed as u64).min(self.unvested_rewards)
+        };
+        self.last_vesting_update = until;
+        self.unvested_rewards -= vested;
+        self.credit_holder_rewards(vested)
+    }
+    
+    // Rewards accumulated by `shares` since the first credit
+    pub fn accumulated_rewards(&self, shares: u64) -> Result<u128> {
+        (shares as u128)
+            .checked_mul(self.acc_reward_per_token)
+            .map(|value| value / Self::ACC_REWARD_PRECISION)
+            .ok_or_else(|| error!(XGrowthError::MathOverflow))
+    }
+    
+    // Effective (buy, sell) fee rates: the agent override, capped by the platform bound
+    pub fn fee_rates(&self, fee_config: &FeeConfig) -> (u16, u16) {
+        match self.fee_override {
+            Some(rates) => (
+                rates.buy_fee_bps.min(fee_config.max_agent_fee_bps),
+                rates.sell_fee_bps.min(fee_config.max_agent_fee_bps),
+            ),
+            None => (fee_config.buy_fee_bps, fee_config.sell_fee_bps),
+        }
+    }
+    
+    // Price a buy of `usdt_amount` against the current curve state.
+    // buy_from_curve and quote_buy both go through here.
+    pub fn quote_buy(&self, fee_config: &FeeConfig, usdt_amount: u64, has_referrer: bool) -> Result<TradeQuote> {
+        let (buy_fee_bps, _) = self.fee_rates(fee_config);
+        let fee = fee_on(usdt_amount, buy_fee_bps);
+        let tokens_out = self
+            .bonding_curve
+            .calculate_tokens_out(usdt_amount - fee, self.circulating_supply)?;
+        self.buy_quote(fee_config, usdt_amount, tokens_out, fee, has_referrer)
+    }
+    
+    // Price a buy of exactly `token_amount` tokens (buy_exact_tokens)
+    pub fn quote_buy_exact_tokens(&self, fee_config: &FeeConfig, token_amount: u64, has_referrer: bool) -> Result<TradeQuote> {
+        let (buy_fee_bps, _) = self.fee_rates(fee_config);
+        let cost = self
+            .bonding_curve
+            .calculate_buy_cost(self.circulating_supply, token_amount)?;
+        let usdt_in = gross_up(cost, buy_fee_bps)?;
+        self.buy_quote(fee_config, usdt_in, token_amount, usdt_in - cost, has_referrer)
+    }
+    
+    // Price a sell of `token_amount` against the current curve state.
+    // sell_to_curve and quote_sell both go through here.
+    pub fn quote_sell(&self, fee_config: &FeeConfig, token_amount: u64, has_referrer: bool) -> Result<TradeQuote> {
+        let (_, sell_fee_bps) = self.fee_rates(fee_config);
+        let usdt_gross = self
+            .bonding_curve
+            .calculate_sell_return(self.circulating_supply, token_amount)?;
+        let usdt_out = usdt_gross - fee_on(usdt_gross, sell_fee_bps);
+        self.sell_quote(fee_config, token_amount, usdt_out, has_referrer)
+    }
+    
+    // Price a sell that pays out exactly `usdt_amount` after fees (sell_for_exact_usdt)
+    pub fn quote_sell_for_exact_usdt(&self, fee_config: &FeeConfig, usdt_amount: u64, has_referrer: bool) -> Result<TradeQuote> {
+        let (_, sell_fee_bps) = self.fee_rates(fee_config);
+        let usdt_gross = gross_up(usdt_amount, sell_fee_bps)?;
+        let tokens_in = self
+            .bonding_curve
+            .calculate_tokens_in(usdt_gross, self.circulating_supply)?;
+        self.sell_quote(fee_config, tokens_in, usdt_amount, has_referrer)
+    }
+    
+    fn buy_quote(
+        &self,
+        fee_config: &FeeConfig,
+        usdt_in: u64,
+        tokens_out: u64,
+        fee: u64,
+        has_referrer: bool,
+    ) -> Result<TradeQuote> {
+        let curve = &self.bonding_curve;
+        let new_supply = self
+            .circulating_supply
+            .checked_add(tokens_out)
+            .ok_or(XGrowthError::MathOverflow)?;
+        
+        // Check max supply
+        require!(new_supply <= curve.max_supply(), XGrowthError::MaxSupplyReached);
+        
+        let spot_price_before = curve.calculate_price(self.circulating_supply)?;
+        let spot_price_after = curve.calculate_price(new_supply)?;
+        let split = fee_config.split(fee, has_referrer);
+        
+        Ok(TradeQuote {
+            usdt_in,
+            tokens_out,
+            tokens_in: 0,
+            usdt_out: 0,
+            fee,
+            protocol_fee: split.protocol_fee,
+            creator_fee: split.creator_fee,
+            referrer_fee: split.referrer_fee,
+            price_impact_bps: TradeQuote::price_impact_bps(spot_price_before, spot_price_after),
+            spot_price_after,
+        })
+    }
+    
+    fn sell_quote(
+        &self,
+        fee_config: &FeeConfig,
+        tokens_in: u64,
+        usdt_out: u64,
+        has_referrer: bool,
+    ) -> Result<TradeQuote> {
+        let curve = &self.bonding_curve;
+        let usdt_gross = curve.calculate_sell_return(self.circulating_supply, tokens_in)?;
+        
+        // Check reserve has enough
+        require!(self.reserve_balance >= usdt_gross, XGrowthError::InsufficientReserve);
+        
+        let spot_price_before = curve.calculate_price(self.circulating_supply)?;
+        let spot_price_after = curve.calculate_price(self.circulating_supply - tokens_in)?;
+        let fee = usdt_gross - usdt_out;
+        let split = fee_config.split(fee, has_referrer);
+        
+        Ok(TradeQuote {
+            usdt_in: 0,
+            tokens_out: 0,
+            tokens_in,
+            usdt_out,
+            fee,
+            protocol_fee: split.protocol_fee,
+            creator_fee: split.creator_fee,
+            referrer_fee: split.referrer_fee,
+            price_impact_bps: TradeQuote::price_impact_bps(spot_price_before, spot_price_after),
+            spot_price_after,
+        })
+    }
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
-pub struct BondingCurveParams {
-    pub base_price: u64,      // Base price in USDT (with decimals)
-    pub curve_factor: u64,    // Multiplier for curve steepness
-    pub max_supply: u64,      // Maximum tokens that can be sold
+// Outcome of a curve trade, returned by quote_buy / quote_sell through return data
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TradeQuote {
+    pub usdt_in: u64,          // USDT paid by the buyer
+    pub tokens_out: u64,       // Tokens minted to the buyer
+    pub tokens_in: u64,        // Tokens burned from the seller
+    pub usdt_out: u64,         // USDT paid to the seller after fees
+    pub fee: u64,              // Fee charged in USDT, split into the three legs below
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    pub referrer_fee: u64,
+    pub price_impact_bps: u64, // Spot price move caused by the trade
+    pub spot_price_after: u64, // Spot price once the trade settles
 }
 
-impl BondingCurveParams {
-    pub const LEN: usize = 8 + 8 + 8;
-    
-    // Calculate price based on current supply
-    // Price = base_price * (1 + supply / max_supply)^2
-    pub fn calculate_price(&self, current_supply: u64) -> u64 {
-        let supply_ratio = (current_supply as u128 * 1_000_000) / self.max_supply as u128;
-        let price_multiplier = 1_000_000 + supply_ratio;
-        let squared = (price_multiplier * price_multiplier) / 1_000_000;
-        ((self.base_price as u128 * squared) / 1_000_000) as u64
-    }
-    
-    // Calculate tokens received for USDT amount
-    pub fn calculate_tokens_out(&self, usdt_amount: u64, current_supply: u64) -> u64 {
-        // Simplified calculation - in production use integral calculus
-        let avg_price = self.calculate_price(current_supply);
-        (usdt_amount * 1_000_000) / avg_price
+impl TradeQuote {
+    pub fn price_impact_bps(spot_price_before: u64, spot_price_after: u64) -> u64 {
+        if spot_price_before == 0 {
+            return 0;
+        }
+        let change = spot_price_before.abs_diff(spot_price_after) as u128;
+        (change * 10_000 / spot_price_before as u128).min(u64::MAX as u128) as u64
     }
 }
 
@@ -94,20 +633,91 @@ pub struct PerformanceMetrics {
     pub daily_views: u64,
     pub daily_comments: u64,
     pub daily_new_followers: u64,
+    
+    // Extended metric set, reported from MetricReport::V2 on. Informational: they feed
+    // daily_engagement_rate_bps and analytics, but ScoreWeights has no weight for them and
+    // calculate_score ignores them.
+    pub total_reposts: u64,
+    pub total_quotes: u64,
+    pub total_bookmarks: u64,
+    pub total_impressions: u64,
+    pub total_followers_lost: u64,
+    pub daily_reposts: u64,
+    pub daily_quotes: u64,
+    pub daily_bookmarks: u64,
+    pub daily_impressions: u64,
+    pub daily_followers_lost: u64,
 }
 
 impl PerformanceMetrics {
-    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 +
+        8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
     
-    // Calculate performance score
-    // Score = (Likes × 1.0) + (Views × 0.1) + (Comments × 2.0) + (Followers × 5.0)
-    pub fn calculate_score(&self) -> u64 {
-        let likes_score = self.daily_likes;
-        let views_score = self.daily_views / 10;
-        let comments_score = self.daily_comments * 2;
-        let followers_score = self.daily_new_followers * 5;
+    // Add one oracle report to the cumulative and daily metrics.
+    // Follower losses lower total_followers, which never goes below zero.
+    pub fn apply_report(&mut self, report: &MetricReport) -> Result<()> {
+        let values = report.values()?;
+        let [
+            likes, views, comments, new_followers,
+            reposts, quotes, bookmarks, impressions, followers_lost,
+        ] = values;
         
-        likes_score + views_score + comments_score + followers_score
+        let add = |total: u64, value: u64| total.checked_add(value).ok_or(XGrowthError::MathOverflow);
+        
+        self.total_likes = add(self.total_likes, likes)?;
+        self.total_views = add(self.total_views, views)?;
+        self.total_comments = add(self.total_comments, comments)?;
+        self.total_followers = add(self.total_followers, new_followers)?.saturating_sub(followers_lost);
+        self.total_reposts = add(self.total_reposts, reposts)?;
+        self.total_quotes = add(self.total_quotes, quotes)?;
+        self.total_bookmarks = add(self.total_bookmarks, bookmarks)?;
+        self.total_impressions = add(self.total_impressions, impressions)?;
+        self.total_followers_lost = add(self.total_followers_lost, followers_lost)?;
+        
+        self.daily_likes = add(self.daily_likes, likes)?;
+        self.daily_views = add(self.daily_views, views)?;
+        self.daily_comments = add(self.daily_comments, comments)?;
+        self.daily_new_followers = add(self.daily_new_followers, new_followers)?;
+        self.daily_reposts = add(self.daily_reposts, reposts)?;
+        self.daily_quotes = add(self.daily_quotes, quotes)?;
+        self.daily_bookmarks = add(self.daily_bookmarks, bookmarks)?;
+        self.daily_impressions = add(self.daily_impressions, impressions)?;
+        self.daily_followers_lost = add(self.daily_followers_lost, followers_lost)?;
+        Ok(())
+    }
+    
+    // Today's engagements (likes, comments, reposts, quotes, bookmarks) per impression,
+    // in basis points; 0 before any impression is reported
+    pub fn daily_engagement_rate_bps(&self) -> u64 {
+        if self.daily_impressions == 0 {
+            return 0;
+        }
+        let engagements = self.daily_likes as u128
+            + self.daily_comments as u128
+            + self.daily_reposts as u128
+            + self.daily_quotes as u128
+            + self.daily_bookmarks as u128;
+        let rate = engagements * BPS_DENOMINATOR as u128 / self.daily_impressions as u128;
+        rate.min(u64::MAX as u128) as u64
+    }
+    
+    // Calculate performance score from the daily metrics, each capped and damped first, rounded down
+    // Score = (Likes × likes) + (Views × views) + (Comments × comments) + (Followers × followers)
+    // Only these four V1 metrics are scored; the extended ones are informational.
+    pub fn calculate_score(&self, weights: &ScoreWeights, transforms: &ScoreTransforms) -> Result<u64> {
+        let weighted = [
+            (transforms.likes.apply(self.daily_likes), weights.likes),
+            (transforms.views.apply(self.daily_views), weights.views),
+            (transforms.comments.apply(self.daily_comments), weights.comments),
+            (transforms.followers.apply(self.daily_new_followers), weights.followers),
+        ]
+        .iter()
+        .try_fold(0u128, |total, (metric, weight)| {
+            total.checked_add(*metric as u128 * *weight as u128)
+        })
+        .ok_or(XGrowthError::MathOverflow)?;
+        u64::try_from(weighted / ScoreWeights::PRECISION as u128)
+            .map_err(|_| error!(XGrowthError::MathOverflow))
     }
     
     pub fn reset_daily_metrics(&mut self) {
@@ -115,6 +725,502 @@ impl PerformanceMetrics {
         self.daily_views = 0;
         self.daily_comments = 0;
         self.daily_new_followers = 0;
+        self.daily_reposts = 0;
+        self.daily_quotes = 0;
+        self.daily_bookmarks = 0;
+        self.daily_impressions = 0;
+        self.daily_followers_lost = 0;
+    }
+}
+
+// One oracle report: metric deltas in the order fixed by the report version
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct MetricReport {
+    pub version: u8,
+    pub values: Vec<u64>,
+}
+
+impl MetricReport {
+    // likes, views, comments, new_followers
+    pub const V1: u8 = 1;
+    
+    // V1, then reposts, quotes, bookmarks, impressions, followers_lost
+    pub const V2: u8 = 2;
+    
+    pub const MAX_METRICS: usize = 9;
+    
+    pub fn metric_count(version: u8) -> Option<usize> {
+        match version {
+            Self::V1 => Some(4),
+            Self::V2 => Some(Self::MAX_METRICS),
+            _ => None,
+        }
+    }
+    
+    // The report as a V2 vector, metrics an older version does not carry being 0
+    pub fn values(&self) -> Result<[u64; Self::MAX_METRICS]> {
+        let count = Self::metric_count(self.version).ok_or(XGrowthError::InvalidMetricReport)?;
+        require!(self.values.len() == count, XGrowthError::InvalidMetricReport);
+        let mut values = [0u64; Self::MAX_METRICS];
+        values[..count].copy_from_slice(&self.values);
+        Ok(values)
+    }
+}
+
+// Epoch of a report the oracle signed off-chain, so that any keeper can relay it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attestation {
+    pub epoch: u64, // the reward epoch the metrics count towards
+}
+
+impl Attestation {
+    // Domain separator, so that no other message signed by an oracle key passes as a report
+    pub const DOMAIN: &'static [u8] = b"x-growth:metric-attestation:v1";
+    
+    // The bytes the oracle signs: domain, program id, agent, epoch, report sequence number,
+    // then the report in its Borsh layout
+    pub fn message(&self, agent: &Pubkey, sequence: u64, report: &MetricReport) -> Vec<u8> {
+        let mut message = Vec::with_capacity(Self::DOMAIN.len() + 32 + 32 + 8 + 8 + 1 + 4 + 8 * report.values.len());
+        message.extend_from_slice(Self::DOMAIN);
+        message.extend_from_slice(crate::ID.as_ref());
+        message.extend_from_slice(agent.as_ref());
+        message.extend_from_slice(&self.epoch.to_le_bytes());
+        message.extend_from_slice(&sequence.to_le_bytes());
+        message.push(report.version);
+        message.extend_from_slice(&(report.values.len() as u32).to_le_bytes());
+        for value in &report.values {
+            message.extend_from_slice(&value.to_le_bytes());
+        }
+        message
+    }
+}
+
+// Sequence number of the last report applied to one agent. Each report must carry a higher one,
+// so a retried, duplicated or replayed report is never counted twice.
+#[account]
+pub struct ReportSequence {
+    pub agent: Pubkey,
+    pub last_sequence: u64,
+    pub bump: u8,
+}
+
+impl ReportSequence {
+    pub const LEN: usize = 8 + 32 + 8 + 1;
+}
+
+// Per-metric score weights in fixed point, ScoreWeights::PRECISION = 1.0
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreWeights {
+    pub likes: u64,
+    pub views: u64,
+    pub comments: u64,
+    pub followers: u64,
+}
+
+impl ScoreWeights {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+    
+    pub const PRECISION: u64 = 1_000;
+    
+    // Upper bound on any one weight (1000.0)
+    pub const MAX_WEIGHT: u64 = 1_000 * Self::PRECISION;
+    
+    // Likes 1.0, views 0.1, comments 2.0, followers 5.0
+    pub const DEFAULT: ScoreWeights = ScoreWeights {
+        likes: 1_000,
+        views: 100,
+        comments: 2_000,
+        followers: 5_000,
+    };
+    
+    pub fn validate(&self) -> Result<()> {
+        let weights = [self.likes, self.views, self.comments, self.followers];
+        require!(
+            weights.iter().all(|weight| *weight <= Self::MAX_WEIGHT),
+            XGrowthError::InvalidScoreWeights
+        );
+        require!(weights.iter().any(|weight| *weight > 0), XGrowthError::InvalidScoreWeights);
+        Ok(())
+    }
+}
+
+// How a daily metric is damped before it is weighted, so that one viral day or bought
+// engagement counts for less than the raw numbers
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Damping {
+    Linear,
+    Sqrt, // floor(sqrt(x))
+    Log,  // log2(1 + x) in thousandths, linear between powers of two
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MetricTransform {
+    pub damping: Damping,
+    pub cap: u64, // most of the metric counted per epoch, before damping; 0 for no cap
+}
+
+impl MetricTransform {
+    pub const LEN: usize = 1 + 8;
+    
+    pub const LINEAR: MetricTransform = MetricTransform { damping: Damping::Linear, cap: 0 };
+    
+    // Scale of Damping::Log output
+    pub const LOG_SCALE: u64 = 1_000;
+    
+    pub fn apply(&self, value: u64) -> u64 {
+        let value = if self.cap > 0 { value.min(self.cap) } else { value };
+        match self.damping {
+            Damping::Linear => value,
+            Damping::Sqrt => integer_sqrt(value),
+            Damping::Log => {
+                let x = value as u128 + 1;
+                let whole = 127 - x.leading_zeros() as u128;
+                let power = 1u128 << whole;
+                let fraction = (x - power) * Self::LOG_SCALE as u128 / power;
+                (whole * Self::LOG_SCALE as u128 + fraction) as u64
+            }
+        }
+    }
+}
+
+// floor(sqrt(value)), by Newton's method from a power of two above the root
+pub fn integer_sqrt(value: u64) -> u64 {
+    if value < 2 {
+        return value;
+    }
+    let mut root = 1u64 << (64 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (root + value / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+// Anti-gaming rules applied when an epoch is scored and paid
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ScoreTransforms {
+    pub likes: MetricTransform,
+    pub views: MetricTransform,
+    pub comments: MetricTransform,
+    pub followers: MetricTransform,
+    
+    // Most of an epoch's pool one agent can take; the excess goes to the other agents
+    pub max_agent_share_bps: u16,
+}
+
+impl ScoreTransforms {
+    pub const LEN: usize = MetricTransform::LEN * 4 + 2;
+    
+    // Lowest share limit, which bounds how many agents an epoch can cap
+    pub const MIN_AGENT_SHARE_BPS: u16 = 500;
+    
+    // Raw metrics and no share limit
+    pub const DEFAULT: ScoreTransforms = ScoreTransforms {
+        likes: MetricTransform::LINEAR,
+        views: MetricTransform::LINEAR,
+        comments: MetricTransform::LINEAR,
+        followers: MetricTransform::LINEAR,
+        max_agent_share_bps: BPS_DENOMINATOR as u16,
+    };
+    
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_agent_share_bps >= Self::MIN_AGENT_SHARE_BPS
+                && self.max_agent_share_bps as u64 <= BPS_DENOMINATOR,
+            XGrowthError::InvalidScoreTransforms
+        );
+        Ok(())
+    }
+}
+
+// Oracle keys allowed to report agent metrics. With a threshold of 1 any of them may call
+// update_performance directly; above that, reports go through submit_observation and the
+// per-metric median is accepted once `threshold` oracles have submitted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct OracleSet {
+    pub oracles: [Pubkey; OracleSet::MAX_ORACLES], // the first `count` are in use
+    pub count: u8,
+    pub threshold: u8,
+    
+    // Submissions further than this from the accepted median are reported as outliers
+    pub outlier_tolerance_bps: u16,
+    
+    // Bumped on every change; pending observations from an older set are discarded
+    pub version: u32,
+}
+
+impl OracleSet {
+    pub const MAX_ORACLES: usize = 10;
+    
+    pub const LEN: usize = 32 * Self::MAX_ORACLES + 1 + 1 + 2 + 4;
+    
+    pub const DEFAULT_OUTLIER_TOLERANCE_BPS: u16 = 2_000;
+    
+    pub fn new(oracles: &[Pubkey], threshold: u8, outlier_tolerance_bps: u16, version: u32) -> Result<Self> {
+        require!(
+            !oracles.is_empty() && oracles.len() <= Self::MAX_ORACLES,
+            XGrowthError::InvalidOracleSet
+        );
+        require!(
+            threshold > 0 && threshold as usize <= oracles.len(),
+            XGrowthError::InvalidOracleSet
+        );
+        for (index, oracle) in oracles.iter().enumerate() {
+            require!(
+                *oracle != Pubkey::default() && !oracles[..index].contains(oracle),
+                XGrowthError::InvalidOracleSet
+            );
+        }
+        
+        let mut set = OracleSet {
+            oracles: [Pubkey::default(); Self::MAX_ORACLES],
+            count: oracles.len() as u8,
+            threshold,
+            outlier_tolerance_bps,
+            version,
+        };
+        set.oracles[..oracles.len()].copy_from_slice(oracles);
+        Ok(set)
+    }
+    
+    pub fn members(&self) -> &[Pubkey] {
+        &self.oracles[..self.count as usize]
+    }
+    
+    pub fn contains(&self, key: &Pubkey) -> bool {
+        self.members().contains(key)
+    }
+    
+    // Whether `value` lies further than outlier_tolerance_bps from `median`
+    pub fn is_outlier(&self, value: u64, median: u64) -> bool {
+        value.abs_diff(median) as u128 * BPS_DENOMINATOR as u128
+            > median as u128 * self.outlier_tolerance_bps as u128
+    }
+}
+
+// Oracle submissions of one agent's metrics for one epoch, pending until the quorum is reached
+#[account]
+pub struct PendingObservation {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub oracle_set_version: u32,
+    pub report_version: u8, // MetricReport version of every submission in the round
+    pub submitters: [Pubkey; OracleSet::MAX_ORACLES],
+    pub values: [[u64; MetricReport::MAX_METRICS]; OracleSet::MAX_ORACLES], // as V2 vectors
+    pub submissions: u8,
+    pub accepted: bool,
+    pub accepted_values: [u64; MetricReport::MAX_METRICS], // per-metric median
+    pub bump: u8,
+}
+
+impl PendingObservation {
+    pub const LEN: usize = 8 + 32 + 8 + 4 + 1 +
+        32 * OracleSet::MAX_ORACLES +
+        8 * MetricReport::MAX_METRICS * OracleSet::MAX_ORACLES +
+        1 + 1 +
+        8 * MetricReport::MAX_METRICS +
+        1;
+    
+    // Add one oracle's report to the round
+    pub fn submit(&mut self, oracle: Pubkey, report: &MetricReport, oracle_set: &OracleSet) -> Result<()> {
+        require!(!self.accepted, XGrowthError::ObservationAlreadyAccepted);
+        let values = report.values()?;
+        
+        // Start over if the oracle set changed since the round began
+        if self.submissions == 0 || self.oracle_set_version != oracle_set.version {
+            self.submissions = 0;
+            self.oracle_set_version = oracle_set.version;
+            self.report_version = report.version;
+        }
+        require!(report.version == self.report_version, XGrowthError::InvalidMetricReport);
+        
+        let submitted = self.submissions as usize;
+        require!(
+            !self.submitters[..submitted].contains(&oracle),
+            XGrowthError::DuplicateObservation
+        );
+        self.submitters[submitted] = oracle;
+        self.values[submitted] = values;
+        self.submissions += 1;
+        Ok(())
+    }
+    
+    // Median of each metric over the submissions; the mean of the middle two, rounded down,
+    // for an even count
+    pub fn median(&self) -> [u64; MetricReport::MAX_METRICS] {
+        let submitted = self.submissions as usize;
+        let mut median = [0u64; MetricReport::MAX_METRICS];
+        for (metric, value) in median.iter_mut().enumerate() {
+            let mut column = [0u64; OracleSet::MAX_ORACLES];
+            for (slot, values) in column.iter_mut().zip(&self.values[..submitted]) {
+                *slot = values[metric];
+            }
+            let column = &mut column[..submitted];
+            column.sort_unstable();
+            *value = if submitted % 2 == 1 {
+                column[submitted / 2]
+            } else {
+                ((column[submitted / 2 - 1] as u128 + column[submitted / 2] as u128) / 2) as u64
+            };
+        }
+        median
+    }
+}
+
+// One reward epoch: agents' scores are recorded, the epoch is finalized with
+// the total score, then each agent is credited its share of reward_pool
+#[account]
+pub struct RewardEpoch {
+    pub epoch: u64,
+    pub reward_pool: u64, // daily_reward_pool when the epoch began
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_score: u64,
+    pub agents_recorded: u64,
+    pub finalized: bool,
+    pub agents_credited: u64,
+    pub total_credited: u64,
+    pub bump: u8,
+    pub score_weights: ScoreWeights, // platform weights when the epoch began
+    pub score_transforms: ScoreTransforms, // platform transforms when the epoch began
+    
+    // Highest recorded scores, descending; enough to find every agent the share limit caps
+    pub top_scores: [u64; RewardEpoch::TOP_SCORES],
+    
+    // Share limit settled at finalize: agents scoring at least capped_score_threshold are
+    // credited share_cap, the others split uncapped_pool by uncapped_score
+    pub share_cap: u64,
+    pub capped_agents: u64,
+    pub capped_score_threshold: u64,
+    pub uncapped_pool: u64,
+    pub uncapped_score: u64,
+    
+    // Set at finalize: scores can be disputed until dispute_end, and agents are credited after it
+    pub dispute_end: i64,
+    pub open_disputes: u64,
+}
+
+impl RewardEpoch {
+    // Capped agents each take more than 1 / TOP_SCORES of the pool, so fewer than TOP_SCORES are capped
+    pub const TOP_SCORES: usize = (BPS_DENOMINATOR / ScoreTransforms::MIN_AGENT_SHARE_BPS as u64) as usize;
+    
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 8 + 1 + ScoreWeights::LEN +
+        ScoreTransforms::LEN +
+        8 * Self::TOP_SCORES +
+        8 + 8 + 8 + 8 + 8 + // share limit
+        8 + 8; // disputes
+    
+    // Rewards the epoch pays out; nothing when no agent scored
+    pub fn allocation(&self) -> u64 {
+        if self.total_score == 0 { 0 } else { self.reward_pool }
+    }
+    
+    // Add one agent's score to the epoch
+    pub fn record_score(&mut self, score: u64) -> Result<()> {
+        self.total_score = self.total_score
+            .checked_add(score)
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.agents_recorded += 1;
+        
+        if let Some(rank) = self.top_scores.iter().position(|top| score > *top) {
+            self.top_scores.copy_within(rank..Self::TOP_SCORES - 1, rank + 1);
+            self.top_scores[rank] = score;
+        }
+        Ok(())
+    }
+    
+    // Apply the share limit once every score is in. Highest scorers are capped one at a time
+    // while their pro rata share of what the cap leaves exceeds the cap; equal scores are
+    // always capped together, so the capped agents are exactly those at or above the threshold.
+    pub fn settle_share_cap(&mut self) -> Result<()> {
+        let pool = self.allocation();
+        self.share_cap = pool;
+        self.capped_agents = 0;
+        self.capped_score_threshold = 0;
+        self.uncapped_pool = pool;
+        self.uncapped_score = self.total_score;
+        
+        let max_share_bps = self.score_transforms.max_agent_share_bps as u64;
+        if pool == 0 || max_share_bps >= BPS_DENOMINATOR {
+            return Ok(());
+        }
+        
+        // Rounded up, so that the bound on capped agents holds for any pool
+        let cap = (pool as u128 * max_share_bps as u128).div_ceil(BPS_DENOMINATOR as u128) as u64;
+        self.share_cap = cap;
+        
+        for score in self.top_scores {
+            if score == 0 {
+                break;
+            }
+            let share_over_cap = self.uncapped_pool as u128 * score as u128
+                > cap as u128 * self.uncapped_score as u128;
+            if !share_over_cap {
+                break;
+            }
+            self.capped_agents += 1;
+            self.capped_score_threshold = score;
+            self.uncapped_pool -= cap;
+            self.uncapped_score -= score;
+        }
+        Ok(())
+    }
+    
+    // The agent's credit: share_cap if capped, else uncapped_pool * score / uncapped_score,
+    // rounded down so credits never exceed the pool
+    pub fn reward_share(&self, score: u64) -> Result<u64> {
+        if self.capped_agents > 0 && score >= self.capped_score_threshold {
+            return Ok(self.share_cap);
+        }
+        if self.uncapped_score == 0 {
+            return Ok(0);
+        }
+        let share = self.uncapped_pool as u128 * score as u128 / self.uncapped_score as u128;
+        u64::try_from(share).map_err(|_| error!(XGrowthError::MathOverflow))
+    }
+    
+    // Count one agent's credit, by the crank or by a merkle root. Once every agent is credited,
+    // returns the rounding dust, and any excess no uncapped agent could take, to release back
+    // to the pool.
+    pub fn credit(&mut self, amount: u64) -> Result<u64> {
+        self.agents_credited += 1;
+        self.total_credited = self.total_credited
+            .checked_add(amount)
+            .ok_or(XGrowthError::MathOverflow)?;
+        if self.agents_credited < self.agents_recorded {
+            return Ok(0);
+        }
+        self.allocation()
+            .checked_sub(self.total_credited)
+            .ok_or(error!(XGrowthError::MathOverflow))
+    }
+}
+
+// A disputed score of one agent for one epoch. The agent is not credited until the dispute is
+// resolved with corrected metrics, or lapses unresolved at resolve_by.
+#[account]
+pub struct ScoreDispute {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub challenger: Pubkey,
+    pub opened_at: i64,
+    pub recorded_score: u64,
+    pub resolved: bool,
+    pub resolved_at: i64,
+    pub corrected_values: [u64; MetricReport::MAX_METRICS], // the epoch's metrics, as a V2 vector
+    pub corrected_score: u64,
+    pub bump: u8,
+    pub resolve_by: i64,
+}
+
+impl ScoreDispute {
+    pub const LEN: usize = 8 + 32 + 8 + 32 + 8 + 8 + 1 + 8 + 8 * MetricReport::MAX_METRICS + 8 + 1 + 8;
+    
+    // Left unresolved past its deadline; the agent is credited on its recorded score
+    pub fn lapsed(&self, now: i64) -> bool {
+        !self.resolved && now >= self.resolve_by
     }
 }
 
@@ -126,8 +1232,315 @@ pub struct UserRewards {
     pub claimed_rewards: u64,
     pub last_claim: i64,
     pub bump: u8,
+    
+    // Staked agent tokens this user earns on, and the rewards already accounted for them
+    pub shares: u64,
+    pub reward_debt: u128,
+    
+    // Expiry: pending_rewards last grew at last_accrual
+    pub last_accrual: i64,
+    pub expired_rewards: u64,
+    
+    // Position in each of the agent's reward streams, by stream index
+    pub streams: [StreamPosition; RewardStream::MAX_PER_AGENT],
 }
 
 impl UserRewards {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1 + 8 + 16 + 8 + 8 +
+        StreamPosition::LEN * RewardStream::MAX_PER_AGENT;
+    
+    // Fill in a freshly created (init_if_needed) account
+    pub fn open(&mut self, user: Pubkey, agent: Pubkey, bump: u8) {
+        if self.user == Pubkey::default() {
+            self.user = user;
+            self.agent = agent;
+            self.bump = bump;
+        }
+    }
+    
+    // Move rewards accrued since the last settlement into pending_rewards.
+    // Must run before `shares` changes. The agent's accumulator last moved no later than its
+    // vesting end, so that is when the rewards accrued, however late they are settled.
+    // Vested rewards must have been released first.
+    pub fn settle(&mut self, agent: &Agent) -> Result<u64> {
+        let accrued = agent
+            .accumulated_rewards(self.shares)?
+            .saturating_sub(self.reward_debt);
+        let accrued = u64::try_from(accrued).map_err(|_| error!(XGrowthError::MathOverflow))?;
+        self.pending_rewards = self.pending_rewards
+            .checked_add(accrued)
+            .ok_or(XGrowthError::MathOverflow)?;
+        if accrued > 0 {
+            let accrued_at = Clock::get()?.unix_timestamp.min(agent.reward_vesting_end);
+            self.last_accrual = self.last_accrual.max(accrued_at);
+        }
+        self.reward_debt = agent.accumulated_rewards(self.shares)?;
+        Ok(accrued)
+    }
+    
+    // Move stream rewards accrued since the last settlement into the stream's pending balance.
+    // The stream must be updated first and `shares` must not have changed since.
+    pub fn settle_stream(&mut self, stream: &mut RewardStream) -> Result<u64> {
+        let accumulated = stream.accumulated_rewards(self.shares)?;
+        let position = &mut self.streams[stream.index as usize];
+        let accrued = accumulated.saturating_sub(position.reward_debt);
+        let accrued = u64::try_from(accrued).map_err(|_| error!(XGrowthError::MathOverflow))?;
+        position.pending = position.pending
+            .checked_add(accrued)
+            .ok_or(XGrowthError::MathOverflow)?;
+        position.reward_debt = accumulated;
+        stream.total_settled = stream.total_settled
+            .checked_add(accrued)
+            .ok_or(XGrowthError::MathOverflow)?;
+        Ok(accrued)
+    }
+    
+    // Restart a stream's debt from the current shares, after they changed
+    pub fn reset_stream_debt(&mut self, stream: &RewardStream) -> Result<()> {
+        self.streams[stream.index as usize].reward_debt = stream.accumulated_rewards(self.shares)?;
+        Ok(())
+    }
+    
+    // Whether pending_rewards have sat unclaimed past the claim window
+    pub fn rewards_expired(&self, claim_window: i64, now: i64) -> bool {
+        claim_window > 0 && now >= self.last_accrual.saturating_add(claim_window)
+    }
+    
+    // Settle, then change shares and keep the agent total in step
+    pub fn update_shares(&mut self, agent: &mut Agent, shares: u64) -> Result<()> {
+        self.settle(agent)?;
+        agent.total_shares = agent.total_shares
+            .checked_sub(self.shares)
+            .and_then(|total| total.checked_add(shares))
+            .ok_or(XGrowthError::MathOverflow)?;
+        self.shares = shares;
+        self.reward_debt = agent.accumulated_rewards(shares)?;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct StreamPosition {
+    pub reward_debt: u128,
+    pub pending: u64,
+    pub claimed: u64,
+}
+
+impl StreamPosition {
+    pub const LEN: usize = 16 + 8 + 8;
+}
+
+// Creator funded bonus rewards in any mint, emitted per second to the agent's stakers
+#[account]
+pub struct RewardStream {
+    pub agent: Pubkey,
+    pub index: u8,
+    pub mint: Pubkey,
+    pub vault_bump: u8, // reward token vault, owned by this stream PDA
+    pub emission_rate: u64, // reward base units per second
+    pub start_time: i64,
+    pub end_time: i64,
+    pub last_update_time: i64,
+    pub acc_reward_per_share: u128, // scaled by Agent::ACC_REWARD_PRECISION
+    pub total_funded: u64,
+    pub total_emitted: u64,
+    pub total_settled: u64, // moved into stakers' pending balances, claimed or not
+    pub total_claimed: u64,
+    pub total_refunded: u64, // returned to the creator once the stream ended
+    pub ended: bool,
+    pub bump: u8,
+}
+
+impl RewardStream {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 1 + 8 + 8 + 8 + 8 + 16 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+    
+    // Streams an agent can ever create; indexes are never reused
+    pub const MAX_PER_AGENT: usize = 8;
+    
+    // Emit from last_update_time to now (capped at end_time) over the current stakers.
+    // Nothing is emitted while nobody is staked or once the funding is used up.
+    pub fn update(&mut self, total_shares: u64, now: i64) -> Result<()> {
+        let until = now.min(self.end_time);
+        if until <= self.last_update_time {
+            return Ok(());
+        }
+        if total_shares > 0 {
+            let elapsed = (until - self.last_update_time) as u128;
+            let available = (self.total_funded - self.total_emitted) as u128;
+            let emitted = (self.emission_rate as u128)
+                .checked_mul(elapsed)
+                .ok_or(XGrowthError::MathOverflow)?
+                .min(available);
+            self.acc_reward_per_share = self.acc_reward_per_share
+                .checked_add(emitted * Agent::ACC_REWARD_PRECISION / total_shares as u128)
+                .ok_or(XGrowthError::MathOverflow)?;
+            self.total_emitted += emitted as u64;
+        }
+        self.last_update_time = until;
+        Ok(())
+    }
+    
+    // Upper bound on what stakers can still claim. While anyone is staked that is everything
+    // emitted and not yet claimed. Once nobody is, every position has been settled, so only
+    // their pending balances are owed and the accumulator's rounding dust is not.
+    pub fn owed(&self, total_shares: u64) -> u64 {
+        let owed_from = if total_shares == 0 { self.total_settled } else { self.total_emitted };
+        owed_from.saturating_sub(self.total_claimed)
+    }
+    
+    // Rewards accumulated by `shares` since the stream started
+    pub fn accumulated_rewards(&self, shares: u64) -> Result<u128> {
+        (shares as u128)
+            .checked_mul(self.acc_reward_per_share)
+            .map(|value| value / Agent::ACC_REWARD_PRECISION)
+            .ok_or_else(|| error!(XGrowthError::MathOverflow))
+    }
+}
+
+#[account]
+pub struct MerkleDistribution {
+    pub agent: Pubkey,
+    pub epoch: u64,
+    pub root: [u8; 32], // over crate::merkle::leaf_hash leaves
+    pub total_amount: u64,
+    pub total_claimed: u64,
+    pub num_leaves: u32,
+    pub bump: u8,
+    pub claimed: Vec<u8>, // one bit per leaf index
+}
+
+impl MerkleDistribution {
+    // Keeps the account within the 10 KiB a program can allocate
+    pub const MAX_LEAVES: u32 = 65_536;
+    
+    pub fn space(num_leaves: u32) -> usize {
+        8 + 32 + 8 + 32 + 8 + 8 + 4 + 1 +
+        (4 + Self::bitmap_len(num_leaves))
+    }
+    
+    pub fn bitmap_len(num_leaves: u32) -> usize {
+        (num_leaves as usize).div_ceil(8)
+    }
+    
+    pub fn is_claimed(&self, index: u32) -> bool {
+        self.claimed[index as usize / 8] & (1 << (index % 8)) != 0
+    }
+    
+    pub fn set_claimed(&mut self, index: u32) {
+        self.claimed[index as usize / 8] |= 1 << (index % 8);
+    }
+}
+
+#[account]
+pub struct UserStake {
+    pub user: Pubkey,
+    pub agent: Pubkey,
+    pub amount: u64, // staked, earning rewards except for warming_amount
+    pub start_time: i64, // when the current position was opened
+    pub unbonding_amount: u64, // unstaked, withdrawable at unbonding_end
+    pub unbonding_end: i64,
+    pub bump: u8,
+    
+    // Stake sits out the epoch it was staked in: warming_amount starts earning once the agent
+    // has been credited for warming_epoch
+    pub warming_amount: u64,
+    pub warming_epoch: u64,
+}
+
+impl UserStake {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 8;
+    
+    // Whether the warming stake may start earning
+    pub fn warmed_up(&self, agent: &Agent) -> bool {
+        self.warming_amount > 0 && agent.last_credited_epoch >= self.warming_epoch
+    }
+}
+
+// Layouts written by the original program, before fees, staking, reward epochs and the oracle
+// set. They are only read by migrate_platform and migrate_agent.
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyPlatform {
+    pub authority: Pubkey,
+    pub daily_reward_pool: u64,
+    pub total_agents: u64,
+    pub usdt_mint: Pubkey,
+    pub oracle: Pubkey,
+    pub bump: u8,
+}
+
+impl LegacyPlatform {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 32 + 32 + 1;
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegacyAgent {
+    pub agent_id: String,
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    
+    // Priced as a QuadraticCurve with curve_factor = FACTOR_PRECISION, whatever the stored factor
+    pub bonding_curve: QuadraticCurve,
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub reserve_balance: u64,
+    
+    pub performance: LegacyPerformanceMetrics,
+    
+    pub total_rewards_earned: u64,
+    pub last_reward_distribution: i64,
+    
+    pub bump: u8,
+}
+
+impl LegacyAgent {
+    pub const LEN: usize = 8 + 
+        (4 + 32) + // agent_id
+        32 + // authority
+        32 + // token_mint
+        (4 + 64) + // name
+        (4 + 16) + // symbol
+        (4 + 200) + // uri
+        QuadraticCurve::LEN +
+        8 + 8 + 8 + // supplies and balance
+        LegacyPerformanceMetrics::LEN +
+        8 + 8 + // rewards
+        1; // bump
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct LegacyPerformanceMetrics {
+    pub total_likes: u64,
+    pub total_views: u64,
+    pub total_comments: u64,
+    pub total_followers: u64,
+    pub last_updated: i64,
+    pub daily_likes: u64,
+    pub daily_views: u64,
+    pub daily_comments: u64,
+    pub daily_new_followers: u64,
+}
+
+impl LegacyPerformanceMetrics {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8;
+}
+
+impl From<LegacyPerformanceMetrics> for PerformanceMetrics {
+    fn from(legacy: LegacyPerformanceMetrics) -> Self {
+        PerformanceMetrics {
+            total_likes: legacy.total_likes,
+            total_views: legacy.total_views,
+            total_comments: legacy.total_comments,
+            total_followers: legacy.total_followers,
+            last_updated: legacy.last_updated,
+            daily_likes: legacy.daily_likes,
+            daily_views: legacy.daily_views,
+            daily_comments: legacy.daily_comments,
+            daily_new_followers: legacy.daily_new_followers,
+            ..Default::default()
+        }
+    }
 }
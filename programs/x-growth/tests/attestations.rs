@@ -0,0 +1,175 @@
+// Oracle-signed metric reports relayed by any keeper through the Ed25519 precompile
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use common::*;
+use ed25519_dalek::Keypair;
+use x_growth::errors::XGrowthError;
+use x_growth::events::AttestationRelayed;
+use x_growth::state::{Attestation, MetricReport, Platform};
+
+fn likes(likes: u64) -> MetricReport {
+    MetricReport { version: MetricReport::V1, values: vec![likes, 0, 0, 0] }
+}
+
+// A fixture whose oracle set also holds an off-chain signing key, and a funded keeper
+fn signing_oracle() -> (Fixture, Keypair, Pubkey) {
+    let mut fixture = Fixture::new();
+    let signer = keypair(7);
+    let oracle = fixture.oracle;
+    fixture.set_oracle_set(&[oracle, keypair_pubkey(&signer)], 1, 1_000).unwrap();
+    let keeper = Pubkey::new_unique();
+    fixture.bank.fund(keeper, 1_000_000_000);
+    (fixture, signer, keeper)
+}
+
+// `report` signed by `signer` for `agent`, relayed by `keeper` as `signer`'s report
+fn relay(
+    fixture: &mut Fixture,
+    keeper: Pubkey,
+    signer: &Keypair,
+    agent: Pubkey,
+    report: MetricReport,
+    sequence: u64,
+    attestation: Attestation,
+) -> std::result::Result<(), ProgramError> {
+    let signed = ed25519_instruction(signer, &attestation.message(&agent, sequence, &report));
+    let accounts = fixture.update_performance_accounts(agent, keypair_pubkey(signer), keeper);
+    fixture.relay_metrics(accounts, report, sequence, attestation, signed)
+}
+
+#[test]
+fn any_keeper_can_relay_a_signed_report_once() {
+    let (mut fixture, signer, keeper) = signing_oracle();
+    let agent = fixture.agent;
+    let attestation = Attestation { epoch: 1 };
+    
+    relay(&mut fixture, keeper, &signer, agent, likes(100), 1, attestation).unwrap();
+    assert_eq!(fixture.agent_state().performance.total_likes, 100);
+    let relayed = fixture.bank.events::<AttestationRelayed>();
+    assert_eq!(relayed.len(), 1);
+    assert_eq!(relayed[0].oracle, keypair_pubkey(&signer));
+    assert_eq!(relayed[0].relayer, keeper);
+    assert_eq!(relayed[0].sequence, 1);
+    
+    // The keeper paid for the sequence account, the oracle signed nothing on-chain
+    let report_sequence: x_growth::state::ReportSequence =
+        fixture.bank.anchor_account(&Fixture::report_sequence_pda(&agent));
+    assert_eq!(report_sequence.agent, agent);
+    assert_eq!(report_sequence.last_sequence, 1);
+    assert!(fixture.bank.accounts[&keeper].lamports < 1_000_000_000);
+    
+    // Neither the same signed report nor an older one is accepted again
+    assert_eq!(
+        relay(&mut fixture, keeper, &signer, agent, likes(100), 1, attestation),
+        Err(custom_error(XGrowthError::StaleReport))
+    );
+    relay(&mut fixture, keeper, &signer, agent, likes(50), 5, attestation).unwrap();
+    assert_eq!(
+        relay(&mut fixture, keeper, &signer, agent, likes(50), 3, attestation),
+        Err(custom_error(XGrowthError::StaleReport))
+    );
+    assert_eq!(fixture.agent_state().performance.total_likes, 150);
+}
+
+#[test]
+fn the_relayed_report_must_be_the_signed_one() {
+    let (mut fixture, signer, keeper) = signing_oracle();
+    let agent = fixture.agent;
+    let oracle = keypair_pubkey(&signer);
+    let attestation = Attestation { epoch: 1 };
+    let message = attestation.message(&agent, 1, &likes(100));
+    let accounts = |fixture: &Fixture, agent| fixture.update_performance_accounts(agent, oracle, keeper);
+    
+    // A tampered report, sequence number or agent no longer matches the verified message
+    let signed = ed25519_instruction(&signer, &message);
+    assert_eq!(
+        fixture.relay_metrics(accounts(&fixture, agent), likes(1_000), 1, attestation, signed.clone()),
+        Err(custom_error(XGrowthError::InvalidAttestation))
+    );
+    assert_eq!(
+        fixture.relay_metrics(accounts(&fixture, agent), likes(100), 2, attestation, signed.clone()),
+        Err(custom_error(XGrowthError::InvalidAttestation))
+    );
+    let other = fixture.add_agent("other-agent", Pubkey::new_unique());
+    assert_eq!(
+        fixture.relay_metrics(accounts(&fixture, other), likes(100), 1, attestation, signed),
+        Err(custom_error(XGrowthError::InvalidAttestation))
+    );
+    
+    // The message must be signed by the oracle the report is attributed to
+    let impostor = keypair(8);
+    let forged = ed25519_instruction(&impostor, &message);
+    assert_eq!(
+        fixture.relay_metrics(accounts(&fixture, agent), likes(100), 1, attestation, forged),
+        Err(custom_error(XGrowthError::InvalidAttestation))
+    );
+    assert_eq!(
+        relay(&mut fixture, keeper, &impostor, agent, likes(100), 1, attestation),
+        Err(custom_error(XGrowthError::UnauthorizedOracle))
+    );
+    
+    // Without the Ed25519 verification in the transaction there is nothing to check against
+    assert_eq!(
+        fixture.bank.process(
+            accounts(&fixture, agent),
+            x_growth::instruction::UpdatePerformance { report: likes(100), sequence: 1, attestation: Some(attestation) },
+        ),
+        Err(custom_error(XGrowthError::InvalidAttestation))
+    );
+    
+    // Nor can a keeper report without an attestation, unless it is the oracle itself
+    assert_eq!(
+        fixture.bank.process(
+            accounts(&fixture, agent),
+            x_growth::instruction::UpdatePerformance { report: likes(100), sequence: 1, attestation: None },
+        ),
+        Err(ProgramError::Custom(ErrorCode::AccountNotSigner.into()))
+    );
+    assert_eq!(fixture.agent_state().performance.total_likes, 0);
+}
+
+#[test]
+fn attestations_must_name_the_epoch_being_counted() {
+    let (mut fixture, signer, keeper) = signing_oracle();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.begin_epoch().unwrap();
+    let relay_for = |fixture: &mut Fixture, epoch: u64| {
+        let sequence = fixture.next_report_sequence(&agent);
+        relay(fixture, keeper, &signer, agent, likes(100), sequence, Attestation { epoch })
+    };
+    
+    // Epoch 1 is running: reports count towards it, not the next one
+    assert_eq!(relay_for(&mut fixture, 2), Err(custom_error(XGrowthError::AttestationEpochMismatch)));
+    assert_eq!(relay_for(&mut fixture, 0), Err(custom_error(XGrowthError::AttestationEpochMismatch)));
+    relay_for(&mut fixture, 1).unwrap();
+    
+    // Once the agent is scored for epoch 1, a report held back for it cannot land in epoch 2
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    assert_eq!(relay_for(&mut fixture, 1), Err(custom_error(XGrowthError::AttestationEpochMismatch)));
+    relay_for(&mut fixture, 2).unwrap();
+    assert_eq!(fixture.agent_state().performance.daily_likes, 100);
+    
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(agent, 1).unwrap();
+    fixture.begin_epoch().unwrap();
+    assert_eq!(relay_for(&mut fixture, 3), Err(custom_error(XGrowthError::AttestationEpochMismatch)));
+    relay_for(&mut fixture, 2).unwrap();
+    assert_eq!(fixture.agent_state().performance.daily_likes, 200);
+}
+
+#[test]
+fn relayed_reports_still_need_a_single_oracle_threshold() {
+    let (mut fixture, signer, keeper) = signing_oracle();
+    let agent = fixture.agent;
+    let oracle = fixture.oracle;
+    fixture.set_oracle_set(&[oracle, keypair_pubkey(&signer)], 2, 1_000).unwrap();
+    
+    assert_eq!(
+        relay(&mut fixture, keeper, &signer, agent, likes(100), 1, Attestation { epoch: 1 }),
+        Err(custom_error(XGrowthError::QuorumRequired))
+    );
+}
@@ -0,0 +1,1283 @@
+// In-process harness for the integration tests.
+// Runs the program entrypoint against an in-memory account store, with CPIs routed
+// to the real SPL Token processor and a minimal system program.
+#![allow(dead_code)]
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    ed25519_program,
+    program_stubs::{self, SyscallStubs},
+    system_program,
+    sysvar::{
+        self,
+        instructions::{self, BorrowedAccountMeta, BorrowedInstruction},
+    },
+};
+use anchor_lang::{AccountSerialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer, Verifier};
+use x_growth::errors::XGrowthError;
+
+thread_local! {
+    static UNIX_TIMESTAMP: Cell<i64> = const { Cell::new(1_700_000_000) };
+    static RETURN_DATA: RefCell<Option<(Pubkey, Vec<u8>)>> = const { RefCell::new(None) };
+    static EVENTS: RefCell<Vec<Vec<u8>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub fn set_unix_timestamp(unix_timestamp: i64) {
+    UNIX_TIMESTAMP.with(|ts| ts.set(unix_timestamp));
+}
+
+pub fn unix_timestamp() -> i64 {
+    UNIX_TIMESTAMP.with(|ts| ts.get())
+}
+
+// Per-holder reward math rounds, so payouts may be off by a few base units
+pub fn assert_close(actual: u64, expected: u64) {
+    assert!(
+        actual.abs_diff(expected) <= 2,
+        "expected {} to be within 2 of {}",
+        actual,
+        expected
+    );
+}
+
+pub fn custom_error(error: XGrowthError) -> ProgramError {
+    ProgramError::Custom(error.into())
+}
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        EVENTS.with(|events| events.borrow_mut().push(fields.concat()));
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = Clock {
+            unix_timestamp: unix_timestamp(),
+            ..Clock::default()
+        };
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|r| *r.borrow_mut() = Some((x_growth::ID, data.to_vec())));
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        RETURN_DATA.with(|r| r.borrow().clone())
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        // PDAs the calling program may sign for
+        let mut pda_signers = Vec::new();
+        for seeds in signers_seeds {
+            pda_signers.push(
+                Pubkey::create_program_address(seeds, &x_growth::ID)
+                    .map_err(|_| ProgramError::InvalidSeeds)?,
+            );
+        }
+
+        let mut infos = Vec::new();
+        for meta in &instruction.accounts {
+            let mut info = account_infos
+                .iter()
+                .find(|info| *info.key == meta.pubkey)
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            info.is_signer = info.is_signer || pda_signers.contains(&meta.pubkey);
+            if meta.is_signer && !info.is_signer {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            infos.push(info);
+        }
+
+        if instruction.program_id == spl_token::ID {
+            spl_token::processor::Processor::process(&spl_token::ID, &infos, &instruction.data)
+        } else if instruction.program_id == system_program::ID {
+            process_system_instruction(&infos, &instruction.data)
+        } else {
+            Err(ProgramError::IncorrectProgramId)
+        }
+    }
+}
+
+// CreateAccount and Transfer, the only system instructions the program uses.
+// Anything else, like Anchor re-initializing an existing account, fails as it would on-chain.
+fn process_system_instruction(infos: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let tag = u32::from_le_bytes(data[0..4].try_into().unwrap());
+    if tag != 0 && tag != 2 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let lamports = u64::from_le_bytes(data[4..12].try_into().unwrap());
+    let (from, to) = (&infos[0], &infos[1]);
+    if from.lamports() < lamports {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    match tag {
+        0 => {
+            let space = u64::from_le_bytes(data[12..20].try_into().unwrap()) as usize;
+            let owner = Pubkey::try_from(&data[20..52]).unwrap();
+            if to.lamports() > 0 || !to.data_is_empty() || !to.is_signer {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            **from.try_borrow_mut_lamports()? -= lamports;
+            **to.try_borrow_mut_lamports()? += lamports;
+            *to.try_borrow_mut_data()? = Box::leak(vec![0u8; space].into_boxed_slice());
+            to.assign(&owner);
+            Ok(())
+        }
+        2 => {
+            **from.try_borrow_mut_lamports()? -= lamports;
+            **to.try_borrow_mut_lamports()? += lamports;
+            Ok(())
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+#[derive(Clone)]
+pub struct StoredAccount {
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub owner: Pubkey,
+    pub executable: bool,
+}
+
+// Minimal in-memory bank: every instruction sees the committed accounts and only
+// commits its own writes when it succeeds.
+pub struct Bank {
+    pub accounts: HashMap<Pubkey, StoredAccount>,
+}
+
+impl Bank {
+    pub fn new() -> Self {
+        program_stubs::set_syscall_stubs(Box::new(Stubs));
+
+        let mut bank = Bank { accounts: HashMap::new() };
+        for program in [x_growth::ID, spl_token::ID, associated_token::ID, system_program::ID] {
+            bank.accounts.insert(program, StoredAccount {
+                lamports: 1,
+                data: vec![],
+                owner: Pubkey::default(),
+                executable: true,
+            });
+        }
+
+        // Rent sysvar, bincode layout
+        let rent = Rent::default();
+        let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        bank.accounts.insert(sysvar::rent::ID, StoredAccount {
+            lamports: 1,
+            data: rent_data,
+            owner: sysvar::ID,
+            executable: false,
+        });
+        bank
+    }
+
+    pub fn fund(&mut self, key: Pubkey, lamports: u64) {
+        self.accounts.insert(key, StoredAccount {
+            lamports,
+            data: vec![],
+            owner: system_program::ID,
+            executable: false,
+        });
+    }
+
+    pub fn create_mint(&mut self, key: Pubkey, authority: Pubkey, decimals: u8) {
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            mint_authority: Some(authority).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(key, data, spl_token::ID);
+    }
+
+    pub fn create_token_account(&mut self, key: Pubkey, mint: Pubkey, owner: Pubkey, amount: u64) {
+        let mut data = vec![0u8; spl_token::state::Account::LEN];
+        spl_token::state::Account {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        self.set_account(key, data, spl_token::ID);
+    }
+
+    // Creates `owner`'s associated token account for `mint`
+    pub fn create_ata(&mut self, mint: Pubkey, owner: Pubkey, amount: u64) -> Pubkey {
+        let key = get_associated_token_address(&owner, &mint);
+        self.create_token_account(key, mint, owner, amount);
+        key
+    }
+
+    pub fn set_account(&mut self, key: Pubkey, data: Vec<u8>, owner: Pubkey) {
+        self.accounts.insert(key, StoredAccount {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner,
+            executable: false,
+        });
+    }
+
+    pub fn set_anchor_account<T: AccountSerialize>(&mut self, key: Pubkey, account: &T, space: usize) {
+        let mut data = Vec::with_capacity(space);
+        account.try_serialize(&mut data).unwrap();
+        data.resize(space, 0);
+        self.set_account(key, data, x_growth::ID);
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        spl_token::state::Account::unpack(&self.accounts[key].data).unwrap().amount
+    }
+
+    pub fn set_token_balance(&mut self, key: &Pubkey, amount: u64) {
+        let account = self.accounts.get_mut(key).unwrap();
+        let mut state = spl_token::state::Account::unpack(&account.data).unwrap();
+        state.amount = amount;
+        state.pack_into_slice(&mut account.data);
+    }
+
+    pub fn anchor_account<T: AccountDeserialize>(&self, key: &Pubkey) -> T {
+        T::try_deserialize(&mut self.accounts[key].data.as_slice()).unwrap()
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts.get(key).is_some_and(|account| account.lamports > 0)
+    }
+
+    // Runs one instruction of this program; accounts marked as signers sign
+    pub fn process<A: ToAccountMetas, D: InstructionData>(&mut self, accounts: A, data: D) -> ProgramResult {
+        self.process_with_remaining(accounts, data, vec![])
+    }
+
+    // Same as `process`, with `remaining` appended after the instruction's own accounts
+    pub fn process_with_remaining<A: ToAccountMetas, D: InstructionData>(
+        &mut self,
+        accounts: A,
+        data: D,
+        remaining: Vec<AccountMeta>,
+    ) -> ProgramResult {
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(remaining);
+        self.process_transaction(&[], metas, data.data())
+    }
+
+    // Runs `precompiles` (Ed25519 signature verifications) and then one instruction of this
+    // program as one transaction, the instructions sysvar describing all of them
+    pub fn process_after<A: ToAccountMetas, D: InstructionData>(
+        &mut self,
+        precompiles: &[Instruction],
+        accounts: A,
+        data: D,
+    ) -> ProgramResult {
+        self.process_transaction(precompiles, accounts.to_account_metas(None), data.data())
+    }
+
+    fn process_transaction(&mut self, precompiles: &[Instruction], metas: Vec<AccountMeta>, data: Vec<u8>) -> ProgramResult {
+        RETURN_DATA.with(|r| *r.borrow_mut() = None);
+        EVENTS.with(|events| events.borrow_mut().clear());
+        for precompile in precompiles {
+            verify_ed25519_instruction(precompile)?;
+        }
+        let instruction = Instruction { program_id: x_growth::ID, accounts: metas.clone(), data: data.clone() };
+        self.set_instructions_sysvar(precompiles.iter().chain([&instruction]));
+
+        // One AccountInfo per distinct key, shared by duplicate metas with their privileges merged
+        let mut unique: Vec<AccountInfo<'static>> = Vec::new();
+        let mut infos: Vec<AccountInfo<'static>> = Vec::new();
+        for meta in &metas {
+            if let Some(info) = unique.iter().find(|info| *info.key == meta.pubkey) {
+                infos.push(info.clone());
+                continue;
+            }
+            let duplicates = metas.iter().filter(|other| other.pubkey == meta.pubkey);
+            let (is_signer, is_writable) = duplicates.fold((false, false), |(signer, writable), other| {
+                (signer || other.is_signer, writable || other.is_writable)
+            });
+            let stored = self.accounts.get(&meta.pubkey).cloned().unwrap_or(StoredAccount {
+                lamports: 0,
+                data: vec![],
+                owner: system_program::ID,
+                executable: false,
+            });
+            let info = AccountInfo::new(
+                serialized_key(meta.pubkey, stored.data.len()),
+                is_signer,
+                is_writable,
+                Box::leak(Box::new(stored.lamports)),
+                serialized_data(stored.data),
+                Box::leak(Box::new(stored.owner)),
+                stored.executable,
+                0,
+            );
+            unique.push(info.clone());
+            infos.push(info);
+        }
+
+        let infos: &'static [AccountInfo<'static>] = Box::leak(infos.into_boxed_slice());
+        x_growth::entry(&x_growth::ID, infos, &data)?;
+
+        for info in unique {
+            self.accounts.insert(*info.key, StoredAccount {
+                lamports: info.lamports(),
+                data: info.data.borrow().to_vec(),
+                owner: *info.owner,
+                executable: info.executable,
+            });
+        }
+        Ok(())
+    }
+
+    // The instructions sysvar of a transaction whose last instruction is executing
+    fn set_instructions_sysvar<'a>(&mut self, transaction: impl Iterator<Item = &'a Instruction>) {
+        let transaction: Vec<&Instruction> = transaction.collect();
+        let borrowed: Vec<BorrowedInstruction> = transaction
+            .iter()
+            .map(|instruction| BorrowedInstruction {
+                program_id: &instruction.program_id,
+                accounts: instruction
+                    .accounts
+                    .iter()
+                    .map(|meta| BorrowedAccountMeta {
+                        pubkey: &meta.pubkey,
+                        is_signer: meta.is_signer,
+                        is_writable: meta.is_writable,
+                    })
+                    .collect(),
+                data: &instruction.data,
+            })
+            .collect();
+        let mut data = instructions::construct_instructions_data(&borrowed);
+        instructions::store_current_index(&mut data, (transaction.len() - 1) as u16);
+        self.accounts.insert(instructions::ID, StoredAccount {
+            lamports: 1,
+            data,
+            owner: sysvar::ID,
+            executable: false,
+        });
+    }
+
+    pub fn return_data(&self) -> Option<Vec<u8>> {
+        RETURN_DATA.with(|r| r.borrow().as_ref().map(|(_, data)| data.clone()))
+    }
+
+    // Events of type T emitted by the last instruction, in order
+    pub fn events<T: anchor_lang::Event>(&self) -> Vec<T> {
+        EVENTS.with(|events| {
+            events
+                .borrow()
+                .iter()
+                .filter(|data| data.starts_with(&T::DISCRIMINATOR))
+                .map(|data| T::try_from_slice(&data[8..]).unwrap())
+                .collect()
+        })
+    }
+}
+
+// The runtime serializes each account as [.., original data len: u32, key, .., data len: u64,
+// data, MAX_PERMITTED_DATA_INCREASE spare bytes]. AccountInfo::realloc relies on that layout,
+// so the bank lays out keys and data the same way.
+#[repr(C)]
+struct SerializedKey {
+    original_data_len: u32,
+    key: Pubkey,
+}
+
+fn serialized_key(key: Pubkey, data_len: usize) -> &'static Pubkey {
+    let slot = Box::leak(Box::new(SerializedKey { original_data_len: data_len as u32, key }));
+    &slot.key
+}
+
+fn serialized_data(data: Vec<u8>) -> &'static mut [u8] {
+    let words = 1 + (data.len() + MAX_PERMITTED_DATA_INCREASE).div_ceil(8);
+    let buffer = Box::leak(vec![0u64; words].into_boxed_slice());
+    buffer[0] = data.len() as u64;
+    let bytes = unsafe { std::slice::from_raw_parts_mut(buffer.as_mut_ptr().add(1) as *mut u8, data.len()) };
+    bytes.copy_from_slice(&data);
+    bytes
+}
+
+// Ed25519 precompile data for one signature, every offset pointing into the instruction itself
+// (u16::MAX), laid out as public key, signature, message
+pub fn ed25519_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+    const HEADER: u16 = 2 + 14;
+    let signature = signer.sign(message).to_bytes();
+    let mut data = vec![1, 0];
+    for field in [HEADER + 32, u16::MAX, HEADER, u16::MAX, HEADER + 96, message.len() as u16, u16::MAX] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(signer.public.as_bytes());
+    data.extend_from_slice(&signature);
+    data.extend_from_slice(message);
+    Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+}
+
+// What the runtime checks before running a transaction with an Ed25519 instruction; the
+// program only ever sees transactions that pass
+fn verify_ed25519_instruction(instruction: &Instruction) -> ProgramResult {
+    let data = &instruction.data;
+    let field = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+    let invalid = ProgramError::InvalidInstructionData;
+    if instruction.program_id != ed25519_program::ID || data.len() < 16 {
+        return Err(invalid);
+    }
+    for signature in 0..data[0] as usize {
+        let at = 2 + 14 * signature;
+        if [field(at + 2), field(at + 6), field(at + 12)] != [u16::MAX as usize; 3] {
+            return Err(invalid);
+        }
+        let bytes = |start: usize, len: usize| data.get(start..start + len).ok_or(ProgramError::InvalidInstructionData);
+        let public = PublicKey::from_bytes(bytes(field(at + 4), 32)?).map_err(|_| invalid.clone())?;
+        let signature = Signature::from_bytes(bytes(field(at), 64)?).map_err(|_| invalid.clone())?;
+        let message = bytes(field(at + 8), field(at + 10))?;
+        public.verify(message, &signature).map_err(|_| invalid.clone())?;
+    }
+    Ok(())
+}
+
+// An Ed25519 keypair derived from `seed`, for keys that sign off-chain
+pub fn keypair(seed: u8) -> Keypair {
+    let secret = SecretKey::from_bytes(&[seed; 32]).unwrap();
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
+pub fn keypair_pubkey(keypair: &Keypair) -> Pubkey {
+    Pubkey::new_from_array(keypair.public.to_bytes())
+}
+
+impl Default for Bank {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub const USDT: u64 = 1_000_000;
+pub const AGENT_ID: &str = "test-agent";
+
+pub fn pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &x_growth::ID).0
+}
+
+// A platform with one agent, on a linear curve unless created with_curve
+pub struct Fixture {
+    pub bank: Bank,
+    pub authority: Pubkey,
+    pub oracle: Pubkey,
+    pub creator: Pubkey,
+    pub usdt_mint: Pubkey,
+    pub platform: Pubkey,
+    pub treasury: Pubkey,
+    pub reward_pool: Pubkey,
+    pub agent: Pubkey,
+    pub token_mint: Pubkey,
+    pub reserve: Pubkey,
+}
+
+pub struct Trader {
+    pub key: Pubkey,
+    pub usdt_account: Pubkey,
+    pub token_account: Pubkey,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self::with_curve(Self::default_curve())
+    }
+
+    // A platform whose agent prices on `curve`
+    pub fn with_curve(curve: x_growth::curve::BondingCurveParams) -> Self {
+        let mut bank = Bank::new();
+        let authority = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        let usdt_mint = Pubkey::new_unique();
+        bank.fund(authority, 100_000_000_000);
+        bank.fund(oracle, 100_000_000_000);
+        bank.fund(creator, 100_000_000_000);
+        bank.create_mint(usdt_mint, Pubkey::new_unique(), 6);
+
+        let agent = pda(&[b"agent", AGENT_ID.as_bytes()]);
+        let mut fixture = Fixture {
+            authority,
+            oracle,
+            creator,
+            usdt_mint,
+            platform: pda(&[b"platform"]),
+            treasury: pda(&[b"treasury"]),
+            reward_pool: pda(&[b"reward_pool"]),
+            agent,
+            token_mint: pda(&[b"token_mint", AGENT_ID.as_bytes()]),
+            reserve: pda(&[b"reserve", agent.as_ref()]),
+            bank,
+        };
+
+        fixture
+            .bank
+            .process(
+                x_growth::accounts::InitializePlatform {
+                    platform: fixture.platform,
+                    authority,
+                    usdt_mint,
+                    treasury: fixture.treasury,
+                    reward_pool: fixture.reward_pool,
+                    oracle,
+                    token_program: spl_token::ID,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                x_growth::instruction::InitializePlatform { daily_reward_pool: 200 * USDT },
+            )
+            .unwrap();
+
+        fixture.create_agent(AGENT_ID, creator, curve).unwrap();
+
+        // Most tests check whole epoch credits; vesting tests set their own period
+        fixture.set_reward_vesting_period(0).unwrap();
+
+        // Most tests also credit right after finalizing; dispute tests open their own window
+        fixture.set_dispute_config(None, 0).unwrap();
+
+        fixture
+    }
+
+    // The linear curve every fixture agent is created on
+    pub fn default_curve() -> x_growth::curve::BondingCurveParams {
+        x_growth::curve::BondingCurveParams::Linear(x_growth::curve::LinearCurve {
+            base_price: 10_000,
+            final_price: 50_000,
+            max_supply: 300_000_000_000_000,
+        })
+    }
+
+    // Creates another agent on the same linear curve and returns its PDA
+    pub fn add_agent(&mut self, agent_id: &str, creator: Pubkey) -> Pubkey {
+        self.create_agent(agent_id, creator, Self::default_curve()).unwrap();
+        pda(&[b"agent", agent_id.as_bytes()])
+    }
+
+    pub fn create_agent(
+        &mut self,
+        agent_id: &str,
+        creator: Pubkey,
+        bonding_curve_params: x_growth::curve::BondingCurveParams,
+    ) -> ProgramResult {
+        let agent = pda(&[b"agent", agent_id.as_bytes()]);
+        if !self.bank.exists(&creator) {
+            self.bank.fund(creator, 100_000_000_000);
+        }
+        self.bank.process(
+            x_growth::accounts::CreateAgent {
+                platform: self.platform,
+                agent,
+                token_mint: pda(&[b"token_mint", agent_id.as_bytes()]),
+                usdt_mint: self.usdt_mint,
+                reserve_usdt_account: pda(&[b"reserve", agent.as_ref()]),
+                stake_vault: pda(&[b"stake_vault", agent.as_ref()]),
+                authority: creator,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            x_growth::instruction::CreateAgent {
+                agent_id: agent_id.to_string(),
+                name: "Test Agent".to_string(),
+                symbol: "TEST".to_string(),
+                uri: "https://example.com/agent.json".to_string(),
+                initial_supply: 0,
+                bonding_curve_params,
+            },
+        )
+    }
+
+    // Oracle update of the daily metrics with a version 1 report
+    pub fn update_performance(&mut self, agent: Pubkey, likes: u64, views: u64, comments: u64, new_followers: u64) -> ProgramResult {
+        let report = x_growth::state::MetricReport {
+            version: x_growth::state::MetricReport::V1,
+            values: vec![likes, views, comments, new_followers],
+        };
+        self.report_metrics(agent, report)
+    }
+
+    // Oracle report with the agent's next sequence number
+    pub fn report_metrics(&mut self, agent: Pubkey, report: x_growth::state::MetricReport) -> ProgramResult {
+        let sequence = self.next_report_sequence(&agent);
+        self.report_metrics_at(agent, sequence, report)
+    }
+
+    pub fn report_metrics_at(&mut self, agent: Pubkey, sequence: u64, report: x_growth::state::MetricReport) -> ProgramResult {
+        let oracle = self.oracle;
+        self.bank.process(
+            self.update_performance_accounts(agent, oracle, oracle),
+            x_growth::instruction::UpdatePerformance { report, sequence, attestation: None },
+        )
+    }
+
+    // An oracle's signed report, relayed after the Ed25519 verification `signed`
+    pub fn relay_metrics(
+        &mut self,
+        accounts: x_growth::accounts::UpdatePerformance,
+        report: x_growth::state::MetricReport,
+        sequence: u64,
+        attestation: x_growth::state::Attestation,
+        signed: Instruction,
+    ) -> ProgramResult {
+        self.bank.process_after(
+            &[signed],
+            accounts,
+            x_growth::instruction::UpdatePerformance { report, sequence, attestation: Some(attestation) },
+        )
+    }
+
+    pub fn update_performance_accounts(&self, agent: Pubkey, oracle: Pubkey, payer: Pubkey) -> x_growth::accounts::UpdatePerformance {
+        x_growth::accounts::UpdatePerformance {
+            agent,
+            platform: self.platform,
+            report_sequence: Self::report_sequence_pda(&agent),
+            oracle,
+            payer,
+            instructions: instructions::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn report_sequence_pda(agent: &Pubkey) -> Pubkey {
+        pda(&[b"report_sequence", agent.as_ref()])
+    }
+
+    // One above the sequence number of the agent's last applied report
+    pub fn next_report_sequence(&self, agent: &Pubkey) -> u64 {
+        let key = Self::report_sequence_pda(agent);
+        if !self.bank.exists(&key) {
+            return 1;
+        }
+        self.bank.anchor_account::<x_growth::state::ReportSequence>(&key).last_sequence + 1
+    }
+
+    pub fn observation_pda(agent: &Pubkey, epoch: u64) -> Pubkey {
+        pda(&[b"observation", agent.as_ref(), epoch.to_le_bytes().as_ref()])
+    }
+
+    pub fn observation_state(&self, agent: &Pubkey, epoch: u64) -> x_growth::state::PendingObservation {
+        self.bank.anchor_account(&Self::observation_pda(agent, epoch))
+    }
+
+    pub fn set_oracle_set(&mut self, oracles: &[Pubkey], threshold: u8, outlier_tolerance_bps: u16) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetOracleSet {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetOracleSet { oracles: oracles.to_vec(), threshold, outlier_tolerance_bps },
+        )
+    }
+
+    // One oracle's observation of `agent` for `epoch`
+    pub fn submit_observation(&mut self, oracle: Pubkey, agent: Pubkey, epoch: u64, report: x_growth::state::MetricReport) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SubmitObservation {
+                agent,
+                platform: self.platform,
+                epoch: Self::epoch_pda(epoch),
+                observation: Self::observation_pda(&agent, epoch),
+                oracle,
+                system_program: system_program::ID,
+            },
+            x_growth::instruction::SubmitObservation { report },
+        )
+    }
+
+    pub fn migrate_agent(&mut self, agent: Pubkey, token_mint: Pubkey) -> ProgramResult {
+        let payer = self.authority;
+        self.bank.process(
+            x_growth::accounts::MigrateAgent {
+                platform: self.platform,
+                agent,
+                token_mint,
+                usdt_mint: self.usdt_mint,
+                reserve_usdt_account: pda(&[b"reserve", agent.as_ref()]),
+                stake_vault: pda(&[b"stake_vault", agent.as_ref()]),
+                payer,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            x_growth::instruction::MigrateAgent {},
+        )
+    }
+
+    pub fn migrate_platform(&mut self, authority: Pubkey) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::MigratePlatform {
+                platform: self.platform,
+                authority,
+                usdt_mint: self.usdt_mint,
+                treasury: self.treasury,
+                reward_pool: self.reward_pool,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+                rent: sysvar::rent::ID,
+            },
+            x_growth::instruction::MigratePlatform {},
+        )
+    }
+
+    pub fn user_rewards_pda(user: &Pubkey, agent: &Pubkey) -> Pubkey {
+        pda(&[b"user_rewards", user.as_ref(), agent.as_ref()])
+    }
+
+    pub fn user_rewards_state(&self, user: &Pubkey) -> x_growth::state::UserRewards {
+        self.bank.anchor_account(&Self::user_rewards_pda(user, &self.agent))
+    }
+
+    pub fn user_stake_pda(user: &Pubkey, agent: &Pubkey) -> Pubkey {
+        pda(&[b"stake", user.as_ref(), agent.as_ref()])
+    }
+
+    pub fn user_stake_state(&self, user: &Pubkey) -> x_growth::state::UserStake {
+        self.bank.anchor_account(&Self::user_stake_pda(user, &self.agent))
+    }
+
+    pub fn stake_vault(&self) -> Pubkey {
+        pda(&[b"stake_vault", self.agent.as_ref()])
+    }
+
+    pub fn reward_stream_pda(agent: &Pubkey, index: u8) -> Pubkey {
+        pda(&[b"reward_stream", agent.as_ref(), &[index]])
+    }
+
+    pub fn stream_vault_pda(stream: &Pubkey) -> Pubkey {
+        pda(&[b"stream_vault", stream.as_ref()])
+    }
+
+    pub fn reward_stream_state(&self, index: u8) -> x_growth::state::RewardStream {
+        self.bank.anchor_account(&Self::reward_stream_pda(&self.agent, index))
+    }
+
+    // Every reward stream of the agent, as stake and unstake expect them
+    pub fn reward_stream_metas(&self) -> Vec<AccountMeta> {
+        (0..self.agent_state().reward_stream_count)
+            .map(|index| AccountMeta::new(Self::reward_stream_pda(&self.agent, index), false))
+            .collect()
+    }
+
+    // Opens the agent's next reward stream in a new mint and returns its index and mint
+    pub fn create_reward_stream(&mut self, emission_rate: u64, end_time: i64) -> std::result::Result<(u8, Pubkey), ProgramError> {
+        let index = self.agent_state().reward_stream_count;
+        let stream = Self::reward_stream_pda(&self.agent, index);
+        let reward_mint = Pubkey::new_unique();
+        self.bank.create_mint(reward_mint, Pubkey::new_unique(), 6);
+        self.bank
+            .process(
+                x_growth::accounts::CreateRewardStream {
+                    agent: self.agent,
+                    stream,
+                    stream_vault: Self::stream_vault_pda(&stream),
+                    reward_mint,
+                    authority: self.creator,
+                    token_program: spl_token::ID,
+                    system_program: system_program::ID,
+                    rent: sysvar::rent::ID,
+                },
+                x_growth::instruction::CreateRewardStream { emission_rate, end_time },
+            )?;
+        Ok((index, reward_mint))
+    }
+
+    // Funds a stream from the creator's token account for its mint
+    pub fn fund_reward_stream(&mut self, index: u8, amount: u64) -> ProgramResult {
+        let stream = Self::reward_stream_pda(&self.agent, index);
+        let mint = self.reward_stream_state(index).mint;
+        let funder_token_account = self.bank.create_ata(mint, self.creator, amount);
+        self.bank.process(
+            x_growth::accounts::FundRewardStream {
+                agent: self.agent,
+                stream,
+                stream_vault: Self::stream_vault_pda(&stream),
+                funder_token_account,
+                authority: self.creator,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::FundRewardStream { amount },
+        )
+    }
+
+    // Ends a stream, refunding into `creator_token_account`
+    pub fn end_reward_stream(&mut self, index: u8, creator_token_account: Pubkey) -> ProgramResult {
+        let stream = Self::reward_stream_pda(&self.agent, index);
+        self.bank.process(
+            x_growth::accounts::EndRewardStream {
+                agent: self.agent,
+                stream,
+                stream_vault: Self::stream_vault_pda(&stream),
+                creator_token_account,
+                authority: self.creator,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::EndRewardStream {},
+        )
+    }
+
+    pub fn sweep_reward_stream(&mut self, index: u8, creator_token_account: Pubkey) -> ProgramResult {
+        let stream = Self::reward_stream_pda(&self.agent, index);
+        self.bank.process(
+            x_growth::accounts::EndRewardStream {
+                agent: self.agent,
+                stream,
+                stream_vault: Self::stream_vault_pda(&stream),
+                creator_token_account,
+                authority: self.creator,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::SweepRewardStream {},
+        )
+    }
+
+    // Claims USDT rewards plus the given (stream index, destination) stream balances
+    pub fn claim_with_streams(&mut self, trader: &Trader, streams: &[(u8, Pubkey)]) -> ProgramResult {
+        let mut remaining = Vec::new();
+        for (index, destination) in streams {
+            let stream = Self::reward_stream_pda(&self.agent, *index);
+            remaining.push(AccountMeta::new(stream, false));
+            remaining.push(AccountMeta::new(Self::stream_vault_pda(&stream), false));
+            remaining.push(AccountMeta::new(*destination, false));
+        }
+        self.bank.process_with_remaining(
+            x_growth::accounts::ClaimRewards {
+                agent: self.agent,
+                user_rewards: Self::user_rewards_pda(&trader.key, &self.agent),
+                user_usdt_account: trader.usdt_account,
+                reward_pool: self.reward_pool,
+                platform: self.platform,
+                user: trader.key,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::ClaimRewards {},
+            remaining,
+        )
+    }
+
+    pub fn stake(&mut self, trader: &Trader, amount: u64) -> ProgramResult {
+        let streams = self.reward_stream_metas();
+        self.bank.process_with_remaining(
+            x_growth::accounts::Stake {
+                agent: self.agent,
+                platform: self.platform,
+                stake_vault: self.stake_vault(),
+                user_token_account: trader.token_account,
+                user_stake: Self::user_stake_pda(&trader.key, &self.agent),
+                user_rewards: Self::user_rewards_pda(&trader.key, &self.agent),
+                user: trader.key,
+                token_program: spl_token::ID,
+                system_program: system_program::ID,
+            },
+            x_growth::instruction::Stake { amount },
+            streams,
+        )
+    }
+
+    pub fn unstake(&mut self, trader: &Trader, amount: u64) -> ProgramResult {
+        let streams = self.reward_stream_metas();
+        self.bank.process_with_remaining(
+            x_growth::accounts::Unstake {
+                agent: self.agent,
+                platform: self.platform,
+                stake_vault: self.stake_vault(),
+                user_token_account: trader.token_account,
+                user_stake: Self::user_stake_pda(&trader.key, &self.agent),
+                user_rewards: Self::user_rewards_pda(&trader.key, &self.agent),
+                user: trader.key,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::Unstake { amount },
+            streams,
+        )
+    }
+
+    pub fn activate_stake(&mut self, user: &Pubkey) -> ProgramResult {
+        let streams = self.reward_stream_metas();
+        self.bank.process_with_remaining(
+            x_growth::accounts::ActivateStake {
+                agent: self.agent,
+                user_stake: Self::user_stake_pda(user, &self.agent),
+                user_rewards: Self::user_rewards_pda(user, &self.agent),
+            },
+            x_growth::instruction::ActivateStake {},
+            streams,
+        )
+    }
+
+    pub fn withdraw_stake(&mut self, trader: &Trader) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::WithdrawStake {
+                agent: self.agent,
+                stake_vault: self.stake_vault(),
+                user_token_account: trader.token_account,
+                user_stake: Self::user_stake_pda(&trader.key, &self.agent),
+                user: trader.key,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::WithdrawStake {},
+        )
+    }
+
+    pub fn set_unbonding_period(&mut self, unbonding_period: i64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetUnbondingPeriod {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetUnbondingPeriod { unbonding_period },
+        )
+    }
+
+    pub fn set_reward_vesting_period(&mut self, reward_vesting_period: i64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetRewardVestingPeriod {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetRewardVestingPeriod { reward_vesting_period },
+        )
+    }
+
+    pub fn set_score_weights(&mut self, score_weights: x_growth::state::ScoreWeights) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetScoreWeights {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetScoreWeights { score_weights },
+        )
+    }
+
+    pub fn set_score_transforms(&mut self, score_transforms: x_growth::state::ScoreTransforms) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetScoreTransforms {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetScoreTransforms { score_transforms },
+        )
+    }
+
+    pub fn epoch_pda(epoch: u64) -> Pubkey {
+        pda(&[b"epoch", epoch.to_le_bytes().as_ref()])
+    }
+
+    pub fn epoch_state(&self, epoch: u64) -> x_growth::state::RewardEpoch {
+        self.bank.anchor_account(&Self::epoch_pda(epoch))
+    }
+
+    pub fn begin_epoch(&mut self) -> ProgramResult {
+        let epoch = Self::epoch_pda(self.platform_state().current_epoch + 1);
+        let payer = self.authority;
+        self.bank.process(
+            x_growth::accounts::BeginEpoch {
+                platform: self.platform,
+                epoch,
+                payer,
+                system_program: system_program::ID,
+            },
+            x_growth::instruction::BeginEpoch {},
+        )
+    }
+
+    pub fn record_agent_score(&mut self, agent: Pubkey, epoch: u64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::RecordAgentScore { agent, epoch: Self::epoch_pda(epoch) },
+            x_growth::instruction::RecordAgentScore {},
+        )
+    }
+
+    pub fn finalize_epoch(&mut self, epoch: u64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::FinalizeEpoch { platform: self.platform, epoch: Self::epoch_pda(epoch) },
+            x_growth::instruction::FinalizeEpoch {},
+        )
+    }
+
+    pub fn distribute_rewards(&mut self, agent: Pubkey, epoch: u64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::DistributeRewards {
+                agent,
+                epoch: Self::epoch_pda(epoch),
+                platform: self.platform,
+                dispute: Self::dispute_pda(&agent, epoch),
+            },
+            x_growth::instruction::DistributeRewards {},
+        )
+    }
+
+    pub fn dispute_pda(agent: &Pubkey, epoch: u64) -> Pubkey {
+        pda(&[b"dispute", agent.as_ref(), epoch.to_le_bytes().as_ref()])
+    }
+
+    pub fn dispute_state(&self, agent: &Pubkey, epoch: u64) -> x_growth::state::ScoreDispute {
+        self.bank.anchor_account(&Self::dispute_pda(agent, epoch))
+    }
+
+    pub fn set_dispute_config(&mut self, guardian: Option<Pubkey>, dispute_window: i64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::SetDisputeConfig {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::SetDisputeConfig { guardian, dispute_window },
+        )
+    }
+
+    pub fn dispute_agent_score(&mut self, challenger: Pubkey, agent: Pubkey, epoch: u64) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::DisputeAgentScore {
+                agent,
+                epoch: Self::epoch_pda(epoch),
+                platform: self.platform,
+                dispute: Self::dispute_pda(&agent, epoch),
+                challenger,
+                system_program: system_program::ID,
+            },
+            x_growth::instruction::DisputeAgentScore {},
+        )
+    }
+
+    pub fn resolve_dispute(&mut self, resolver: Pubkey, agent: Pubkey, epoch: u64, report: x_growth::state::MetricReport) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::ResolveDispute {
+                agent,
+                epoch: Self::epoch_pda(epoch),
+                platform: self.platform,
+                dispute: Self::dispute_pda(&agent, epoch),
+                resolver,
+            },
+            x_growth::instruction::ResolveDispute { report },
+        )
+    }
+
+    // Runs a full epoch over `agents` (every agent on the platform) and returns its number
+    pub fn run_epoch(&mut self, agents: &[Pubkey]) -> u64 {
+        self.begin_epoch().unwrap();
+        let epoch = self.platform_state().current_epoch;
+        set_unix_timestamp(unix_timestamp() + x_growth::state::Platform::EPOCH_DURATION);
+        for agent in agents {
+            self.record_agent_score(*agent, epoch).unwrap();
+        }
+        self.finalize_epoch(epoch).unwrap();
+        set_unix_timestamp(unix_timestamp().max(self.epoch_state(epoch).dispute_end));
+        for agent in agents {
+            self.distribute_rewards(*agent, epoch).unwrap();
+        }
+        epoch
+    }
+
+    // Deposit `amount` into the reward pool from a new funder
+    pub fn fund_reward_pool(&mut self, amount: u64) -> ProgramResult {
+        let funder = Pubkey::new_unique();
+        let funder_usdt_account = self.bank.create_ata(self.usdt_mint, funder, amount);
+        self.bank.process(
+            x_growth::accounts::FundRewardPool {
+                platform: self.platform,
+                reward_pool: self.reward_pool,
+                funder_usdt_account,
+                funder,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::FundRewardPool { amount },
+        )
+    }
+
+    pub fn claim_rewards(&mut self, user: Pubkey, user_usdt_account: Pubkey) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::ClaimRewards {
+                agent: self.agent,
+                user_rewards: Self::user_rewards_pda(&user, &self.agent),
+                user_usdt_account,
+                reward_pool: self.reward_pool,
+                platform: self.platform,
+                user,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::ClaimRewards {},
+        )
+    }
+
+    // Buys agent tokens with the trader's pending rewards, staking them with `stake`
+    pub fn compound_rewards(&mut self, trader: &Trader, min_tokens_out: u64, stake: bool) -> ProgramResult {
+        let streams = if stake { self.reward_stream_metas() } else { vec![] };
+        self.bank.process_with_remaining(
+            x_growth::accounts::CompoundRewards {
+                agent: self.agent,
+                token_mint: self.token_mint,
+                user_rewards: Self::user_rewards_pda(&trader.key, &self.agent),
+                reward_pool: self.reward_pool,
+                reserve_usdt_account: self.reserve,
+                treasury: self.treasury,
+                platform: self.platform,
+                user_token_account: (!stake).then_some(trader.token_account),
+                stake_vault: stake.then(|| self.stake_vault()),
+                user_stake: stake.then(|| Self::user_stake_pda(&trader.key, &self.agent)),
+                user: trader.key,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::CompoundRewards { min_tokens_out, stake },
+            streams,
+        )
+    }
+
+    pub fn platform_state(&self) -> x_growth::state::Platform {
+        self.bank.anchor_account(&self.platform)
+    }
+
+    pub fn agent_state(&self) -> x_growth::state::Agent {
+        self.bank.anchor_account(&self.agent)
+    }
+
+    pub fn agent_state_of(&self, agent: &Pubkey) -> x_growth::state::Agent {
+        self.bank.anchor_account(agent)
+    }
+
+    pub fn update_fee_config(&mut self, fee_config: x_growth::state::FeeConfig) -> ProgramResult {
+        self.bank.process(
+            x_growth::accounts::UpdateFeeConfig {
+                platform: self.platform,
+                authority: self.authority,
+            },
+            x_growth::instruction::UpdateFeeConfig { fee_config },
+        )
+    }
+
+    // A wallet with `usdt` USDT and an empty agent token account
+    pub fn new_trader(&mut self, usdt: u64) -> Trader {
+        let key = Pubkey::new_unique();
+        self.bank.fund(key, 1_000_000_000);
+        Trader {
+            key,
+            usdt_account: self.bank.create_ata(self.usdt_mint, key, usdt),
+            token_account: self.bank.create_ata(self.token_mint, key, 0),
+        }
+    }
+
+    pub fn new_usdt_account(&mut self, owner: Pubkey) -> Pubkey {
+        self.bank.create_ata(self.usdt_mint, owner, 0)
+    }
+
+    pub fn buy_accounts(&self, trader: &Trader, referrer: Option<Pubkey>) -> x_growth::accounts::BuyFromCurve {
+        x_growth::accounts::BuyFromCurve {
+            agent: self.agent,
+            token_mint: self.token_mint,
+            buyer_token_account: trader.token_account,
+            buyer_usdt_account: trader.usdt_account,
+            reserve_usdt_account: self.reserve,
+            platform: self.platform,
+            treasury: self.treasury,
+            buyer: trader.key,
+            referrer_usdt_account: referrer,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn sell_accounts(&self, trader: &Trader, referrer: Option<Pubkey>) -> x_growth::accounts::SellToCurve {
+        x_growth::accounts::SellToCurve {
+            agent: self.agent,
+            token_mint: self.token_mint,
+            seller_token_account: trader.token_account,
+            seller_usdt_account: trader.usdt_account,
+            reserve_usdt_account: self.reserve,
+            platform: self.platform,
+            treasury: self.treasury,
+            seller: trader.key,
+            referrer_usdt_account: referrer,
+            token_program: spl_token::ID,
+        }
+    }
+
+    // Runs quote_buy and decodes the TradeQuote it returns
+    pub fn quote_buy(&mut self, usdt_amount: u64, with_referrer: bool) -> std::result::Result<x_growth::state::TradeQuote, ProgramError> {
+        self.bank.process(
+            x_growth::accounts::QuoteBuy { agent: self.agent, platform: self.platform },
+            x_growth::instruction::QuoteBuy { usdt_amount, with_referrer },
+        )?;
+        Ok(self.returned_quote())
+    }
+
+    pub fn quote_sell(&mut self, token_amount: u64, with_referrer: bool) -> std::result::Result<x_growth::state::TradeQuote, ProgramError> {
+        self.bank.process(
+            x_growth::accounts::QuoteSell { agent: self.agent, platform: self.platform },
+            x_growth::instruction::QuoteSell { token_amount, with_referrer },
+        )?;
+        Ok(self.returned_quote())
+    }
+
+    fn returned_quote(&self) -> x_growth::state::TradeQuote {
+        let data = self.bank.return_data().expect("no return data");
+        x_growth::state::TradeQuote::try_from_slice(&data).unwrap()
+    }
+
+    pub fn buy(&mut self, trader: &Trader, usdt_amount: u64, referrer: Option<Pubkey>) -> ProgramResult {
+        let accounts = self.buy_accounts(trader, referrer);
+        self.bank.process(
+            accounts,
+            x_growth::instruction::BuyFromCurve { usdt_amount, min_tokens_out: 0 },
+        )
+    }
+
+    pub fn sell(&mut self, trader: &Trader, token_amount: u64, referrer: Option<Pubkey>) -> ProgramResult {
+        let accounts = self.sell_accounts(trader, referrer);
+        self.bank.process(
+            accounts,
+            x_growth::instruction::SellToCurve { token_amount, min_usdt_out: 0 },
+        )
+    }
+
+    // The reserve holds exactly reserve_balance; the treasury holds the protocol
+    // balance plus unclaimed creator fees
+    pub fn assert_vaults_balanced(&self) {
+        let platform = self.platform_state();
+        let agent = self.agent_state();
+        assert_eq!(self.bank.token_balance(&self.reserve), agent.reserve_balance);
+        assert_eq!(
+            self.bank.token_balance(&self.treasury),
+            platform.protocol_fee_balance + agent.creator_fee_balance
+        );
+    }
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
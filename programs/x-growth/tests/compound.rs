@@ -0,0 +1,179 @@
+// Compounding pending USDT rewards into agent tokens through the bonding curve
+mod common;
+
+use anchor_spl::token::spl_token;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::events::TradeFeesCharged;
+use x_growth::state::FeeConfig;
+
+// 1% buys, 2% sells, split 50/30/20 between protocol, creator and referrer
+const FEES: FeeConfig = FeeConfig {
+    buy_fee_bps: 100,
+    sell_fee_bps: 200,
+    protocol_share_bps: 5_000,
+    creator_share_bps: 3_000,
+    referrer_share_bps: 2_000,
+    max_agent_fee_bps: 500,
+};
+
+// Alice stakes and earns one epoch of rewards; returns her and her staked tokens
+fn earning_staker(fixture: &mut Fixture) -> (Trader, u64) {
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let alice = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&alice, 100 * USDT, None).unwrap();
+    let tokens = fixture.bank.token_balance(&alice.token_account);
+    fixture.stake(&alice, tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    (alice, tokens)
+}
+
+#[test]
+fn compound_buys_tokens_with_pending_rewards() {
+    let mut fixture = Fixture::new();
+    let (alice, _) = earning_staker(&mut fixture);
+    let agent_before = fixture.agent_state();
+    let reserve_before = fixture.bank.token_balance(&fixture.reserve);
+
+    fixture.compound_rewards(&alice, 0, false).unwrap();
+
+    // The rewards bought tokens exactly as buy_from_curve would have
+    let rewards = fixture.user_rewards_state(&alice.key);
+    let usdt = rewards.claimed_rewards;
+    assert_close(usdt, 200 * USDT);
+    assert_eq!(rewards.pending_rewards, 0);
+    let quote = agent_before.quote_buy(&fixture.platform_state().fee_config, usdt, false).unwrap();
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), quote.tokens_out);
+    assert_eq!(fixture.agent_state().circulating_supply, agent_before.circulating_supply + quote.tokens_out);
+
+    // Paid from the reward pool into the reserve and treasury, never through Alice
+    assert_eq!(fixture.bank.token_balance(&alice.usdt_account), 900 * USDT);
+    assert_eq!(fixture.bank.token_balance(&fixture.reward_pool), 1_000 * USDT - usdt);
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), reserve_before + usdt - quote.fee);
+    assert_eq!(fixture.platform_state().total_rewards_claimed, usdt);
+    fixture.assert_vaults_balanced();
+
+    assert_eq!(
+        fixture.compound_rewards(&alice, 0, false),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+}
+
+#[test]
+fn compounded_tokens_can_be_staked_directly() {
+    let mut fixture = Fixture::new();
+    let (alice, staked) = earning_staker(&mut fixture);
+    let vault_before = fixture.bank.token_balance(&fixture.stake_vault());
+
+    fixture.compound_rewards(&alice, 0, true).unwrap();
+
+    let bought = fixture.bank.token_balance(&fixture.stake_vault()) - vault_before;
+    assert!(bought > 0);
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), 0);
+    assert_eq!(fixture.user_stake_state(&alice.key).amount, staked + bought);
+    assert_eq!(fixture.user_rewards_state(&alice.key).shares, staked + bought);
+    assert_eq!(fixture.agent_state().total_shares, staked + bought);
+
+    // The compounded stake earns like any other
+    let agent = fixture.agent;
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.bank.token_balance(&alice.usdt_account), 900 * USDT + 200 * USDT);
+}
+
+#[test]
+fn compound_respects_the_slippage_bound() {
+    let mut fixture = Fixture::new();
+    let (alice, _) = earning_staker(&mut fixture);
+    let agent_before = fixture.agent_state();
+    let fee_config = fixture.platform_state().fee_config;
+
+    // Alice's pending rewards are settled inside the instruction; bound them from both sides
+    let floor = agent_before.quote_buy(&fee_config, 200 * USDT - 2, false).unwrap().tokens_out;
+    let ceiling = agent_before.quote_buy(&fee_config, 200 * USDT + 2, false).unwrap().tokens_out;
+    assert_eq!(
+        fixture.compound_rewards(&alice, ceiling + 1, false),
+        Err(custom_error(XGrowthError::SlippageExceeded))
+    );
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), 0);
+
+    fixture.compound_rewards(&alice, floor, false).unwrap();
+    assert!(fixture.bank.token_balance(&alice.token_account) >= floor);
+}
+
+#[test]
+fn compound_settles_fees_like_a_buy() {
+    let mut fixture = Fixture::new();
+    fixture.update_fee_config(FEES).unwrap();
+    let (alice, _) = earning_staker(&mut fixture);
+    let agent_before = fixture.agent_state();
+    let treasury_before = fixture.bank.token_balance(&fixture.treasury);
+    fixture.compound_rewards(&alice, 0, false).unwrap();
+
+    // No referrer: its leg stays in the treasury, and the agent's fee counters move as on a buy
+    let usdt = fixture.user_rewards_state(&alice.key).claimed_rewards;
+    let quote = agent_before.quote_buy(&FEES, usdt, false).unwrap();
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury) - treasury_before,
+        quote.protocol_fee + quote.creator_fee
+    );
+    let agent = fixture.agent_state();
+    assert_eq!(agent.referrer_fees_paid, agent_before.referrer_fees_paid);
+    assert_eq!(agent.creator_fees_accrued - agent_before.creator_fees_accrued, quote.creator_fee);
+    assert_eq!(agent.reserve_balance - agent_before.reserve_balance, usdt - quote.fee);
+
+    let charged = fixture.bank.events::<TradeFeesCharged>();
+    assert_eq!(charged.len(), 1);
+    assert_eq!(charged[0].trader, alice.key);
+    assert_eq!(charged[0].fee, quote.fee);
+    assert_eq!(charged[0].referrer, None);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn compounding_needs_the_accounts_the_tokens_go_to() {
+    let mut fixture = Fixture::new();
+    let (alice, staked) = earning_staker(&mut fixture);
+    let accounts = x_growth::accounts::CompoundRewards {
+        agent: fixture.agent,
+        token_mint: fixture.token_mint,
+        user_rewards: Fixture::user_rewards_pda(&alice.key, &fixture.agent),
+        reward_pool: fixture.reward_pool,
+        reserve_usdt_account: fixture.reserve,
+        treasury: fixture.treasury,
+        platform: fixture.platform,
+        user_token_account: None,
+        stake_vault: None,
+        user_stake: None,
+        user: alice.key,
+        token_program: spl_token::ID,
+    };
+
+    assert_eq!(
+        fixture.bank.process_with_remaining(
+            x_growth::accounts::CompoundRewards { ..accounts },
+            x_growth::instruction::CompoundRewards { min_tokens_out: 0, stake: true },
+            fixture.reward_stream_metas(),
+        ),
+        Err(custom_error(XGrowthError::StakeAccountsRequired))
+    );
+    assert_eq!(fixture.user_stake_state(&alice.key).amount, staked);
+    assert_eq!(fixture.user_rewards_state(&alice.key).claimed_rewards, 0);
+
+    // Without staking the tokens need somewhere to go
+    assert_eq!(
+        fixture.bank.process(
+            accounts,
+            x_growth::instruction::CompoundRewards { min_tokens_out: 0, stake: false },
+        ),
+        Err(custom_error(XGrowthError::UserTokenAccountRequired))
+    );
+    assert_eq!(fixture.user_rewards_state(&alice.key).claimed_rewards, 0);
+
+    // With them the same compound goes through
+    fixture.compound_rewards(&alice, 0, true).unwrap();
+    assert!(fixture.user_stake_state(&alice.key).amount > staked);
+}
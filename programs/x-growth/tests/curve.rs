@@ -0,0 +1,351 @@
+// Curve pricing invariants: round trips never pay out more than was paid in, and the
+// reserve always backs the integral of the outstanding supply
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::curve::{
+    BondingCurveParams, ConstantProductCurve, Curve, ExponentialCurve, LinearCurve, QuadraticCurve,
+    SigmoidCurve, FACTOR_PRECISION, PRICE_PRECISION,
+};
+use x_growth::errors::XGrowthError;
+
+const MAX_SUPPLY: u64 = 300_000_000_000_000;
+
+const EXPONENTIAL: ExponentialCurve = ExponentialCurve {
+    base_price: 10_000,
+    growth_rate: 5 * FACTOR_PRECISION,
+    max_supply: MAX_SUPPLY,
+};
+
+const SIGMOID: SigmoidCurve = SigmoidCurve {
+    base_price: 10_000,
+    max_price: 100_000,
+    midpoint: MAX_SUPPLY / 2,
+    steepness: 10 * FACTOR_PRECISION,
+    max_supply: MAX_SUPPLY,
+};
+
+const CONSTANT_PRODUCT: ConstantProductCurve = ConstantProductCurve {
+    virtual_usdt_reserve: 30_000_000 * USDT,
+    virtual_token_reserve: 1_073_000_000_000_000,
+    max_supply: 800_000_000_000_000,
+};
+
+// One curve of every family
+fn curves() -> Vec<BondingCurveParams> {
+    vec![
+        BondingCurveParams::Quadratic(QuadraticCurve {
+            base_price: 10_000,
+            curve_factor: FACTOR_PRECISION,
+            max_supply: MAX_SUPPLY,
+        }),
+        BondingCurveParams::Linear(LinearCurve {
+            base_price: 10_000,
+            final_price: 50_000,
+            max_supply: MAX_SUPPLY,
+        }),
+        BondingCurveParams::Exponential(EXPONENTIAL),
+        BondingCurveParams::Sigmoid(SIGMOID),
+        BondingCurveParams::ConstantProduct(CONSTANT_PRODUCT),
+    ]
+}
+
+// Supplies from empty to one token unit short of sold out
+fn supplies(curve: &BondingCurveParams) -> Vec<u64> {
+    let max = curve.max_supply();
+    vec![0, 1, 12_345, max / 3, max / 2, max - 1_000_000, max - 1]
+}
+
+// From a single base unit of USDT up to more than any curve can absorb
+const AMOUNTS: [u64; 8] = [1, 2, 99, USDT, 1_000 * USDT, 1_000_000 * USDT, 5_000_000 * USDT, u64::MAX / 4];
+
+// Deterministic pseudo-random sequence for the trade walks
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        (self.0 >> 33) % bound.max(1)
+    }
+}
+
+fn error_of<T>(result: Result<T>) -> ProgramError {
+    match result {
+        Ok(_) => panic!("expected an error"),
+        Err(error) => error.into(),
+    }
+}
+
+#[test]
+fn buy_then_sell_never_returns_more_than_was_paid() {
+    for curve in curves() {
+        for supply in supplies(&curve) {
+            let remaining = curve.max_supply() - supply;
+            let cost_to_sell_out = curve.calculate_buy_cost(supply, remaining).unwrap();
+            
+            for amount in AMOUNTS {
+                if amount > cost_to_sell_out {
+                    assert_eq!(
+                        error_of(curve.calculate_tokens_out(amount, supply)),
+                        custom_error(XGrowthError::MaxSupplyReached)
+                    );
+                    continue;
+                }
+                
+                // The largest amount of tokens the payment covers, and no more
+                let tokens = curve.calculate_tokens_out(amount, supply).unwrap();
+                assert!(curve.calculate_buy_cost(supply, tokens).unwrap() <= amount);
+                if tokens < remaining {
+                    assert!(curve.calculate_buy_cost(supply, tokens + 1).unwrap() > amount);
+                }
+                
+                let returned = curve.calculate_sell_return(supply + tokens, tokens).unwrap();
+                assert!(returned <= amount, "supply {supply}: paid {amount}, returned {returned}");
+            }
+        }
+    }
+}
+
+#[test]
+fn tiny_trades_round_in_the_reserves_favour() {
+    for curve in curves() {
+        for supply in supplies(&curve) {
+            // Single token units are priced from the rounded-up integral, never for free at a profit
+            let remaining = curve.max_supply() - supply;
+            let cost = curve.calculate_buy_cost(supply, remaining.min(1)).unwrap();
+            let returned = curve.calculate_sell_return(supply + remaining.min(1), remaining.min(1)).unwrap();
+            assert!(returned <= cost);
+            
+            // Asking for one base unit back burns enough tokens to release at least that
+            if supply > 0 {
+                let reserve = curve.calculate_reserve(supply).unwrap();
+                if reserve > 0 {
+                    let tokens_in = curve.calculate_tokens_in(1, supply).unwrap();
+                    assert!(tokens_in > 0);
+                    assert!(curve.calculate_sell_return(supply, tokens_in).unwrap() >= 1);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn the_last_tokens_sell_out_exactly_at_max_supply() {
+    for curve in curves() {
+        let max = curve.max_supply();
+        for supply in [0, max / 2, max - 1_000_000, max - 1] {
+            let cost = curve.calculate_buy_cost(supply, max - supply).unwrap();
+            assert_eq!(curve.calculate_tokens_out(cost, supply).unwrap(), max - supply);
+            assert_eq!(
+                error_of(curve.calculate_tokens_out(cost + 1, supply)),
+                custom_error(XGrowthError::MaxSupplyReached)
+            );
+        }
+        assert_eq!(
+            error_of(curve.calculate_tokens_out(1, max)),
+            custom_error(XGrowthError::MaxSupplyReached)
+        );
+        assert_eq!(curve.calculate_reserve(max).unwrap(), curve.calculate_buy_cost(0, max).unwrap());
+    }
+}
+
+#[test]
+fn reserve_always_covers_the_integral_of_the_supply() {
+    for (seed, curve) in curves().into_iter().enumerate() {
+        let max = curve.max_supply();
+        let mut rng = Lcg(seed as u64 + 1);
+        let (mut supply, mut reserve) = (0u64, 0u64);
+        
+        for _ in 0..300 {
+            // Amounts spread over many orders of magnitude, down to single base units
+            let magnitude = 10u64.pow(rng.next(12) as u32);
+            match rng.next(4) {
+                // Buy for a USDT amount: the reserve keeps all of it
+                0 => {
+                    let amount = rng.next(magnitude) + 1;
+                    if let Ok(tokens) = curve.calculate_tokens_out(amount, supply) {
+                        supply += tokens;
+                        reserve += amount;
+                    }
+                }
+                // Buy an exact token amount at its cost
+                1 => {
+                    let tokens = (rng.next(magnitude) + 1).min(max - supply);
+                    reserve += curve.calculate_buy_cost(supply, tokens).unwrap();
+                    supply += tokens;
+                }
+                // Sell a token amount for its return
+                2 => {
+                    let tokens = rng.next(supply + 1);
+                    reserve -= curve.calculate_sell_return(supply, tokens).unwrap();
+                    supply -= tokens;
+                }
+                // Sell for an exact USDT amount
+                _ => {
+                    let amount = rng.next(magnitude) + 1;
+                    if let Ok(tokens) = curve.calculate_tokens_in(amount, supply) {
+                        let returned = curve.calculate_sell_return(supply, tokens).unwrap();
+                        assert!(returned >= amount);
+                        reserve -= returned;
+                        supply -= tokens;
+                    }
+                }
+            }
+            assert!(reserve >= curve.calculate_reserve(supply).unwrap(), "supply {supply}, reserve {reserve}");
+        }
+        
+        // Everyone can still sell out
+        let returned = curve.calculate_sell_return(supply, supply).unwrap();
+        assert!(returned <= reserve);
+    }
+}
+
+#[test]
+fn create_agent_rejects_invalid_curve_params() {
+    let mut fixture = Fixture::new();
+    let creator = fixture.creator;
+    let quadratic = QuadraticCurve { base_price: 10_000, curve_factor: FACTOR_PRECISION, max_supply: MAX_SUPPLY };
+    let invalid = [
+        QuadraticCurve { curve_factor: 0, ..quadratic },
+        QuadraticCurve { curve_factor: QuadraticCurve::MAX_CURVE_FACTOR + 1, ..quadratic },
+        QuadraticCurve { base_price: 0, ..quadratic },
+        QuadraticCurve { max_supply: 0, ..quadratic },
+    ];
+    for (i, curve) in invalid.into_iter().enumerate() {
+        assert_eq!(
+            fixture.create_agent(&format!("invalid-{i}"), creator, BondingCurveParams::Quadratic(curve)),
+            Err(custom_error(XGrowthError::InvalidBondingCurve))
+        );
+    }
+    
+    let linear = LinearCurve { base_price: 10_000, final_price: 50_000, max_supply: MAX_SUPPLY };
+    for (i, curve) in [
+        LinearCurve { base_price: 0, ..linear },
+        LinearCurve { max_supply: 0, ..linear },
+        LinearCurve { final_price: 9_999, ..linear },
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        assert_eq!(
+            fixture.create_agent(&format!("invalid-linear-{i}"), creator, BondingCurveParams::Linear(curve)),
+            Err(custom_error(XGrowthError::InvalidBondingCurve))
+        );
+    }
+    assert_eq!(fixture.platform_state().total_agents, 1);
+    
+    // The steepest accepted curve is still fine
+    let steepest = QuadraticCurve { curve_factor: QuadraticCurve::MAX_CURVE_FACTOR, ..quadratic };
+    fixture.create_agent("steepest", creator, BondingCurveParams::Quadratic(steepest)).unwrap();
+    assert_eq!(fixture.platform_state().total_agents, 2);
+}
+
+#[test]
+fn curve_families_start_at_their_base_price_and_rise() {
+    assert_eq!(EXPONENTIAL.calculate_price(0).unwrap(), 10_000);
+    assert_eq!(EXPONENTIAL.calculate_price(MAX_SUPPLY / 5).unwrap(), 27_182);
+    assert_eq!(EXPONENTIAL.calculate_price(MAX_SUPPLY).unwrap(), 1_484_131);
+    
+    // Halfway between base and max price at the midpoint, close to them at either end
+    assert_eq!(SIGMOID.calculate_price(MAX_SUPPLY / 2).unwrap(), 55_000);
+    assert_eq!(SIGMOID.calculate_price(0).unwrap(), 10_602);
+    assert_eq!(SIGMOID.calculate_price(MAX_SUPPLY).unwrap(), 99_397);
+    
+    // Virtual USDT per virtual token, rising as the virtual token side drains
+    let virtual_price = CONSTANT_PRODUCT.virtual_usdt_reserve as u128 * PRICE_PRECISION as u128
+        / CONSTANT_PRODUCT.virtual_token_reserve as u128;
+    assert_eq!(CONSTANT_PRODUCT.calculate_price(0).unwrap() as u128, virtual_price);
+    
+    for curve in curves() {
+        let max = curve.max_supply();
+        let prices: Vec<u64> = (0..=20).map(|i| curve.calculate_price(max / 20 * i).unwrap()).collect();
+        assert!(prices.windows(2).all(|pair| pair[0] <= pair[1]), "{prices:?}");
+    }
+}
+
+#[test]
+fn reserve_is_the_integral_of_the_price() {
+    for curve in curves() {
+        let max = curve.max_supply();
+        assert_eq!(curve.calculate_reserve(0).unwrap(), 0);
+        
+        // Over a hundredth of the supply the integral is the midpoint price times the width
+        let width = max / 100;
+        for step in 0..100 {
+            let start = width * step;
+            let actual = curve.calculate_buy_cost(start, width).unwrap() as u128;
+            let price = curve.calculate_price(start + width / 2).unwrap() as u128;
+            let expected = price * width as u128 / PRICE_PRECISION as u128;
+            assert!(
+                actual.abs_diff(expected) <= expected / 1_000 + 1,
+                "from {start}: integral {actual}, midpoint estimate {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn inverses_round_trip_through_the_integral() {
+    // The closed-form inverses land within a token of the supply they were computed from
+    for supply in [0, 1_000_000, MAX_SUPPLY / 7, MAX_SUPPLY / 2, MAX_SUPPLY - 1] {
+        let reserve = EXPONENTIAL.calculate_reserve(supply).unwrap();
+        assert!(EXPONENTIAL.estimate_supply(reserve).unwrap().abs_diff(supply) <= 1_000_000);
+    }
+    for supply in [0, 1_000_000, CONSTANT_PRODUCT.max_supply / 7, CONSTANT_PRODUCT.max_supply - 1] {
+        let reserve = CONSTANT_PRODUCT.calculate_reserve(supply).unwrap();
+        assert!(CONSTANT_PRODUCT.estimate_supply(reserve).unwrap().abs_diff(supply) <= 1_000_000);
+    }
+    
+    // Whatever the estimate, buying back the cost of a trade gets exactly its tokens, and selling
+    // for its return burns exactly as many
+    for curve in curves() {
+        let max = curve.max_supply();
+        for supply in [0, max / 10, max / 2, max / 10 * 9] {
+            for tokens in [1, 1_000_000, max / 100, max / 10 - 1] {
+                let cost = curve.calculate_buy_cost(supply, tokens).unwrap();
+                let bought = curve.calculate_tokens_out(cost, supply).unwrap();
+                assert!(bought >= tokens);
+                assert_eq!(curve.calculate_buy_cost(supply, bought).unwrap(), cost);
+                
+                let returned = curve.calculate_sell_return(supply + tokens, tokens).unwrap();
+                if returned > 0 {
+                    let burned = curve.calculate_tokens_in(returned, supply + tokens).unwrap();
+                    assert!(burned <= tokens);
+                    assert_eq!(curve.calculate_sell_return(supply + tokens, burned).unwrap(), returned);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn every_curve_family_keeps_the_reserve_vault_balanced() {
+    for curve in curves() {
+        let mut fixture = Fixture::with_curve(curve);
+        let alice = fixture.new_trader(10_000 * USDT);
+        let bob = fixture.new_trader(10_000 * USDT);
+        
+        fixture.buy(&alice, 1_000 * USDT, None).unwrap();
+        fixture.buy(&bob, 2_500 * USDT, None).unwrap();
+        fixture.assert_vaults_balanced();
+        let agent = fixture.agent_state();
+        assert!(agent.reserve_balance >= curve.calculate_reserve(agent.circulating_supply).unwrap());
+        
+        let alice_tokens = fixture.bank.token_balance(&alice.token_account);
+        fixture.sell(&alice, alice_tokens / 3, None).unwrap();
+        fixture.assert_vaults_balanced();
+        
+        // Everyone sells out; the reserve keeps only what rounding left behind
+        fixture.sell(&alice, alice_tokens - alice_tokens / 3, None).unwrap();
+        let bob_tokens = fixture.bank.token_balance(&bob.token_account);
+        fixture.sell(&bob, bob_tokens, None).unwrap();
+        fixture.assert_vaults_balanced();
+        let agent = fixture.agent_state();
+        assert_eq!(agent.circulating_supply, 0);
+        assert!(agent.reserve_balance <= 10);
+        
+        let paid_out = fixture.bank.token_balance(&alice.usdt_account) + fixture.bank.token_balance(&bob.usdt_account);
+        assert!(paid_out <= 20_000 * USDT);
+    }
+}
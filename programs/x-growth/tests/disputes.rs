@@ -0,0 +1,324 @@
+// Finalized epoch scores sit in a dispute window before they are credited
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::events::{AgentScoreDisputed, ScoreDisputeLapsed, ScoreDisputeResolved};
+use x_growth::state::{MetricReport, Platform, ScoreTransforms};
+
+const WINDOW: i64 = 3_600;
+
+fn likes(likes: u64) -> MetricReport {
+    MetricReport { version: MetricReport::V1, values: vec![likes, 0, 0, 0] }
+}
+
+// Two agents with equal scores in a finalized epoch 1, a guardian, and an open dispute window
+fn finalized_epoch() -> (Fixture, Pubkey, Pubkey) {
+    let mut fixture = Fixture::new();
+    let guardian = Pubkey::new_unique();
+    fixture.bank.fund(guardian, 1_000_000_000);
+    fixture.set_dispute_config(Some(guardian), WINDOW).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    
+    let agent = fixture.agent;
+    let other = fixture.add_agent("other-agent", Pubkey::new_unique());
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.update_performance(other, 10, 0, 0, 0).unwrap();
+    
+    fixture.begin_epoch().unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.record_agent_score(other, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    (fixture, guardian, other)
+}
+
+#[test]
+fn credits_wait_for_the_dispute_window() {
+    let (mut fixture, guardian, other) = finalized_epoch();
+    let agent = fixture.agent;
+    let finalized_at = unix_timestamp();
+    assert_eq!(fixture.epoch_state(1).dispute_end, finalized_at + WINDOW);
+    
+    // No dispute is open yet, but the crank still cannot credit ahead of one
+    assert_eq!(
+        fixture.distribute_rewards(agent, 1),
+        Err(custom_error(XGrowthError::DisputeWindowOpen))
+    );
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    
+    set_unix_timestamp(finalized_at + WINDOW);
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(fixture.agent_state_of(&other).total_rewards_earned, 100 * USDT);
+}
+
+#[test]
+fn a_resolved_dispute_is_credited_before_the_window_closes() {
+    let (mut fixture, guardian, other) = finalized_epoch();
+    let agent = fixture.agent;
+    
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    fixture.resolve_dispute(guardian, agent, 1, likes(10)).unwrap();
+    fixture.distribute_rewards(agent, 1).unwrap();
+    assert_eq!(fixture.agent_state().total_rewards_earned, 100 * USDT);
+    
+    // Undisputed agents still wait for the window
+    assert_eq!(
+        fixture.distribute_rewards(other, 1),
+        Err(custom_error(XGrowthError::DisputeWindowOpen))
+    );
+    set_unix_timestamp(fixture.epoch_state(1).dispute_end);
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(fixture.agent_state_of(&other).total_rewards_earned, 100 * USDT);
+}
+
+#[test]
+fn an_unresolved_dispute_lapses_to_the_recorded_score() {
+    let (mut fixture, guardian, other) = finalized_epoch();
+    let agent = fixture.agent;
+    let recorded_score = fixture.agent_state().epoch_score;
+    
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    let resolve_by = fixture.dispute_state(&agent, 1).resolve_by;
+    assert_eq!(resolve_by, fixture.epoch_state(1).dispute_end + Platform::DISPUTE_RESOLUTION_PERIOD);
+    
+    set_unix_timestamp(resolve_by - 1);
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(
+        fixture.distribute_rewards(agent, 1),
+        Err(custom_error(XGrowthError::ScoreDisputed))
+    );
+    
+    // Past the deadline the dispute can no longer be resolved, and the agent is credited as recorded
+    set_unix_timestamp(resolve_by);
+    assert_eq!(
+        fixture.resolve_dispute(guardian, agent, 1, likes(5)),
+        Err(custom_error(XGrowthError::DisputeLapsed))
+    );
+    fixture.distribute_rewards(agent, 1).unwrap();
+    let lapsed = fixture.bank.events::<ScoreDisputeLapsed>();
+    assert_eq!(lapsed.len(), 1);
+    assert_eq!(lapsed[0].recorded_score, recorded_score);
+    assert_eq!(fixture.agent_state().total_rewards_earned, 100 * USDT);
+    assert_eq!(fixture.epoch_state(1).open_disputes, 0);
+    
+    // And the next epoch goes ahead
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.update_performance(other, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent, other]);
+    assert_eq!(fixture.agent_state().total_rewards_earned, 200 * USDT);
+}
+
+#[test]
+fn a_disputed_share_is_frozen_until_corrected() {
+    let (mut fixture, guardian, other) = finalized_epoch();
+    let agent = fixture.agent;
+    
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    let disputed = fixture.bank.events::<AgentScoreDisputed>();
+    assert_eq!(disputed.len(), 1);
+    assert_eq!(disputed[0].challenger, guardian);
+    assert_eq!(fixture.epoch_state(1).open_disputes, 1);
+    
+    // The other agent is credited when the window closes, the disputed one is not
+    set_unix_timestamp(unix_timestamp() + WINDOW);
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(
+        fixture.distribute_rewards(agent, 1),
+        Err(custom_error(XGrowthError::ScoreDisputed))
+    );
+    
+    // The agent really had half the likes, so it gets half its share
+    let recorded_score = fixture.agent_state().epoch_score;
+    fixture.resolve_dispute(guardian, agent, 1, likes(5)).unwrap();
+    let resolved = fixture.bank.events::<ScoreDisputeResolved>();
+    assert_eq!(resolved[0].recorded_score, recorded_score);
+    assert_eq!(resolved[0].credited_score, recorded_score / 2);
+    let dispute = fixture.dispute_state(&agent, 1);
+    assert!(dispute.resolved);
+    assert_eq!(dispute.corrected_values[0], 5);
+    assert_eq!(fixture.epoch_state(1).open_disputes, 0);
+    
+    fixture.distribute_rewards(agent, 1).unwrap();
+    assert_eq!(fixture.agent_state().total_rewards_earned, 50 * USDT);
+    assert_eq!(fixture.agent_state_of(&other).total_rewards_earned, 100 * USDT);
+    
+    // What the correction took off goes back to the pool
+    assert_eq!(fixture.platform_state().unallocated_rewards, 850 * USDT);
+    
+    assert_eq!(
+        fixture.resolve_dispute(guardian, agent, 1, likes(5)),
+        Err(custom_error(XGrowthError::DisputeAlreadyResolved))
+    );
+}
+
+#[test]
+fn a_correction_cannot_raise_a_share() {
+    let (mut fixture, _, other) = finalized_epoch();
+    let agent = fixture.agent;
+    let authority = fixture.authority;
+    
+    // The platform authority can dispute and resolve as well
+    fixture.dispute_agent_score(authority, agent, 1).unwrap();
+    fixture.resolve_dispute(authority, agent, 1, likes(1_000)).unwrap();
+    
+    // The epoch total is fixed, so a higher corrected score would take from the other agent.
+    // The under-reported agent keeps its recorded score.
+    let dispute = fixture.dispute_state(&agent, 1);
+    assert!(dispute.corrected_score > dispute.recorded_score);
+    assert_eq!(fixture.agent_state().epoch_score, dispute.recorded_score);
+    let resolved = fixture.bank.events::<ScoreDisputeResolved>();
+    assert_eq!(resolved[0].corrected_score, dispute.corrected_score);
+    assert_eq!(resolved[0].credited_score, dispute.recorded_score);
+    
+    set_unix_timestamp(unix_timestamp() + WINDOW);
+    fixture.distribute_rewards(agent, 1).unwrap();
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(fixture.agent_state().total_rewards_earned, 100 * USDT);
+    assert_eq!(fixture.agent_state_of(&other).total_rewards_earned, 100 * USDT);
+}
+
+#[test]
+fn a_capped_agent_corrected_below_the_cap_keeps_at_most_its_share() {
+    let mut fixture = Fixture::new();
+    let guardian = Pubkey::new_unique();
+    fixture.bank.fund(guardian, 1_000_000_000);
+    fixture.set_dispute_config(Some(guardian), WINDOW).unwrap();
+    fixture.set_score_transforms(ScoreTransforms { max_agent_share_bps: 5_000, ..ScoreTransforms::DEFAULT }).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    
+    // The agent scores ten times the other and is capped at half the 200 USDT pool
+    let agent = fixture.agent;
+    let other = fixture.add_agent("other-agent", Pubkey::new_unique());
+    fixture.update_performance(agent, 100, 0, 0, 0).unwrap();
+    fixture.update_performance(other, 10, 0, 0, 0).unwrap();
+    fixture.begin_epoch().unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.record_agent_score(other, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    let epoch = fixture.epoch_state(1);
+    assert_eq!(epoch.capped_agents, 1);
+    assert_eq!(epoch.share_cap, 100 * USDT);
+    
+    // Just below the cap threshold, the uncapped formula alone would pay 990 USDT
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    fixture.resolve_dispute(guardian, agent, 1, likes(99)).unwrap();
+    assert!(fixture.agent_state().epoch_score < epoch.capped_score_threshold);
+    
+    set_unix_timestamp(unix_timestamp() + WINDOW);
+    fixture.distribute_rewards(agent, 1).unwrap();
+    fixture.distribute_rewards(other, 1).unwrap();
+    assert_eq!(fixture.agent_state().total_rewards_earned, 100 * USDT);
+    assert_eq!(fixture.agent_state_of(&other).total_rewards_earned, 100 * USDT);
+    
+    let epoch = fixture.epoch_state(1);
+    assert_eq!(epoch.total_credited, 200 * USDT);
+    assert_eq!(fixture.platform_state().unallocated_rewards, 800 * USDT);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn only_the_authority_or_guardian_dispute_within_the_window() {
+    let (mut fixture, guardian, other) = finalized_epoch();
+    let agent = fixture.agent;
+    
+    let outsider = Pubkey::new_unique();
+    fixture.bank.fund(outsider, 1_000_000_000);
+    assert_eq!(
+        fixture.dispute_agent_score(outsider, agent, 1),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    // One dispute per agent and epoch
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    assert!(fixture.dispute_agent_score(guardian, agent, 1).is_err());
+    assert_eq!(
+        fixture.resolve_dispute(outsider, agent, 1, likes(5)),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    set_unix_timestamp(unix_timestamp() + WINDOW);
+    assert_eq!(
+        fixture.dispute_agent_score(guardian, other, 1),
+        Err(custom_error(XGrowthError::DisputeWindowClosed))
+    );
+}
+
+#[test]
+fn scores_cannot_be_disputed_before_the_epoch_is_finalized() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.set_dispute_config(None, WINDOW).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.begin_epoch().unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    
+    let authority = fixture.authority;
+    assert_eq!(
+        fixture.dispute_agent_score(authority, agent, 1),
+        Err(custom_error(XGrowthError::EpochNotFinalized))
+    );
+}
+
+#[test]
+fn merkle_roots_wait_for_every_dispute() {
+    let (mut fixture, guardian, _) = finalized_epoch();
+    let agent = fixture.agent;
+    let authority = fixture.authority;
+    let post = |fixture: &mut Fixture| {
+        fixture.bank.process(
+            x_growth::accounts::PostMerkleRoot {
+                agent,
+                epoch: Fixture::epoch_pda(1),
+                distribution: pda(&[b"distribution", agent.as_ref(), 1u64.to_le_bytes().as_ref()]),
+                platform: fixture.platform,
+                dispute: Fixture::dispute_pda(&agent, 1),
+                poster: authority,
+                system_program: anchor_lang::system_program::ID,
+            },
+            x_growth::instruction::PostMerkleRoot { root: [1; 32], total_amount: 10 * USDT, num_leaves: 1 },
+        )
+    };
+    
+    fixture.dispute_agent_score(guardian, agent, 1).unwrap();
+    assert_eq!(post(&mut fixture), Err(custom_error(XGrowthError::DisputeWindowOpen)));
+    
+    set_unix_timestamp(unix_timestamp() + WINDOW);
+    assert_eq!(post(&mut fixture), Err(custom_error(XGrowthError::ScoreDisputed)));
+    
+    fixture.resolve_dispute(guardian, agent, 1, likes(10)).unwrap();
+    post(&mut fixture).unwrap();
+}
+
+#[test]
+fn set_dispute_config_is_bounded_and_authority_only() {
+    let mut fixture = Fixture::new();
+    let guardian = Pubkey::new_unique();
+    
+    assert_eq!(
+        fixture.set_dispute_config(Some(guardian), Platform::MAX_DISPUTE_WINDOW + 1),
+        Err(custom_error(XGrowthError::InvalidDisputeWindow))
+    );
+    assert_eq!(
+        fixture.set_dispute_config(Some(guardian), -1),
+        Err(custom_error(XGrowthError::InvalidDisputeWindow))
+    );
+    
+    let platform = fixture.platform;
+    let outsider = fixture.new_trader(0);
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetDisputeConfig { platform, authority: outsider.key },
+            x_growth::instruction::SetDisputeConfig { guardian: Some(outsider.key), dispute_window: WINDOW },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    fixture.set_dispute_config(Some(guardian), Platform::MAX_DISPUTE_WINDOW).unwrap();
+    let state = fixture.platform_state();
+    assert_eq!(state.guardian, Some(guardian));
+    assert_eq!(state.dispute_window, Platform::MAX_DISPUTE_WINDOW);
+}
@@ -0,0 +1,168 @@
+// Epoch crank: record scores, finalize, credit each agent its share of the pool
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::{Platform, ScoreTransforms, ScoreWeights};
+
+// Platform with two agents and an ended first epoch
+fn ended_epoch() -> (Fixture, Pubkey, Pubkey) {
+    let mut fixture = Fixture::new();
+    let first = fixture.agent;
+    let second = fixture.add_agent("second-agent", Pubkey::new_unique());
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+
+    fixture.begin_epoch().unwrap();
+    // Scores 400 and 100
+    fixture.update_performance(first, 100, 1_000, 50, 20).unwrap();
+    fixture.update_performance(second, 50, 500, 0, 0).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    (fixture, first, second)
+}
+
+#[test]
+fn epoch_splits_pool_by_score() {
+    let (mut fixture, first, second) = ended_epoch();
+
+    fixture.record_agent_score(first, 1).unwrap();
+    fixture.record_agent_score(second, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(first, 1).unwrap();
+    fixture.distribute_rewards(second, 1).unwrap();
+
+    let epoch = fixture.epoch_state(1);
+    assert_eq!(epoch.total_score, 500);
+    assert_eq!(epoch.agents_credited, 2);
+    assert_eq!(epoch.total_credited, 200 * USDT);
+    assert_eq!(fixture.agent_state_of(&first).total_rewards_earned, 160 * USDT);
+    assert_eq!(fixture.agent_state_of(&second).total_rewards_earned, 40 * USDT);
+
+    // Daily metrics start over for the next epoch
+    let performance = fixture.agent_state_of(&first).performance;
+    assert_eq!(performance.calculate_score(&ScoreWeights::DEFAULT, &ScoreTransforms::DEFAULT).unwrap(), 0);
+    fixture.begin_epoch().unwrap();
+    assert_eq!(fixture.platform_state().current_epoch, 2);
+}
+
+#[test]
+fn epoch_rejects_double_counting() {
+    let (mut fixture, first, second) = ended_epoch();
+
+    fixture.record_agent_score(first, 1).unwrap();
+    assert_eq!(
+        fixture.record_agent_score(first, 1),
+        Err(custom_error(XGrowthError::ScoreAlreadyRecorded))
+    );
+    fixture.record_agent_score(second, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+
+    fixture.distribute_rewards(first, 1).unwrap();
+    assert_eq!(
+        fixture.distribute_rewards(first, 1),
+        Err(custom_error(XGrowthError::RewardsAlreadyCredited))
+    );
+    assert_eq!(
+        fixture.finalize_epoch(1),
+        Err(custom_error(XGrowthError::EpochAlreadyFinalized))
+    );
+}
+
+#[test]
+fn epoch_rejects_skipped_agents() {
+    let (mut fixture, first, second) = ended_epoch();
+
+    fixture.record_agent_score(first, 1).unwrap();
+    assert_eq!(
+        fixture.finalize_epoch(1),
+        Err(custom_error(XGrowthError::AgentsNotRecorded))
+    );
+    assert_eq!(
+        fixture.distribute_rewards(first, 1),
+        Err(custom_error(XGrowthError::EpochNotFinalized))
+    );
+    assert_eq!(
+        fixture.begin_epoch(),
+        Err(custom_error(XGrowthError::PreviousEpochNotFinalized))
+    );
+
+    fixture.record_agent_score(second, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+
+    // An agent left uncredited cannot be recorded into the next epoch
+    fixture.distribute_rewards(first, 1).unwrap();
+    fixture.begin_epoch().unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(first, 2).unwrap();
+    assert_eq!(
+        fixture.record_agent_score(second, 2),
+        Err(custom_error(XGrowthError::RewardsNotCredited))
+    );
+    fixture.distribute_rewards(second, 1).unwrap();
+    fixture.record_agent_score(second, 2).unwrap();
+}
+
+#[test]
+fn epoch_waits_for_metrics_window() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.begin_epoch().unwrap();
+
+    assert_eq!(
+        fixture.record_agent_score(agent, 1),
+        Err(custom_error(XGrowthError::EpochNotEnded))
+    );
+    assert_eq!(
+        fixture.finalize_epoch(1),
+        Err(custom_error(XGrowthError::EpochNotEnded))
+    );
+}
+
+#[test]
+fn epoch_refuses_to_finalize_beyond_funded_rewards() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(150 * USDT).unwrap();
+    fixture.begin_epoch().unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+
+    assert_eq!(
+        fixture.finalize_epoch(1),
+        Err(custom_error(XGrowthError::RewardPoolUnderfunded))
+    );
+
+    fixture.fund_reward_pool(100 * USDT).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(agent, 1).unwrap();
+
+    let platform = fixture.platform_state();
+    assert_eq!(platform.total_rewards_funded, 250 * USDT);
+    assert_eq!(platform.total_rewards_allocated, 200 * USDT);
+    assert_eq!(platform.unallocated_rewards, 50 * USDT);
+    assert_eq!(fixture.bank.token_balance(&fixture.reward_pool), 250 * USDT);
+}
+
+#[test]
+fn epoch_returns_rounding_dust_to_the_pool() {
+    let (mut fixture, first, second) = ended_epoch();
+    let third = fixture.add_agent("third-agent", Pubkey::new_unique());
+    fixture.update_performance(third, 25, 0, 0, 0).unwrap();
+
+    // Scores 400, 100 and 100 split 200 USDT with a unit of dust
+    for agent in [first, second, third] {
+        fixture.record_agent_score(agent, 1).unwrap();
+    }
+    fixture.finalize_epoch(1).unwrap();
+    assert_eq!(fixture.platform_state().total_rewards_allocated, 200 * USDT);
+    for agent in [first, second, third] {
+        fixture.distribute_rewards(agent, 1).unwrap();
+    }
+
+    let credited = fixture.epoch_state(1).total_credited;
+    assert!(credited < 200 * USDT);
+    let platform = fixture.platform_state();
+    assert_eq!(platform.total_rewards_allocated, credited);
+    assert_eq!(platform.unallocated_rewards, 1_000 * USDT - credited);
+}
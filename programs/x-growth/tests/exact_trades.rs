@@ -0,0 +1,95 @@
+// Exact-output buys: a fixed token amount for at most max_usdt_in
+mod common;
+
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::FeeConfig;
+
+// 1% buys, 2% sells, split 50/30/20 between protocol, creator and referrer
+const FEES: FeeConfig = FeeConfig {
+    buy_fee_bps: 100,
+    sell_fee_bps: 200,
+    protocol_share_bps: 5_000,
+    creator_share_bps: 3_000,
+    referrer_share_bps: 2_000,
+    max_agent_fee_bps: 500,
+};
+
+const TOKEN: u64 = 1_000_000;
+
+fn buy_exact(fixture: &mut Fixture, trader: &Trader, token_amount: u64, max_usdt_in: u64) -> ProgramResult {
+    let accounts = fixture.buy_accounts(trader, None);
+    fixture.bank.process(
+        accounts,
+        x_growth::instruction::BuyExactTokens { token_amount, max_usdt_in },
+    )
+}
+
+#[test]
+fn buy_exact_tokens_mints_the_requested_amount() {
+    let mut fixture = Fixture::new();
+    fixture.update_fee_config(FEES).unwrap();
+    let trader = fixture.new_trader(10_000 * USDT);
+    fixture.buy(&trader, 1_000 * USDT, None).unwrap();
+    
+    let agent = fixture.agent_state();
+    let tokens_before = fixture.bank.token_balance(&trader.token_account);
+    let usdt_before = fixture.bank.token_balance(&trader.usdt_account);
+    let reserve_before = fixture.bank.token_balance(&fixture.reserve);
+    let quote = agent.quote_buy_exact_tokens(&FEES, 25_000 * TOKEN, false).unwrap();
+    
+    // The exact cost is accepted as the bound
+    buy_exact(&mut fixture, &trader, 25_000 * TOKEN, quote.usdt_in).unwrap();
+    
+    assert_eq!(fixture.bank.token_balance(&trader.token_account), tokens_before + 25_000 * TOKEN);
+    assert_eq!(fixture.bank.token_balance(&trader.usdt_account), usdt_before - quote.usdt_in);
+    
+    // The reserve takes the curve cost, the fee goes to the treasury
+    let cost = agent.bonding_curve.calculate_buy_cost(agent.circulating_supply, 25_000 * TOKEN).unwrap();
+    assert_eq!(quote.usdt_in - quote.fee, cost);
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), reserve_before + cost);
+    let agent = fixture.agent_state();
+    assert_eq!(agent.circulating_supply, tokens_before + 25_000 * TOKEN);
+    assert!(agent.reserve_balance >= agent.bonding_curve.calculate_reserve(agent.circulating_supply).unwrap());
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn buy_exact_tokens_respects_max_usdt_in() {
+    let mut fixture = Fixture::new();
+    fixture.update_fee_config(FEES).unwrap();
+    let trader = fixture.new_trader(10_000 * USDT);
+    let quote = fixture
+        .agent_state()
+        .quote_buy_exact_tokens(&FEES, 10_000 * TOKEN, false)
+        .unwrap();
+    
+    assert_eq!(
+        buy_exact(&mut fixture, &trader, 10_000 * TOKEN, quote.usdt_in - 1),
+        Err(custom_error(XGrowthError::SlippageExceeded))
+    );
+    assert_eq!(fixture.bank.token_balance(&trader.token_account), 0);
+    assert_eq!(fixture.bank.token_balance(&trader.usdt_account), 10_000 * USDT);
+    assert_eq!(fixture.agent_state().circulating_supply, 0);
+    
+    // A looser bound only ever charges the exact cost
+    buy_exact(&mut fixture, &trader, 10_000 * TOKEN, quote.usdt_in * 2).unwrap();
+    assert_eq!(fixture.bank.token_balance(&trader.usdt_account), 10_000 * USDT - quote.usdt_in);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn buy_exact_tokens_stops_at_max_supply() {
+    let mut fixture = Fixture::new();
+    let trader = fixture.new_trader(100_000_000 * USDT);
+    let max_supply = fixture.agent_state().bonding_curve.max_supply();
+    
+    assert_eq!(
+        buy_exact(&mut fixture, &trader, max_supply + 1, u64::MAX),
+        Err(custom_error(XGrowthError::MaxSupplyReached))
+    );
+    buy_exact(&mut fixture, &trader, max_supply, u64::MAX).unwrap();
+    assert_eq!(fixture.agent_state().circulating_supply, max_supply);
+    fixture.assert_vaults_balanced();
+}
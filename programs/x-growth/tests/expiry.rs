@@ -0,0 +1,195 @@
+// Unclaimed rewards expire after the claim window and are swept back to the pool or the treasury
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_spl::token::spl_token;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::Platform;
+
+const WINDOW: i64 = Platform::MIN_CLAIM_WINDOW;
+
+fn set_claim_window(fixture: &mut Fixture, authority: Pubkey, claim_window: i64, sweep_to_treasury: bool) -> ProgramResult {
+    fixture.bank.process(
+        x_growth::accounts::SetClaimWindow { platform: fixture.platform, authority },
+        x_growth::instruction::SetClaimWindow { claim_window, sweep_to_treasury },
+    )
+}
+
+fn sweep(fixture: &mut Fixture, user: &Pubkey) -> ProgramResult {
+    fixture.bank.process(
+        x_growth::accounts::SweepExpiredRewards {
+            agent: fixture.agent,
+            user_rewards: Fixture::user_rewards_pda(user, &fixture.agent),
+            platform: fixture.platform,
+            reward_pool: fixture.reward_pool,
+            treasury: fixture.treasury,
+            token_program: spl_token::ID,
+        },
+        x_growth::instruction::SweepExpiredRewards {},
+    )
+}
+
+// Alice earns one epoch of rewards and unstakes, leaving 200 USDT pending.
+// Returns her and the time the rewards became pending.
+fn pending_rewards() -> (Fixture, Trader, i64) {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let alice = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&alice, 100 * USDT, None).unwrap();
+    let tokens = fixture.bank.token_balance(&alice.token_account);
+    fixture.stake(&alice, tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    fixture.unstake(&alice, tokens).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).pending_rewards, 200 * USDT);
+    (fixture, alice, unix_timestamp())
+}
+
+#[test]
+fn expired_rewards_return_to_the_pool() {
+    let (mut fixture, alice, accrued_at) = pending_rewards();
+    let authority = fixture.authority;
+    set_claim_window(&mut fixture, authority, WINDOW, false).unwrap();
+    let pending = fixture.user_rewards_state(&alice.key).pending_rewards;
+    let unallocated = fixture.platform_state().unallocated_rewards;
+
+    set_unix_timestamp(accrued_at + WINDOW - 1);
+    assert_eq!(
+        sweep(&mut fixture, &alice.key),
+        Err(custom_error(XGrowthError::RewardsNotExpired))
+    );
+
+    set_unix_timestamp(accrued_at + WINDOW);
+    sweep(&mut fixture, &alice.key).unwrap();
+
+    let rewards = fixture.user_rewards_state(&alice.key);
+    assert_eq!(rewards.pending_rewards, 0);
+    assert_eq!(rewards.expired_rewards, pending);
+    let platform = fixture.platform_state();
+    assert_eq!(platform.unallocated_rewards, unallocated + pending);
+    assert_eq!(platform.total_rewards_expired, pending);
+    assert_eq!(fixture.bank.token_balance(&fixture.reward_pool), 1_000 * USDT);
+
+    assert_eq!(
+        fixture.claim_rewards(alice.key, alice.usdt_account),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+    assert_eq!(
+        sweep(&mut fixture, &alice.key),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+}
+
+#[test]
+fn expired_rewards_can_go_to_the_treasury() {
+    let (mut fixture, alice, accrued_at) = pending_rewards();
+    let authority = fixture.authority;
+    set_claim_window(&mut fixture, authority, WINDOW, true).unwrap();
+    let pending = fixture.user_rewards_state(&alice.key).pending_rewards;
+
+    set_unix_timestamp(accrued_at + WINDOW);
+    sweep(&mut fixture, &alice.key).unwrap();
+
+    assert_eq!(fixture.bank.token_balance(&fixture.treasury), pending);
+    assert_eq!(fixture.bank.token_balance(&fixture.reward_pool), 1_000 * USDT - pending);
+    let platform = fixture.platform_state();
+    assert_eq!(platform.protocol_fee_balance, pending);
+    assert_eq!(platform.total_rewards_swept_to_treasury, pending);
+    assert_eq!(
+        platform.total_rewards_funded,
+        platform.unallocated_rewards + platform.total_rewards_allocated + pending
+    );
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn rewards_inside_the_window_are_never_swept() {
+    let (mut fixture, alice, accrued_at) = pending_rewards();
+
+    // Without a claim window nothing ever expires
+    set_unix_timestamp(accrued_at + 10 * WINDOW);
+    assert_eq!(
+        sweep(&mut fixture, &alice.key),
+        Err(custom_error(XGrowthError::RewardsNotExpired))
+    );
+
+    // Newly settled rewards restart the window for the whole pending balance
+    let authority = fixture.authority;
+    set_claim_window(&mut fixture, authority, WINDOW, false).unwrap();
+    let agent = fixture.agent;
+    let tokens = fixture.bank.token_balance(&alice.token_account);
+    fixture.stake(&alice, tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    fixture.unstake(&alice, tokens).unwrap();
+    assert_eq!(
+        sweep(&mut fixture, &alice.key),
+        Err(custom_error(XGrowthError::RewardsNotExpired))
+    );
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 400 * USDT);
+}
+
+#[test]
+fn sweeps_settle_the_stake_first() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let authority = fixture.authority;
+    set_claim_window(&mut fixture, authority, WINDOW, false).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let alice = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&alice, 100 * USDT, None).unwrap();
+    let tokens = fixture.bank.token_balance(&alice.token_account);
+    fixture.stake(&alice, tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    // Half the stake stays earning, with 200 USDT settled by the unstake
+    fixture.unstake(&alice, tokens / 2).unwrap();
+    let accrued_at = unix_timestamp();
+    assert_close(fixture.user_rewards_state(&alice.key).pending_rewards, 200 * USDT);
+
+    // A credit after the window accrues to the stake, and settling it restarts the window
+    set_unix_timestamp(accrued_at + WINDOW);
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert_eq!(
+        sweep(&mut fixture, &alice.key),
+        Err(custom_error(XGrowthError::RewardsNotExpired))
+    );
+
+    // Once nothing has accrued for a whole window, the sweep takes everything, including the
+    // rewards it settles itself
+    set_unix_timestamp(unix_timestamp() + 2 * WINDOW);
+    sweep(&mut fixture, &alice.key).unwrap();
+    let rewards = fixture.user_rewards_state(&alice.key);
+    assert_eq!(rewards.pending_rewards, 0);
+    assert_close(rewards.expired_rewards, 400 * USDT);
+    assert_eq!(
+        fixture.claim_rewards(alice.key, alice.usdt_account),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+}
+
+#[test]
+fn set_claim_window_is_bounded_and_authority_only() {
+    let mut fixture = Fixture::new();
+    let authority = fixture.authority;
+
+    assert_eq!(
+        set_claim_window(&mut fixture, authority, WINDOW - 1, false),
+        Err(custom_error(XGrowthError::InvalidClaimWindow))
+    );
+    assert_eq!(
+        set_claim_window(&mut fixture, Pubkey::new_unique(), WINDOW, false),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    set_claim_window(&mut fixture, authority, 0, false).unwrap();
+    set_claim_window(&mut fixture, authority, WINDOW, true).unwrap();
+    let platform = fixture.platform_state();
+    assert_eq!(platform.claim_window, WINDOW);
+    assert!(platform.sweep_to_treasury);
+}
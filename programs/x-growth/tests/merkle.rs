@@ -0,0 +1,237 @@
+// Merkle distributions: off-chain tree builder, posting roots and claiming with proofs
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_spl::token::spl_token;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::merkle::{self, MerkleTree};
+use x_growth::state::{MerkleDistribution, Platform};
+
+fn distribution_pda(agent: &Pubkey, epoch: u64) -> Pubkey {
+    pda(&[b"distribution", agent.as_ref(), &epoch.to_le_bytes()])
+}
+
+// A finalized epoch with two agents: the fixture agent's share is 160 USDT and the
+// second agent's 40 USDT of the 200 USDT pool
+fn finalized_epoch() -> (Fixture, Pubkey) {
+    let mut fixture = Fixture::new();
+    let first = fixture.agent;
+    let second = fixture.add_agent("second-agent", Pubkey::new_unique());
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+
+    fixture.begin_epoch().unwrap();
+    fixture.update_performance(first, 100, 1_000, 50, 20).unwrap();
+    fixture.update_performance(second, 50, 500, 0, 0).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(first, 1).unwrap();
+    fixture.record_agent_score(second, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    (fixture, second)
+}
+
+fn post_root(fixture: &mut Fixture, poster: Pubkey, epoch: u64, tree: &MerkleTree, total_amount: u64) -> ProgramResult {
+    if !fixture.bank.exists(&poster) {
+        fixture.bank.fund(poster, 1_000_000_000);
+    }
+    fixture.bank.process(
+        x_growth::accounts::PostMerkleRoot {
+            agent: fixture.agent,
+            epoch: Fixture::epoch_pda(epoch),
+            distribution: distribution_pda(&fixture.agent, epoch),
+            platform: fixture.platform,
+            dispute: Fixture::dispute_pda(&fixture.agent, epoch),
+            poster,
+            system_program: anchor_lang::system_program::ID,
+        },
+        x_growth::instruction::PostMerkleRoot {
+            root: tree.root(),
+            total_amount,
+            num_leaves: tree.num_leaves(),
+        },
+    )
+}
+
+fn claim(fixture: &mut Fixture, user: &Trader, epoch: u64, index: u32, amount: u64, proof: Vec<[u8; 32]>) -> ProgramResult {
+    fixture.bank.process(
+        x_growth::accounts::ClaimWithProof {
+            distribution: distribution_pda(&fixture.agent, epoch),
+            user_usdt_account: user.usdt_account,
+            reward_pool: fixture.reward_pool,
+            platform: fixture.platform,
+            user: user.key,
+            token_program: spl_token::ID,
+        },
+        x_growth::instruction::ClaimWithProof { index, amount, proof },
+    )
+}
+
+#[test]
+fn tree_proofs_verify_for_every_leaf() {
+    for size in 1..=9u64 {
+        let leaves: Vec<(Pubkey, u64)> = (0..size).map(|i| (Pubkey::new_unique(), (i + 1) * USDT)).collect();
+        let tree = MerkleTree::new(&leaves);
+        for (index, (user, amount)) in leaves.iter().enumerate() {
+            let index = index as u32;
+            let proof = tree.proof(index);
+            assert!(merkle::verify(&proof, &tree.root(), merkle::leaf_hash(index, user, *amount)));
+            assert!(!merkle::verify(&proof, &tree.root(), merkle::leaf_hash(index, user, amount + 1)));
+            assert!(!merkle::verify(&proof, &tree.root(), merkle::leaf_hash(index + 1, user, *amount)));
+        }
+    }
+}
+
+#[test]
+fn claim_with_proof_pays_each_leaf_once() {
+    let (mut fixture, second) = finalized_epoch();
+    let agent = fixture.agent;
+    let reward_pool = fixture.reward_pool;
+
+    let alice = fixture.new_trader(0);
+    let bob = fixture.new_trader(0);
+    let carol = fixture.new_trader(0);
+    let tree = MerkleTree::new(&[(alice.key, 50 * USDT), (bob.key, 30 * USDT), (carol.key, 20 * USDT)]);
+    let oracle = fixture.oracle;
+    post_root(&mut fixture, oracle, 1, &tree, 100 * USDT).unwrap();
+
+    claim(&mut fixture, &alice, 1, 0, 50 * USDT, tree.proof(0)).unwrap();
+    assert_eq!(fixture.bank.token_balance(&alice.usdt_account), 50 * USDT);
+    assert_eq!(
+        claim(&mut fixture, &alice, 1, 0, 50 * USDT, tree.proof(0)),
+        Err(custom_error(XGrowthError::AlreadyClaimed))
+    );
+
+    // Bob cannot claim Alice's amount, nor a leaf past the end
+    assert_eq!(
+        claim(&mut fixture, &bob, 1, 1, 50 * USDT, tree.proof(1)),
+        Err(custom_error(XGrowthError::InvalidMerkleProof))
+    );
+    assert_eq!(
+        claim(&mut fixture, &bob, 1, 3, 30 * USDT, tree.proof(1)),
+        Err(custom_error(XGrowthError::InvalidLeafIndex))
+    );
+    claim(&mut fixture, &bob, 1, 1, 30 * USDT, tree.proof(1)).unwrap();
+    claim(&mut fixture, &carol, 1, 2, 20 * USDT, tree.proof(2)).unwrap();
+
+    let distribution: MerkleDistribution = fixture.bank.anchor_account(&distribution_pda(&agent, 1));
+    assert_eq!(distribution.total_claimed, 100 * USDT);
+    assert!((0..3).all(|index| distribution.is_claimed(index)));
+    assert_eq!(fixture.bank.token_balance(&reward_pool), 900 * USDT);
+    assert_eq!(fixture.agent_state().total_rewards_earned, 100 * USDT);
+
+    // The root counts as the agent's credit; what it left of the share goes back to the pool
+    // with the epoch's dust once the other agent is credited
+    let platform = fixture.platform_state();
+    assert_eq!(platform.total_rewards_allocated, 200 * USDT);
+    assert_eq!(platform.total_rewards_claimed, 100 * USDT);
+    fixture.distribute_rewards(second, 1).unwrap();
+    let platform = fixture.platform_state();
+    assert_eq!(platform.total_rewards_allocated, 140 * USDT);
+    assert_eq!(platform.unallocated_rewards, 860 * USDT);
+    assert_eq!(fixture.epoch_state(1).total_credited, 140 * USDT);
+}
+
+#[test]
+fn claims_cannot_exceed_the_posted_total() {
+    let (mut fixture, _) = finalized_epoch();
+
+    let alice = fixture.new_trader(0);
+    let bob = fixture.new_trader(0);
+    let tree = MerkleTree::new(&[(alice.key, 60 * USDT), (bob.key, 60 * USDT)]);
+    let authority = fixture.authority;
+    post_root(&mut fixture, authority, 1, &tree, 100 * USDT).unwrap();
+
+    claim(&mut fixture, &alice, 1, 0, 60 * USDT, tree.proof(0)).unwrap();
+    assert_eq!(
+        claim(&mut fixture, &bob, 1, 1, 60 * USDT, tree.proof(1)),
+        Err(custom_error(XGrowthError::DistributionExceeded))
+    );
+}
+
+#[test]
+fn post_merkle_root_requires_oracle_or_authority_and_finalized_epoch() {
+    let mut fixture = Fixture::new();
+    let tree = MerkleTree::new(&[(Pubkey::new_unique(), 10 * USDT)]);
+    let oracle = fixture.oracle;
+    fixture.fund_reward_pool(200 * USDT).unwrap();
+
+    fixture.begin_epoch().unwrap();
+    let agent = fixture.agent;
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    assert_eq!(
+        post_root(&mut fixture, oracle, 1, &tree, 10 * USDT),
+        Err(custom_error(XGrowthError::EpochNotFinalized))
+    );
+
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+
+    assert_eq!(
+        post_root(&mut fixture, Pubkey::new_unique(), 1, &tree, 10 * USDT),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    post_root(&mut fixture, oracle, 1, &tree, 10 * USDT).unwrap();
+
+    // One root per agent and epoch
+    assert!(post_root(&mut fixture, oracle, 1, &tree, 10 * USDT).is_err());
+}
+
+#[test]
+fn roots_are_bounded_by_the_agents_share() {
+    let (mut fixture, _) = finalized_epoch();
+    let tree = MerkleTree::new(&[(Pubkey::new_unique(), 10 * USDT)]);
+    let oracle = fixture.oracle;
+
+    // The fixture agent's share is 160 USDT, not the whole 200 USDT pool
+    assert_eq!(
+        post_root(&mut fixture, oracle, 1, &tree, 160 * USDT + 1),
+        Err(custom_error(XGrowthError::InvalidDistribution))
+    );
+    post_root(&mut fixture, oracle, 1, &tree, 160 * USDT).unwrap();
+    assert_eq!(fixture.epoch_state(1).total_credited, 160 * USDT);
+
+    // An agent that scored nothing has no share to distribute
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let scorer = fixture.add_agent("scorer", Pubkey::new_unique());
+    fixture.fund_reward_pool(200 * USDT).unwrap();
+    fixture.begin_epoch().unwrap();
+    fixture.update_performance(scorer, 10, 0, 0, 0).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.record_agent_score(scorer, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    let oracle = fixture.oracle;
+    assert_eq!(
+        post_root(&mut fixture, oracle, 1, &tree, 1),
+        Err(custom_error(XGrowthError::InvalidDistribution))
+    );
+}
+
+#[test]
+fn an_agent_is_paid_by_root_or_by_credit_not_both() {
+    // Posted first: the crank no longer credits the agent
+    let (mut fixture, _) = finalized_epoch();
+    let agent = fixture.agent;
+    let tree = MerkleTree::new(&[(Pubkey::new_unique(), 100 * USDT)]);
+    let oracle = fixture.oracle;
+    post_root(&mut fixture, oracle, 1, &tree, 100 * USDT).unwrap();
+    assert_eq!(
+        fixture.distribute_rewards(agent, 1),
+        Err(custom_error(XGrowthError::RewardsAlreadyCredited))
+    );
+    assert_eq!(fixture.agent_state().last_credited_epoch, 1);
+
+    // Credited first: no root can be posted for the agent
+    let (mut fixture, _) = finalized_epoch();
+    let agent = fixture.agent;
+    let oracle = fixture.oracle;
+    fixture.distribute_rewards(agent, 1).unwrap();
+    assert_eq!(
+        post_root(&mut fixture, oracle, 1, &tree, 100 * USDT),
+        Err(custom_error(XGrowthError::RewardsAlreadyCredited))
+    );
+    assert_eq!(fixture.agent_state().total_rewards_earned, 160 * USDT);
+}
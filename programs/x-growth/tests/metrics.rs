@@ -0,0 +1,315 @@
+// Versioned oracle metric reports and the migration of accounts from the original layout
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use anchor_lang::Discriminator;
+use x_growth::curve::{BondingCurveParams, QuadraticCurve, FACTOR_PRECISION};
+use x_growth::state::{
+    Agent, LegacyAgent, LegacyPerformanceMetrics, LegacyPlatform, MetricReport, PerformanceMetrics,
+    Platform, ScoreTransforms, ScoreWeights,
+};
+
+fn v2(values: [u64; 9]) -> MetricReport {
+    MetricReport { version: MetricReport::V2, values: values.to_vec() }
+}
+
+// Rewrites the fixture agent as the original program stored it: the original layout, with no
+// reserve or stake vault
+fn make_legacy(fixture: &mut Fixture) -> LegacyAgent {
+    let agent = fixture.agent;
+    let current = fixture.agent_state();
+    let legacy = LegacyAgent {
+        agent_id: current.agent_id,
+        authority: current.authority,
+        token_mint: current.token_mint,
+        name: current.name,
+        symbol: current.symbol,
+        uri: current.uri,
+        bonding_curve: QuadraticCurve {
+            base_price: 10_000,
+            curve_factor: 3,
+            max_supply: 300_000_000_000_000,
+        },
+        total_supply: 1_000_000,
+        circulating_supply: 0,
+        reserve_balance: 0,
+        performance: LegacyPerformanceMetrics {
+            total_likes: 100,
+            total_views: 1_000,
+            total_comments: 50,
+            total_followers: 20,
+            last_updated: 1_000,
+            daily_likes: 10,
+            daily_views: 100,
+            daily_comments: 5,
+            daily_new_followers: 2,
+        },
+        total_rewards_earned: 7 * USDT,
+        last_reward_distribution: 1_000,
+        bump: current.bump,
+    };
+    
+    let mut data = Agent::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    data.resize(LegacyAgent::LEN, 0);
+    fixture.bank.set_account(agent, data, x_growth::ID);
+    fixture.bank.accounts.remove(&fixture.reserve);
+    fixture.bank.accounts.remove(&fixture.stake_vault());
+    legacy
+}
+
+// Rewrites the fixture platform as the original program stored it, with no treasury or reward pool
+fn make_legacy_platform(fixture: &mut Fixture) -> LegacyPlatform {
+    let legacy = LegacyPlatform {
+        authority: fixture.authority,
+        daily_reward_pool: 150 * USDT,
+        total_agents: 1,
+        usdt_mint: fixture.usdt_mint,
+        oracle: fixture.oracle,
+        bump: fixture.platform_state().bump,
+    };
+    
+    let mut data = Platform::DISCRIMINATOR.to_vec();
+    legacy.serialize(&mut data).unwrap();
+    assert_eq!(data.len(), LegacyPlatform::LEN);
+    let platform = fixture.platform;
+    fixture.bank.set_account(platform, data, x_growth::ID);
+    fixture.bank.accounts.remove(&fixture.treasury);
+    fixture.bank.accounts.remove(&fixture.reward_pool);
+    legacy
+}
+
+#[test]
+fn v2_reports_carry_the_extended_metrics() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    
+    // likes, views, comments, new_followers, reposts, quotes, bookmarks, impressions, followers_lost
+    fixture.report_metrics(agent, v2([100, 1_000, 50, 20, 30, 10, 10, 4_000, 5])).unwrap();
+    fixture.report_metrics(agent, v2([0, 0, 0, 0, 0, 0, 0, 1_000, 0])).unwrap();
+    
+    let performance = fixture.agent_state().performance;
+    assert_eq!(performance.total_reposts, 30);
+    assert_eq!(performance.daily_quotes, 10);
+    assert_eq!(performance.daily_bookmarks, 10);
+    assert_eq!(performance.total_impressions, 5_000);
+    assert_eq!(performance.total_followers_lost, 5);
+    assert_eq!(performance.total_followers, 15);
+    assert_eq!(performance.daily_new_followers, 20);
+    
+    // (100 + 50 + 30 + 10 + 10) engagements over 5_000 impressions
+    assert_eq!(performance.daily_engagement_rate_bps(), 400);
+    
+    // The extended metrics are informational; only the V1 metrics are scored
+    let score = |performance: &PerformanceMetrics| {
+        performance.calculate_score(&ScoreWeights::DEFAULT, &ScoreTransforms::DEFAULT).unwrap()
+    };
+    let scored = score(&performance);
+    fixture.report_metrics(agent, v2([0, 0, 0, 0, 500, 500, 500, 100_000, 500])).unwrap();
+    assert_eq!(score(&fixture.agent_state().performance), scored);
+    assert_eq!(scored, 100 + 100 + 100 + 100);
+    
+    // The extended daily metrics start over with the others
+    fixture.run_epoch(&[agent]);
+    let performance = fixture.agent_state().performance;
+    assert_eq!(performance.daily_impressions, 0);
+    assert_eq!(performance.daily_engagement_rate_bps(), 0);
+    assert_eq!(performance.total_impressions, 105_000);
+}
+
+#[test]
+fn v1_reports_still_apply_and_followers_never_go_negative() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    
+    fixture.update_performance(agent, 100, 1_000, 50, 20).unwrap();
+    let performance = fixture.agent_state().performance;
+    assert_eq!(performance.total_likes, 100);
+    assert_eq!(performance.total_followers, 20);
+    assert_eq!(performance.total_reposts, 0);
+    assert_eq!(performance.daily_engagement_rate_bps(), 0);
+    
+    fixture.report_metrics(agent, v2([0, 0, 0, 0, 0, 0, 0, 0, 50])).unwrap();
+    let performance = fixture.agent_state().performance;
+    assert_eq!(performance.total_followers, 0);
+    assert_eq!(performance.total_followers_lost, 50);
+}
+
+#[test]
+fn malformed_reports_are_rejected() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    
+    for report in [
+        MetricReport { version: 0, values: vec![1, 2, 3, 4] },
+        MetricReport { version: 3, values: vec![0; 9] },
+        MetricReport { version: MetricReport::V1, values: vec![0; 9] },
+        MetricReport { version: MetricReport::V2, values: vec![1, 2, 3, 4] },
+    ] {
+        assert_eq!(
+            fixture.report_metrics(agent, report),
+            Err(custom_error(XGrowthError::InvalidMetricReport))
+        );
+    }
+    
+    fixture.update_performance(agent, u64::MAX, 0, 0, 0).unwrap();
+    assert_eq!(
+        fixture.update_performance(agent, 1, 0, 0, 0),
+        Err(custom_error(XGrowthError::MathOverflow))
+    );
+}
+
+#[test]
+fn legacy_agents_are_reallocated_to_the_new_layout() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let token_mint = fixture.token_mint;
+    let trader = fixture.new_trader(1_000 * USDT);
+    
+    // An agent of any other size is refused, even where its bytes still happen to load
+    let current = fixture.bank.accounts[&agent].data.clone();
+    fixture.bank.set_account(agent, current[..LegacyAgent::LEN].to_vec(), x_growth::ID);
+    assert_eq!(
+        fixture.update_performance(agent, 1, 0, 0, 0),
+        Err(custom_error(XGrowthError::AgentNotMigrated))
+    );
+    assert_eq!(
+        fixture.buy(&trader, 100 * USDT, None),
+        Err(custom_error(XGrowthError::AgentNotMigrated))
+    );
+    
+    // The original layout does not load at all
+    let legacy = make_legacy(&mut fixture);
+    assert_eq!(
+        fixture.update_performance(agent, 1, 0, 0, 0),
+        Err(ProgramError::Custom(ErrorCode::AccountDidNotDeserialize.into()))
+    );
+    
+    fixture.migrate_agent(agent, token_mint).unwrap();
+    let stored = &fixture.bank.accounts[&agent];
+    assert_eq!(stored.data.len(), Agent::LEN);
+    assert!(stored.lamports >= Rent::default().minimum_balance(Agent::LEN));
+    
+    // The original fields carry over; the curve keeps its original pricing
+    let after = fixture.agent_state();
+    assert_eq!(after.agent_id, legacy.agent_id);
+    assert_eq!(after.authority, legacy.authority);
+    assert_eq!(after.token_mint, token_mint);
+    assert_eq!(after.total_supply, legacy.total_supply);
+    assert_eq!(after.performance.total_likes, 100);
+    assert_eq!(after.performance.last_updated, 1_000);
+    assert_eq!(after.performance.daily_new_followers, 2);
+    assert_eq!(after.performance.total_impressions, 0);
+    assert_eq!(after.total_rewards_earned, 7 * USDT);
+    assert_eq!(after.last_reward_distribution, 1_000);
+    assert_eq!(after.bump, legacy.bump);
+    match after.bonding_curve {
+        BondingCurveParams::Quadratic(curve) => {
+            assert_eq!(curve.base_price, 10_000);
+            assert_eq!(curve.curve_factor, FACTOR_PRECISION);
+            assert_eq!(curve.max_supply, 300_000_000_000_000);
+        }
+        _ => panic!("legacy agents migrate to a quadratic curve"),
+    }
+    assert!(after.fee_override.is_none());
+    assert_eq!(after.total_shares, 0);
+    
+    // The reserve and stake vault the original program never created now exist
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), 0);
+    assert_eq!(fixture.bank.token_balance(&fixture.stake_vault()), 0);
+    
+    // The migrated agent trades and reports as usual
+    fixture.report_metrics(agent, v2([1, 0, 0, 0, 0, 0, 0, 10, 0])).unwrap();
+    fixture.buy(&trader, 100 * USDT, None).unwrap();
+    fixture.assert_vaults_balanced();
+    
+    assert_eq!(
+        fixture.migrate_agent(agent, token_mint),
+        Err(custom_error(XGrowthError::AgentAlreadyMigrated))
+    );
+    let platform = fixture.platform;
+    assert_eq!(
+        fixture.migrate_agent(platform, token_mint),
+        Err(ProgramError::Custom(ErrorCode::AccountDiscriminatorMismatch.into()))
+    );
+    
+    // Only the original size is migrated; an agent of an unknown layout is left alone
+    let mut data = fixture.bank.accounts[&agent].data.clone();
+    data.truncate(LegacyAgent::LEN - 8);
+    fixture.bank.set_account(agent, data, x_growth::ID);
+    assert_eq!(
+        fixture.migrate_agent(agent, token_mint),
+        Err(ProgramError::Custom(ErrorCode::AccountDidNotDeserialize.into()))
+    );
+}
+
+#[test]
+fn legacy_agents_keep_their_mint() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let other = fixture.add_agent("other", fixture.creator);
+    let other_mint = fixture.agent_state_of(&other).token_mint;
+    make_legacy(&mut fixture);
+    
+    assert_eq!(
+        fixture.migrate_agent(agent, other_mint),
+        Err(custom_error(XGrowthError::InvalidMint))
+    );
+}
+
+#[test]
+fn the_original_platform_is_migrated_before_its_agents() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let token_mint = fixture.token_mint;
+    let legacy = make_legacy_platform(&mut fixture);
+    make_legacy(&mut fixture);
+    
+    // Agents need the current platform
+    assert_eq!(
+        fixture.migrate_agent(agent, token_mint),
+        Err(ProgramError::Custom(ErrorCode::AccountDidNotDeserialize.into()))
+    );
+    
+    let stranger = Pubkey::new_unique();
+    fixture.bank.fund(stranger, 100_000_000_000);
+    assert_eq!(
+        fixture.migrate_platform(stranger),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    let authority = fixture.authority;
+    fixture.migrate_platform(authority).unwrap();
+    assert_eq!(fixture.bank.accounts[&fixture.platform].data.len(), Platform::LEN);
+    
+    // The original settings carry over, the oracle becomes a 1-of-1 set
+    let platform = fixture.platform_state();
+    assert_eq!(platform.authority, legacy.authority);
+    assert_eq!(platform.daily_reward_pool, 150 * USDT);
+    assert_eq!(platform.total_agents, 1);
+    assert_eq!(platform.usdt_mint, legacy.usdt_mint);
+    assert_eq!(platform.bump, legacy.bump);
+    assert_eq!(platform.oracle_set.count, 1);
+    assert_eq!(platform.oracle_set.threshold, 1);
+    assert_eq!(platform.oracle_set.oracles[0], legacy.oracle);
+    assert_eq!(platform.dispute_window, Platform::DEFAULT_DISPUTE_WINDOW);
+    assert_eq!(platform.reward_vesting_period, Platform::EPOCH_DURATION);
+    assert_eq!(fixture.bank.token_balance(&fixture.treasury), 0);
+    assert_eq!(fixture.bank.token_balance(&fixture.reward_pool), 0);
+    
+    assert_eq!(
+        fixture.migrate_platform(authority),
+        Err(custom_error(XGrowthError::PlatformAlreadyMigrated))
+    );
+    
+    // Then its agents, which are rewarded as usual
+    fixture.migrate_agent(agent, token_mint).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.update_performance(agent, 100, 1_000, 50, 20).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert_eq!(fixture.agent_state().total_rewards_earned, 7 * USDT + 150 * USDT);
+}
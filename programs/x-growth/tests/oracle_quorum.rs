@@ -0,0 +1,186 @@
+// M-of-N oracle quorum: per-agent, per-epoch observations accepted at their median
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::events::{ObservationAccepted, ObservationOutlier};
+use x_growth::state::{MetricReport, OracleSet};
+
+fn likes(likes: u64, views: u64) -> MetricReport {
+    MetricReport { version: MetricReport::V1, values: vec![likes, views, 0, 0] }
+}
+
+// Three funded oracles with a 2-of-3 threshold, and epoch 1 begun
+fn quorum() -> (Fixture, [Pubkey; 3]) {
+    let mut fixture = Fixture::new();
+    let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    for oracle in oracles {
+        fixture.bank.fund(oracle, 1_000_000_000);
+    }
+    fixture.set_oracle_set(&oracles, 2, 1_000).unwrap();
+    fixture.begin_epoch().unwrap();
+    (fixture, oracles)
+}
+
+#[test]
+fn the_median_is_accepted_once_the_quorum_is_reached() {
+    let (mut fixture, [a, b, c]) = quorum();
+    let agent = fixture.agent;
+    
+    fixture.submit_observation(a, agent, 1, likes(100, 1_000)).unwrap();
+    let observation = fixture.observation_state(&agent, 1);
+    assert_eq!(observation.submissions, 1);
+    assert!(!observation.accepted);
+    assert_eq!(fixture.agent_state().performance.daily_likes, 0);
+    
+    // The second submission reaches 2 of 3: the median of two is their mean
+    fixture.submit_observation(b, agent, 1, likes(200, 1_000)).unwrap();
+    let observation = fixture.observation_state(&agent, 1);
+    assert!(observation.accepted);
+    assert_eq!(observation.accepted_values[..2], [150, 1_000]);
+    let performance = fixture.agent_state().performance;
+    assert_eq!(performance.daily_likes, 150);
+    assert_eq!(performance.total_views, 1_000);
+    let accepted = fixture.bank.events::<ObservationAccepted>();
+    assert_eq!(accepted.len(), 1);
+    assert_eq!(accepted[0].values[..2], [150, 1_000]);
+    
+    // Nothing is added twice for the same agent and epoch
+    assert_eq!(
+        fixture.submit_observation(c, agent, 1, likes(150, 1_000)),
+        Err(custom_error(XGrowthError::ObservationAlreadyAccepted))
+    );
+}
+
+#[test]
+fn a_single_faked_report_cannot_move_the_median_and_is_flagged() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let oracles = [Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique()];
+    for oracle in oracles {
+        fixture.bank.fund(oracle, 1_000_000_000);
+    }
+    fixture.set_oracle_set(&oracles, 3, 1_000).unwrap();
+    fixture.begin_epoch().unwrap();
+    
+    // A compromised oracle reports a million likes
+    fixture.submit_observation(oracles[0], agent, 1, likes(1_000_000, 1_000)).unwrap();
+    fixture.submit_observation(oracles[1], agent, 1, likes(100, 1_050)).unwrap();
+    fixture.submit_observation(oracles[2], agent, 1, likes(104, 1_000)).unwrap();
+    
+    assert_eq!(fixture.agent_state().performance.daily_likes, 104);
+    assert_eq!(fixture.agent_state().performance.daily_views, 1_000);
+    
+    // Only the faked likes are more than 10% from the median
+    let outliers = fixture.bank.events::<ObservationOutlier>();
+    assert_eq!(outliers.len(), 1);
+    assert_eq!(outliers[0].oracle, oracles[0]);
+    assert_eq!(outliers[0].metric, 0);
+    assert_eq!(outliers[0].value, 1_000_000);
+    assert_eq!(outliers[0].median, 104);
+}
+
+#[test]
+fn observations_are_checked_against_the_set_and_epoch() {
+    let (mut fixture, [a, b, _]) = quorum();
+    let agent = fixture.agent;
+    
+    let outsider = Pubkey::new_unique();
+    fixture.bank.fund(outsider, 1_000_000_000);
+    assert_eq!(
+        fixture.submit_observation(outsider, agent, 1, likes(100, 0)),
+        Err(custom_error(XGrowthError::UnauthorizedOracle))
+    );
+    
+    // The single-oracle path is closed once more than one oracle must agree
+    let oracle = fixture.oracle;
+    assert_eq!(
+        fixture.update_performance(agent, 100, 0, 0, 0),
+        Err(custom_error(XGrowthError::UnauthorizedOracle))
+    );
+    fixture.set_oracle_set(&[oracle, a, b], 2, 1_000).unwrap();
+    assert_eq!(
+        fixture.update_performance(agent, 100, 0, 0, 0),
+        Err(custom_error(XGrowthError::QuorumRequired))
+    );
+    
+    fixture.submit_observation(a, agent, 1, likes(100, 0)).unwrap();
+    assert_eq!(
+        fixture.submit_observation(a, agent, 1, likes(100, 0)),
+        Err(custom_error(XGrowthError::DuplicateObservation))
+    );
+    let extended = MetricReport { version: MetricReport::V2, values: vec![100, 0, 0, 0, 0, 0, 0, 0, 0] };
+    assert_eq!(
+        fixture.submit_observation(b, agent, 1, extended),
+        Err(custom_error(XGrowthError::InvalidMetricReport))
+    );
+    
+    // Once the agent is scored, the epoch takes no more observations
+    set_unix_timestamp(unix_timestamp() + x_growth::state::Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    assert_eq!(
+        fixture.submit_observation(b, agent, 1, likes(100, 0)),
+        Err(custom_error(XGrowthError::ScoreAlreadyRecorded))
+    );
+}
+
+#[test]
+fn changing_the_set_discards_pending_submissions() {
+    let (mut fixture, [a, b, c]) = quorum();
+    let agent = fixture.agent;
+    
+    fixture.submit_observation(a, agent, 1, likes(1_000, 0)).unwrap();
+    fixture.set_oracle_set(&[b, c], 2, 1_000).unwrap();
+    
+    // a's submission was made under the old set and no longer counts
+    fixture.submit_observation(b, agent, 1, likes(100, 0)).unwrap();
+    let observation = fixture.observation_state(&agent, 1);
+    assert_eq!(observation.submissions, 1);
+    assert!(!observation.accepted);
+    
+    fixture.submit_observation(c, agent, 1, likes(110, 0)).unwrap();
+    assert_eq!(fixture.agent_state().performance.daily_likes, 105);
+}
+
+#[test]
+fn set_oracle_set_is_validated_and_authority_only() {
+    let mut fixture = Fixture::new();
+    let oracle = fixture.oracle;
+    let set = fixture.platform_state().oracle_set;
+    assert_eq!(set.members(), [oracle]);
+    assert_eq!(set.threshold, 1);
+    
+    let other = Pubkey::new_unique();
+    let too_many: Vec<Pubkey> = (0..=OracleSet::MAX_ORACLES).map(|_| Pubkey::new_unique()).collect();
+    for (oracles, threshold) in [
+        (vec![], 1),
+        (vec![oracle, other], 0),
+        (vec![oracle, other], 3),
+        (vec![oracle, oracle], 1),
+        (vec![oracle, Pubkey::default()], 1),
+        (too_many, 1),
+    ] {
+        assert_eq!(
+            fixture.set_oracle_set(&oracles, threshold, 1_000),
+            Err(custom_error(XGrowthError::InvalidOracleSet))
+        );
+    }
+    
+    let platform = fixture.platform;
+    let outsider = fixture.new_trader(0);
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetOracleSet { platform, authority: outsider.key },
+            x_growth::instruction::SetOracleSet { oracles: vec![other], threshold: 1, outlier_tolerance_bps: 0 },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    fixture.set_oracle_set(&[oracle, other], 2, 500).unwrap();
+    let set = fixture.platform_state().oracle_set;
+    assert_eq!(set.members(), [oracle, other]);
+    assert_eq!(set.threshold, 2);
+    assert_eq!(set.outlier_tolerance_bps, 500);
+    assert_eq!(set.version, 1);
+}
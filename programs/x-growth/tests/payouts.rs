@@ -0,0 +1,320 @@
+// End-to-end runs of every instruction that pays USDT out of a program vault
+mod common;
+
+use anchor_lang::error::ErrorCode;
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::FeeConfig;
+
+// 1% buys, 2% sells, split 50/30/20 between protocol, creator and referrer
+const FEES: FeeConfig = FeeConfig {
+    buy_fee_bps: 100,
+    sell_fee_bps: 200,
+    protocol_share_bps: 5_000,
+    creator_share_bps: 3_000,
+    referrer_share_bps: 2_000,
+    max_agent_fee_bps: 500,
+};
+
+fn fixture_with_fees() -> Fixture {
+    let mut fixture = Fixture::new();
+    fixture.update_fee_config(FEES).unwrap();
+    fixture
+}
+
+#[test]
+fn buy_pays_reserve_treasury_and_referrer() {
+    let mut fixture = fixture_with_fees();
+    let buyer = fixture.new_trader(1_000 * USDT);
+    let referrer = Pubkey::new_unique();
+    let referrer_usdt = fixture.new_usdt_account(referrer);
+
+    let quote = fixture
+        .agent_state()
+        .quote_buy(&FEES, 100 * USDT, true)
+        .unwrap();
+    fixture.buy(&buyer, 100 * USDT, Some(referrer_usdt)).unwrap();
+
+    assert_eq!(fixture.bank.token_balance(&buyer.usdt_account), 900 * USDT);
+    assert_eq!(fixture.bank.token_balance(&buyer.token_account), quote.tokens_out);
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), 100 * USDT - quote.fee);
+    assert_eq!(fixture.bank.token_balance(&referrer_usdt), quote.referrer_fee);
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury),
+        quote.protocol_fee + quote.creator_fee
+    );
+    assert!(quote.referrer_fee > 0 && quote.creator_fee > 0);
+
+    let agent = fixture.agent_state();
+    assert_eq!(agent.circulating_supply, quote.tokens_out);
+    assert_eq!(agent.creator_fee_balance, quote.creator_fee);
+    assert_eq!(agent.referrer_fees_paid, quote.referrer_fee);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn buy_too_small_to_mint_fails() {
+    let mut fixture = fixture_with_fees();
+    let buyer = fixture.new_trader(1_000 * USDT);
+
+    // The fee rounds up to the whole unit, leaving nothing to buy tokens with
+    let quote = fixture.agent_state().quote_buy(&FEES, 1, false).unwrap();
+    assert_eq!((quote.fee, quote.tokens_out), (1, 0));
+    assert_eq!(
+        fixture.buy(&buyer, 1, None),
+        Err(custom_error(XGrowthError::TradeTooSmall))
+    );
+    assert_eq!(fixture.bank.token_balance(&buyer.usdt_account), 1_000 * USDT);
+    assert_eq!(fixture.bank.token_balance(&fixture.treasury), 0);
+
+    fixture.buy(&buyer, 2, None).unwrap();
+    assert!(fixture.bank.token_balance(&buyer.token_account) > 0);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn sell_pays_seller_treasury_and_referrer() {
+    let mut fixture = fixture_with_fees();
+    let trader = fixture.new_trader(1_000 * USDT);
+    let referrer = Pubkey::new_unique();
+    let referrer_usdt = fixture.new_usdt_account(referrer);
+    fixture.buy(&trader, 500 * USDT, None).unwrap();
+
+    let tokens = fixture.bank.token_balance(&trader.token_account);
+    let usdt_before = fixture.bank.token_balance(&trader.usdt_account);
+    let treasury_before = fixture.bank.token_balance(&fixture.treasury);
+    let quote = fixture
+        .agent_state()
+        .quote_sell(&FEES, tokens / 2, true)
+        .unwrap();
+    fixture.sell(&trader, tokens / 2, Some(referrer_usdt)).unwrap();
+
+    assert_eq!(fixture.bank.token_balance(&trader.token_account), tokens - tokens / 2);
+    assert_eq!(
+        fixture.bank.token_balance(&trader.usdt_account),
+        usdt_before + quote.usdt_out
+    );
+    assert_eq!(fixture.bank.token_balance(&referrer_usdt), quote.referrer_fee);
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury),
+        treasury_before + quote.protocol_fee + quote.creator_fee
+    );
+    fixture.assert_vaults_balanced();
+
+    // Selling the rest empties the reserve down to the curve integral of zero supply
+    fixture.sell(&trader, tokens - tokens / 2, None).unwrap();
+    assert_eq!(fixture.agent_state().circulating_supply, 0);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn sell_for_exact_usdt_pays_requested_amount() {
+    let mut fixture = fixture_with_fees();
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, 500 * USDT, None).unwrap();
+
+    let usdt_before = fixture.bank.token_balance(&trader.usdt_account);
+    let accounts = fixture.sell_accounts(&trader, None);
+    fixture
+        .bank
+        .process(
+            accounts,
+            x_growth::instruction::SellForExactUsdt {
+                usdt_amount: 100 * USDT,
+                max_tokens_in: u64::MAX,
+            },
+        )
+        .unwrap();
+
+    assert_eq!(
+        fixture.bank.token_balance(&trader.usdt_account),
+        usdt_before + 100 * USDT
+    );
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn withdraw_protocol_fees_pays_platform_authority() {
+    let mut fixture = fixture_with_fees();
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, 1_000 * USDT, None).unwrap();
+
+    let balance = fixture.platform_state().protocol_fee_balance;
+    assert!(balance > 0);
+    let authority = fixture.authority;
+    let destination = fixture.new_usdt_account(authority);
+    let withdraw = |fixture: &mut Fixture, authority: Pubkey, amount: u64| {
+        fixture.bank.process(
+            x_growth::accounts::WithdrawProtocolFees {
+                platform: fixture.platform,
+                treasury: fixture.treasury,
+                destination,
+                authority,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::WithdrawProtocolFees { amount },
+        )
+    };
+
+    assert_eq!(
+        withdraw(&mut fixture, trader.key, balance),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    assert_eq!(
+        withdraw(&mut fixture, authority, balance + 1),
+        Err(custom_error(XGrowthError::InsufficientFeeBalance))
+    );
+
+    withdraw(&mut fixture, authority, balance).unwrap();
+    assert_eq!(fixture.bank.token_balance(&destination), balance);
+
+    let platform = fixture.platform_state();
+    assert_eq!(platform.protocol_fee_balance, 0);
+    assert_eq!(platform.total_protocol_fees_withdrawn, balance);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn claim_creator_fees_pays_agent_authority() {
+    let mut fixture = fixture_with_fees();
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, 1_000 * USDT, None).unwrap();
+
+    let balance = fixture.agent_state().creator_fee_balance;
+    assert!(balance > 0);
+    let (authority, creator) = (fixture.authority, fixture.creator);
+    let destination = fixture.new_usdt_account(creator);
+    let claim = |fixture: &mut Fixture, authority: Pubkey| {
+        fixture.bank.process(
+            x_growth::accounts::ClaimCreatorFees {
+                agent: fixture.agent,
+                platform: fixture.platform,
+                treasury: fixture.treasury,
+                creator_usdt_account: destination,
+                authority,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::ClaimCreatorFees {},
+        )
+    };
+
+    assert_eq!(
+        claim(&mut fixture, authority),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+
+    claim(&mut fixture, creator).unwrap();
+    assert_eq!(fixture.bank.token_balance(&destination), balance);
+    assert_eq!(fixture.agent_state().creator_fees_claimed, balance);
+    fixture.assert_vaults_balanced();
+
+    assert_eq!(
+        claim(&mut fixture, creator),
+        Err(custom_error(XGrowthError::NoFeesToClaim))
+    );
+}
+
+#[test]
+fn claim_rewards_pays_from_reward_pool() {
+    let mut fixture = Fixture::new();
+    let user = Pubkey::new_unique();
+    let user_usdt = fixture.new_usdt_account(user);
+    let (user_rewards, bump) = Pubkey::find_program_address(
+        &[b"user_rewards", user.as_ref(), fixture.agent.as_ref()],
+        &x_growth::ID,
+    );
+    fixture.bank.set_anchor_account(
+        user_rewards,
+        &x_growth::state::UserRewards {
+            user,
+            agent: fixture.agent,
+            pending_rewards: 25 * USDT,
+            claimed_rewards: 0,
+            last_claim: 0,
+            bump,
+            shares: 0,
+            reward_debt: 0,
+            last_accrual: 0,
+            expired_rewards: 0,
+            streams: Default::default(),
+        },
+        x_growth::state::UserRewards::LEN,
+    );
+    let reward_pool = fixture.reward_pool;
+    fixture.bank.set_token_balance(&reward_pool, 200 * USDT);
+
+    fixture
+        .bank
+        .process(
+            x_growth::accounts::ClaimRewards {
+                agent: fixture.agent,
+                user_rewards,
+                user_usdt_account: user_usdt,
+                reward_pool,
+                platform: fixture.platform,
+                user,
+                token_program: spl_token::ID,
+            },
+            x_growth::instruction::ClaimRewards {},
+        )
+        .unwrap();
+
+    assert_eq!(fixture.bank.token_balance(&user_usdt), 25 * USDT);
+    assert_eq!(fixture.bank.token_balance(&reward_pool), 175 * USDT);
+    let rewards: x_growth::state::UserRewards = fixture.bank.anchor_account(&user_rewards);
+    assert_eq!(rewards.pending_rewards, 0);
+    assert_eq!(rewards.claimed_rewards, 25 * USDT);
+}
+
+#[test]
+fn sell_rejects_usdt_account_of_another_mint() {
+    let mut fixture = Fixture::new();
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, 100 * USDT, None).unwrap();
+
+    let other_mint = Pubkey::new_unique();
+    fixture.bank.create_mint(other_mint, Pubkey::new_unique(), 6);
+    let mut accounts = fixture.sell_accounts(&trader, None);
+    accounts.seller_usdt_account = fixture.bank.create_ata(other_mint, trader.key, 0);
+
+    assert_eq!(
+        fixture.bank.process(
+            accounts,
+            x_growth::instruction::SellToCurve { token_amount: 1_000, min_usdt_out: 0 },
+        ),
+        Err(custom_error(XGrowthError::InvalidMint))
+    );
+}
+
+#[test]
+fn sell_rejects_reserve_not_owned_by_agent() {
+    let mut fixture = Fixture::new();
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, 100 * USDT, None).unwrap();
+
+    // A reserve at the right address whose token authority is someone else
+    let balance = fixture.bank.token_balance(&fixture.reserve);
+    let (reserve, usdt_mint) = (fixture.reserve, fixture.usdt_mint);
+    fixture
+        .bank
+        .create_token_account(reserve, usdt_mint, Pubkey::new_unique(), balance);
+
+    assert_eq!(
+        fixture.sell(&trader, 1_000, None),
+        Err(ProgramError::Custom(ErrorCode::ConstraintTokenOwner.into()))
+    );
+}
+
+#[test]
+fn trade_rejects_trader_as_referrer() {
+    let mut fixture = fixture_with_fees();
+    let trader = fixture.new_trader(1_000 * USDT);
+    let own_account = trader.usdt_account;
+
+    assert_eq!(
+        fixture.buy(&trader, 100 * USDT, Some(own_account)),
+        Err(custom_error(XGrowthError::InvalidReferrer))
+    );
+}
@@ -0,0 +1,128 @@
+// quote_buy / quote_sell return data matches the trade they simulate
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::{FeeConfig, TradeQuote};
+
+// 1% buys, 2% sells, split 50/30/20 between protocol, creator and referrer
+const FEES: FeeConfig = FeeConfig {
+    buy_fee_bps: 100,
+    sell_fee_bps: 200,
+    protocol_share_bps: 5_000,
+    creator_share_bps: 3_000,
+    referrer_share_bps: 2_000,
+    max_agent_fee_bps: 500,
+};
+
+// A fixture with fees and some supply already sold, so the curve is off its base price
+fn traded_fixture() -> (Fixture, Trader, Pubkey) {
+    let mut fixture = Fixture::new();
+    fixture.update_fee_config(FEES).unwrap();
+    let trader = fixture.new_trader(10_000 * USDT);
+    fixture.buy(&trader, 2_000 * USDT, None).unwrap();
+    let referrer = Pubkey::new_unique();
+    let referrer_usdt = fixture.new_usdt_account(referrer);
+    (fixture, trader, referrer_usdt)
+}
+
+fn spot_price(fixture: &Fixture) -> u64 {
+    let agent = fixture.agent_state();
+    agent.bonding_curve.calculate_price(agent.circulating_supply).unwrap()
+}
+
+#[test]
+fn quote_buy_matches_the_buy() {
+    let (mut fixture, trader, referrer_usdt) = traded_fixture();
+    let reserve_before = fixture.bank.token_balance(&fixture.reserve);
+    let treasury_before = fixture.bank.token_balance(&fixture.treasury);
+    let tokens_before = fixture.bank.token_balance(&trader.token_account);
+    let price_before = spot_price(&fixture);
+    
+    let quote = fixture.quote_buy(500 * USDT, true).unwrap();
+    
+    // Quoting moves nothing
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), reserve_before);
+    assert_eq!(spot_price(&fixture), price_before);
+    
+    fixture.buy(&trader, 500 * USDT, Some(referrer_usdt)).unwrap();
+    assert_eq!(quote.usdt_in, 500 * USDT);
+    assert_eq!(fixture.bank.token_balance(&trader.token_account) - tokens_before, quote.tokens_out);
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve) - reserve_before, quote.usdt_in - quote.fee);
+    assert_eq!(fixture.bank.token_balance(&referrer_usdt), quote.referrer_fee);
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury) - treasury_before,
+        quote.protocol_fee + quote.creator_fee
+    );
+    assert_eq!(quote.fee, quote.protocol_fee + quote.creator_fee + quote.referrer_fee);
+    
+    // The spot price and impact the trade actually produced
+    let price_after = spot_price(&fixture);
+    assert_eq!(quote.spot_price_after, price_after);
+    assert!(price_after > price_before);
+    assert_eq!(quote.price_impact_bps, TradeQuote::price_impact_bps(price_before, price_after));
+    assert_eq!(quote.price_impact_bps, (price_after - price_before) * 10_000 / price_before);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn quote_sell_matches_the_sell() {
+    let (mut fixture, trader, referrer_usdt) = traded_fixture();
+    let tokens = fixture.bank.token_balance(&trader.token_account) / 3;
+    let usdt_before = fixture.bank.token_balance(&trader.usdt_account);
+    let reserve_before = fixture.bank.token_balance(&fixture.reserve);
+    let treasury_before = fixture.bank.token_balance(&fixture.treasury);
+    let price_before = spot_price(&fixture);
+    
+    let quote = fixture.quote_sell(tokens, true).unwrap();
+    assert_eq!(fixture.bank.token_balance(&fixture.reserve), reserve_before);
+    
+    fixture.sell(&trader, tokens, Some(referrer_usdt)).unwrap();
+    assert_eq!(quote.tokens_in, tokens);
+    assert_eq!(fixture.bank.token_balance(&trader.usdt_account) - usdt_before, quote.usdt_out);
+    assert_eq!(reserve_before - fixture.bank.token_balance(&fixture.reserve), quote.usdt_out + quote.fee);
+    assert_eq!(fixture.bank.token_balance(&referrer_usdt), quote.referrer_fee);
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury) - treasury_before,
+        quote.protocol_fee + quote.creator_fee
+    );
+    
+    let price_after = spot_price(&fixture);
+    assert_eq!(quote.spot_price_after, price_after);
+    assert!(price_after < price_before);
+    assert_eq!(quote.price_impact_bps, (price_before - price_after) * 10_000 / price_before);
+    fixture.assert_vaults_balanced();
+}
+
+#[test]
+fn quotes_without_a_referrer_keep_its_share_in_the_treasury() {
+    let (mut fixture, trader, _) = traded_fixture();
+    let treasury_before = fixture.bank.token_balance(&fixture.treasury);
+    
+    let quote = fixture.quote_buy(100 * USDT, false).unwrap();
+    assert_eq!(quote.referrer_fee, 0);
+    assert_eq!(quote.fee, 100 * USDT / 100);
+    
+    fixture.buy(&trader, 100 * USDT, None).unwrap();
+    assert_eq!(
+        fixture.bank.token_balance(&fixture.treasury) - treasury_before,
+        quote.protocol_fee + quote.creator_fee
+    );
+    assert_eq!(quote.protocol_fee + quote.creator_fee, quote.fee);
+}
+
+#[test]
+fn quotes_fail_like_the_trade_would() {
+    let (mut fixture, trader, _) = traded_fixture();
+    
+    let sold_out = fixture.agent_state().bonding_curve.calculate_reserve(300_000_000_000_000).unwrap();
+    assert_eq!(
+        fixture.quote_buy(sold_out * 2, false).err(),
+        Some(custom_error(XGrowthError::MaxSupplyReached))
+    );
+    
+    let supply = fixture.agent_state().circulating_supply;
+    assert!(fixture.quote_sell(supply + 1, false).is_err());
+    assert!(fixture.sell(&trader, supply + 1, None).is_err());
+}
@@ -0,0 +1,77 @@
+// Sequence numbers keep a retried or duplicated oracle report from being counted twice
+mod common;
+
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::MetricReport;
+
+fn likes(likes: u64) -> MetricReport {
+    MetricReport { version: MetricReport::V1, values: vec![likes, 0, 0, 0] }
+}
+
+#[test]
+fn a_report_is_applied_once() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    
+    fixture.report_metrics_at(agent, 1, likes(100)).unwrap();
+    
+    // A retry of the same transaction is rejected, not added again
+    assert_eq!(
+        fixture.report_metrics_at(agent, 1, likes(100)),
+        Err(custom_error(XGrowthError::StaleReport))
+    );
+    assert_eq!(fixture.agent_state().performance.total_likes, 100);
+    assert_eq!(fixture.agent_state().performance.daily_likes, 100);
+}
+
+#[test]
+fn sequence_numbers_only_move_forward() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    
+    // Gaps are fine, so an oracle may number its reports by timestamp
+    fixture.report_metrics_at(agent, 1_700_000_000, likes(10)).unwrap();
+    fixture.report_metrics_at(agent, 1_700_000_600, likes(20)).unwrap();
+    
+    // A report delayed behind a newer one is stale
+    assert_eq!(
+        fixture.report_metrics_at(agent, 1_700_000_300, likes(40)),
+        Err(custom_error(XGrowthError::StaleReport))
+    );
+    assert_eq!(
+        fixture.report_metrics_at(agent, 0, likes(40)),
+        Err(custom_error(XGrowthError::StaleReport))
+    );
+    assert_eq!(fixture.agent_state().performance.total_likes, 30);
+    assert_eq!(fixture.next_report_sequence(&agent), 1_700_000_601);
+}
+
+#[test]
+fn a_failed_report_does_not_use_up_its_sequence_number() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.report_metrics_at(agent, 1, likes(u64::MAX)).unwrap();
+    
+    assert_eq!(
+        fixture.report_metrics_at(agent, 2, likes(1)),
+        Err(custom_error(XGrowthError::MathOverflow))
+    );
+    assert_eq!(fixture.next_report_sequence(&agent), 2);
+    fixture.report_metrics_at(agent, 2, likes(0)).unwrap();
+}
+
+#[test]
+fn each_agent_has_its_own_sequence() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    let other = fixture.add_agent("other-agent", fixture.creator);
+    
+    fixture.report_metrics_at(agent, 5, likes(10)).unwrap();
+    fixture.report_metrics_at(other, 1, likes(20)).unwrap();
+    
+    assert_eq!(fixture.agent_state().performance.total_likes, 10);
+    assert_eq!(fixture.agent_state_of(&other).performance.total_likes, 20);
+    assert_eq!(fixture.next_report_sequence(&agent), 6);
+    assert_eq!(fixture.next_report_sequence(&other), 2);
+}
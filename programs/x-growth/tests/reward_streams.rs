@@ -0,0 +1,288 @@
+// Creator funded reward streams in their own mints, emitted per second to stakers
+mod common;
+
+use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::RewardStream;
+
+const RATE: u64 = 1_000;
+const DURATION: i64 = 1_000;
+
+// A trader holding and staking agent tokens bought with `usdt`
+fn staker(fixture: &mut Fixture, usdt: u64) -> (Trader, u64) {
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, usdt, None).unwrap();
+    let tokens = fixture.bank.token_balance(&trader.token_account);
+    (trader, tokens)
+}
+
+// Opens a stream and funds it for its whole duration
+fn funded_stream(fixture: &mut Fixture) -> (u8, Pubkey) {
+    let (index, mint) = fixture.create_reward_stream(RATE, unix_timestamp() + DURATION).unwrap();
+    fixture.fund_reward_stream(index, RATE * DURATION as u64).unwrap();
+    (index, mint)
+}
+
+#[test]
+fn streams_emit_to_stakers_in_proportion_to_stake() {
+    let mut fixture = Fixture::new();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let (bob, _) = staker(&mut fixture, 200 * USDT);
+    let (index, mint) = funded_stream(&mut fixture);
+    let start = unix_timestamp();
+
+    // Alice alone for 100s, then Bob joins with the same stake for 100s
+    fixture.stake(&alice, tokens).unwrap();
+    set_unix_timestamp(start + 100);
+    fixture.stake(&bob, tokens).unwrap();
+    set_unix_timestamp(start + 200);
+
+    let alice_rewards = fixture.bank.create_ata(mint, alice.key, 0);
+    let bob_rewards = fixture.bank.create_ata(mint, bob.key, 0);
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    fixture.claim_with_streams(&bob, &[(index, bob_rewards)]).unwrap();
+
+    assert_close(fixture.bank.token_balance(&alice_rewards), 150 * RATE);
+    assert_close(fixture.bank.token_balance(&bob_rewards), 50 * RATE);
+    let stream = fixture.reward_stream_state(index);
+    assert_eq!(stream.total_emitted, 200 * RATE);
+    assert_eq!(
+        stream.total_claimed,
+        fixture.bank.token_balance(&alice_rewards) + fixture.bank.token_balance(&bob_rewards)
+    );
+    assert_eq!(fixture.user_rewards_state(&alice.key).streams[index as usize].pending, 0);
+
+    // Nothing new has streamed since the claim
+    assert_eq!(
+        fixture.claim_with_streams(&alice, &[(index, alice_rewards)]),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+}
+
+#[test]
+fn one_claim_pays_usdt_and_every_stream_mint() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let (first, first_mint) = funded_stream(&mut fixture);
+    let (second, second_mint) = funded_stream(&mut fixture);
+    let start = unix_timestamp();
+
+    fixture.stake(&alice, tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert!(unix_timestamp() > start + DURATION);
+
+    let first_rewards = fixture.bank.create_ata(first_mint, alice.key, 0);
+    let second_rewards = fixture.bank.create_ata(second_mint, alice.key, 0);
+    fixture
+        .claim_with_streams(&alice, &[(second, second_rewards), (first, first_rewards)])
+        .unwrap();
+
+    // Both streams ran their whole duration with Alice as the only staker
+    assert_close(fixture.bank.token_balance(&first_rewards), RATE * DURATION as u64);
+    assert_close(fixture.bank.token_balance(&second_rewards), RATE * DURATION as u64);
+    assert_close(fixture.bank.token_balance(&alice.usdt_account), 900 * USDT + 200 * USDT);
+}
+
+#[test]
+fn streams_only_emit_while_staked_and_funded() {
+    let mut fixture = Fixture::new();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let start = unix_timestamp();
+    let (index, mint) = fixture.create_reward_stream(RATE, start + DURATION).unwrap();
+    fixture.fund_reward_stream(index, 150 * RATE).unwrap();
+
+    // No stakers for the first 100s, so nothing is emitted
+    set_unix_timestamp(start + 100);
+    fixture.stake(&alice, tokens).unwrap();
+    assert_eq!(fixture.reward_stream_state(index).total_emitted, 0);
+
+    // The funding runs out after 150s of staking
+    set_unix_timestamp(start + 400);
+    let alice_rewards = fixture.bank.create_ata(mint, alice.key, 0);
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    assert_close(fixture.bank.token_balance(&alice_rewards), 150 * RATE);
+
+    // Topping up resumes the stream from now, not from when the funds ran out
+    fixture.fund_reward_stream(index, 1_000 * RATE).unwrap();
+    set_unix_timestamp(start + 500);
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    assert_close(fixture.bank.token_balance(&alice_rewards), 250 * RATE);
+
+    // Nothing accrues past end_time
+    set_unix_timestamp(start + DURATION + 500);
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    assert_close(fixture.bank.token_balance(&alice_rewards), 750 * RATE);
+    assert_eq!(fixture.reward_stream_state(index).total_emitted, 750 * RATE);
+}
+
+#[test]
+fn ending_a_stream_refunds_what_was_not_emitted() {
+    let mut fixture = Fixture::new();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let (index, mint) = funded_stream(&mut fixture);
+    let start = unix_timestamp();
+    fixture.stake(&alice, tokens).unwrap();
+
+    set_unix_timestamp(start + 300);
+    let creator = fixture.creator;
+    let refund_account = fixture.bank.create_ata(mint, creator, 0);
+    fixture.end_reward_stream(index, refund_account).unwrap();
+    assert_eq!(fixture.bank.token_balance(&refund_account), 700 * RATE);
+
+    let stream = fixture.reward_stream_state(index);
+    assert!(stream.ended);
+    assert_eq!(stream.end_time, start + 300);
+    assert_eq!(stream.total_refunded, 700 * RATE);
+
+    // Emitted rewards stay claimable, and nothing more accrues
+    set_unix_timestamp(start + 600);
+    let alice_rewards = fixture.bank.create_ata(mint, alice.key, 0);
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    assert_close(fixture.bank.token_balance(&alice_rewards), 300 * RATE);
+
+    assert_eq!(
+        fixture.fund_reward_stream(index, RATE),
+        Err(custom_error(XGrowthError::RewardStreamEnded))
+    );
+    assert_eq!(
+        fixture.end_reward_stream(index, refund_account),
+        Err(custom_error(XGrowthError::RewardStreamEnded))
+    );
+}
+
+#[test]
+fn sweeping_an_ended_stream_returns_what_stakers_cannot_claim() {
+    let mut fixture = Fixture::new();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let (index, mint) = funded_stream(&mut fixture);
+    let (running, running_mint) = funded_stream(&mut fixture);
+    let start = unix_timestamp();
+    fixture.stake(&alice, tokens).unwrap();
+
+    let creator = fixture.creator;
+    let refund_account = fixture.bank.create_ata(mint, creator, 0);
+    let alice_rewards = fixture.bank.create_ata(mint, alice.key, 0);
+
+    // Each claim rounds the accumulator down
+    for elapsed in [7, 113, 229] {
+        set_unix_timestamp(start + elapsed);
+        fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    }
+    set_unix_timestamp(start + 300);
+    fixture.end_reward_stream(index, refund_account).unwrap();
+    assert_eq!(fixture.bank.token_balance(&refund_account), 700 * RATE);
+
+    let running_refund_account = fixture.bank.create_ata(running_mint, creator, 0);
+    assert_eq!(
+        fixture.sweep_reward_stream(running, running_refund_account),
+        Err(custom_error(XGrowthError::RewardStreamActive))
+    );
+
+    // While Alice is staked everything emitted and unclaimed stays hers to claim
+    assert_eq!(
+        fixture.sweep_reward_stream(index, refund_account),
+        Err(custom_error(XGrowthError::NothingToSweep))
+    );
+
+    // Tokens sent to the vault directly are owed to nobody
+    let vault = Fixture::stream_vault_pda(&Fixture::reward_stream_pda(&fixture.agent, index));
+    let balance = fixture.bank.token_balance(&vault);
+    fixture.bank.set_token_balance(&vault, balance + 5);
+    fixture.sweep_reward_stream(index, refund_account).unwrap();
+    assert_eq!(fixture.bank.token_balance(&refund_account), 700 * RATE + 5);
+
+    // Once she leaves and claims, the rounding dust is owed to nobody either
+    fixture.unstake(&alice, tokens).unwrap();
+    fixture.claim_with_streams(&alice, &[(index, alice_rewards)]).unwrap();
+    let stream = fixture.reward_stream_state(index);
+    assert_eq!(stream.total_emitted, 300 * RATE);
+    assert!(stream.total_settled < stream.total_emitted);
+    assert_eq!(stream.total_claimed, stream.total_settled);
+
+    fixture.sweep_reward_stream(index, refund_account).unwrap();
+    let refunded = fixture.bank.token_balance(&refund_account);
+    assert_eq!(fixture.bank.token_balance(&vault), 0);
+    assert_eq!(refunded + fixture.bank.token_balance(&alice_rewards), RATE * DURATION as u64 + 5);
+    assert_eq!(fixture.reward_stream_state(index).total_refunded, refunded);
+}
+
+#[test]
+fn stream_accounts_are_validated() {
+    let mut fixture = Fixture::new();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let (index, _) = funded_stream(&mut fixture);
+    fixture.stake(&alice, tokens / 2).unwrap();
+
+    // Stake changes must carry every stream of the agent
+    let accounts = x_growth::accounts::Stake {
+        agent: fixture.agent,
+        platform: fixture.platform,
+        stake_vault: fixture.stake_vault(),
+        user_token_account: alice.token_account,
+        user_stake: Fixture::user_stake_pda(&alice.key, &fixture.agent),
+        user_rewards: Fixture::user_rewards_pda(&alice.key, &fixture.agent),
+        user: alice.key,
+        token_program: spl_token::ID,
+        system_program: anchor_lang::system_program::ID,
+    };
+    assert_eq!(
+        fixture.bank.process(accounts, x_growth::instruction::Stake { amount: 1 }),
+        Err(custom_error(XGrowthError::InvalidRewardStream))
+    );
+
+    // Rewards are paid only in the stream's own mint
+    set_unix_timestamp(unix_timestamp() + 10);
+    assert_eq!(
+        fixture.claim_with_streams(&alice, &[(index, alice.usdt_account)]),
+        Err(custom_error(XGrowthError::InvalidMint))
+    );
+}
+
+#[test]
+fn only_the_creator_manages_a_bounded_number_of_streams() {
+    let mut fixture = Fixture::new();
+    let now = unix_timestamp();
+
+    assert_eq!(
+        fixture.create_reward_stream(0, now + DURATION),
+        Err(custom_error(XGrowthError::InvalidStreamSchedule))
+    );
+    assert_eq!(
+        fixture.create_reward_stream(RATE, now),
+        Err(custom_error(XGrowthError::InvalidStreamSchedule))
+    );
+
+    let outsider = Pubkey::new_unique();
+    fixture.bank.fund(outsider, 1_000_000_000);
+    let stream = Fixture::reward_stream_pda(&fixture.agent, 0);
+    let usdt_mint = fixture.usdt_mint;
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::CreateRewardStream {
+                agent: fixture.agent,
+                stream,
+                stream_vault: Fixture::stream_vault_pda(&stream),
+                reward_mint: usdt_mint,
+                authority: outsider,
+                token_program: spl_token::ID,
+                system_program: anchor_lang::system_program::ID,
+                rent: anchor_lang::solana_program::sysvar::rent::ID,
+            },
+            x_growth::instruction::CreateRewardStream { emission_rate: RATE, end_time: now + DURATION },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+
+    for _ in 0..RewardStream::MAX_PER_AGENT {
+        fixture.create_reward_stream(RATE, now + DURATION).unwrap();
+    }
+    assert_eq!(
+        fixture.create_reward_stream(RATE, now + DURATION),
+        Err(custom_error(XGrowthError::TooManyRewardStreams))
+    );
+}
@@ -0,0 +1,276 @@
+// Anti-gaming scoring: damped and capped metrics, and a per-agent limit on the epoch pool
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::{
+    integer_sqrt, Damping, MetricTransform, Platform, RewardEpoch, ScoreTransforms, BPS_DENOMINATOR,
+};
+
+fn limited(max_agent_share_bps: u16) -> ScoreTransforms {
+    ScoreTransforms { max_agent_share_bps, ..ScoreTransforms::DEFAULT }
+}
+
+// A finalized epoch over `scores`, built without the crank
+fn settled_epoch(reward_pool: u64, max_agent_share_bps: u16, scores: &[u64]) -> RewardEpoch {
+    let mut epoch = RewardEpoch::try_deserialize_unchecked(&mut &vec![0u8; RewardEpoch::LEN][..]).unwrap();
+    epoch.reward_pool = reward_pool;
+    epoch.score_transforms = limited(max_agent_share_bps);
+    for score in scores {
+        epoch.record_score(*score).unwrap();
+    }
+    epoch.settle_share_cap().unwrap();
+    epoch
+}
+
+// Straightforward water-filling over every score: cap whoever's pro rata share of what
+// is left exceeds the cap, until nobody does
+fn reference_shares(reward_pool: u64, max_agent_share_bps: u16, scores: &[u64]) -> Vec<u64> {
+    let pool = reward_pool as u128;
+    let cap = if max_agent_share_bps as u64 >= BPS_DENOMINATOR {
+        pool
+    } else {
+        (pool * max_agent_share_bps as u128).div_ceil(BPS_DENOMINATOR as u128)
+    };
+    let mut capped = vec![false; scores.len()];
+    loop {
+        let left = pool - cap * capped.iter().filter(|capped| **capped).count() as u128;
+        let left_score: u128 = (0..scores.len())
+            .filter(|i| !capped[*i])
+            .map(|i| scores[i] as u128)
+            .sum();
+        let over: Vec<usize> = (0..scores.len())
+            .filter(|i| !capped[*i] && left * scores[*i] as u128 > cap * left_score)
+            .collect();
+        if over.is_empty() {
+            return (0..scores.len())
+                .map(|i| match (capped[i], left_score) {
+                    (true, _) => cap as u64,
+                    (false, 0) => 0,
+                    (false, _) => (left * scores[i] as u128 / left_score) as u64,
+                })
+                .collect();
+        }
+        for i in over {
+            capped[i] = true;
+        }
+    }
+}
+
+// SplitMix64, so every run checks the same cases
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+    
+    // Mostly small scores with the odd zero, tie and viral outlier
+    fn score(&mut self, previous: Option<u64>) -> u64 {
+        match self.below(10) {
+            0 => 0,
+            1 => previous.unwrap_or(1),
+            2 => self.below(u32::MAX as u64) * 1_000,
+            _ => self.below(10_000),
+        }
+    }
+}
+
+#[test]
+fn capped_shares_stay_within_the_pool() {
+    let mut rng = Rng(0x5EED);
+    for _ in 0..5_000 {
+        let reward_pool = match rng.below(3) {
+            0 => rng.below(1_000),
+            _ => rng.below(1_000_000 * USDT),
+        };
+        let max_share_bps = ScoreTransforms::MIN_AGENT_SHARE_BPS
+            + rng.below(BPS_DENOMINATOR - ScoreTransforms::MIN_AGENT_SHARE_BPS as u64 + 1) as u16;
+        let agents = 1 + rng.below(60) as usize;
+        let mut scores: Vec<u64> = Vec::with_capacity(agents);
+        for _ in 0..agents {
+            let score = rng.score(scores.last().copied());
+            scores.push(score);
+        }
+        
+        let epoch = settled_epoch(reward_pool, max_share_bps, &scores);
+        let shares: Vec<u64> = scores.iter().map(|score| epoch.reward_share(*score).unwrap()).collect();
+        let credited: u64 = shares.iter().sum();
+        let case = format!("pool {} limit {} scores {:?}", reward_pool, max_share_bps, scores);
+        
+        // Credits never exceed the pool; nothing is credited when nobody scored
+        assert!(credited <= reward_pool, "{}", case);
+        if scores.iter().all(|score| *score == 0) {
+            assert_eq!(credited, 0, "{}", case);
+        }
+        
+        // Same split as plain water-filling over every score
+        assert_eq!(shares, reference_shares(reward_pool, max_share_bps, &scores), "{}", case);
+        
+        // Nobody exceeds the cap, the cap is the configured limit, and higher scores never earn less
+        let limit = (reward_pool as u128 * max_share_bps as u128).div_ceil(BPS_DENOMINATOR as u128);
+        assert!(epoch.share_cap as u128 <= limit, "{}", case);
+        assert!(shares.iter().all(|share| *share <= epoch.share_cap), "{}", case);
+        for (a, b) in scores.iter().zip(&shares) {
+            for (c, d) in scores.iter().zip(&shares) {
+                assert!(a <= c || b >= d, "{}", case);
+            }
+        }
+        
+        // The excess reaches the other agents: less than one unit per agent is left over,
+        // unless every agent with a score is capped
+        let leftover = reward_pool - credited;
+        let uncapped_scorers = scores
+            .iter()
+            .filter(|score| **score > 0 && epoch.reward_share(**score).unwrap() < epoch.share_cap)
+            .count() as u64;
+        let anyone_scored = scores.iter().any(|score| *score > 0);
+        if anyone_scored && (uncapped_scorers > 0 || epoch.capped_agents == 0) {
+            assert!(leftover < agents as u64, "{}", case);
+        }
+    }
+}
+
+#[test]
+fn damping_is_exact_and_monotonic() {
+    let sqrt = MetricTransform { damping: Damping::Sqrt, cap: 0 };
+    let log = MetricTransform { damping: Damping::Log, cap: 0 };
+    for value in [0, 1, 2, 3, 4, 15, 16, 17, 99, 100, 1 << 40, u64::MAX - 1, u64::MAX] {
+        let root = integer_sqrt(value) as u128;
+        assert!(root * root <= value as u128 && (root + 1) * (root + 1) > value as u128);
+    }
+    assert_eq!(sqrt.apply(1_000_000), 1_000);
+    
+    // log2(1 + x) in thousandths, exact at powers of two
+    assert_eq!(log.apply(0), 0);
+    assert_eq!(log.apply(1), 1_000);
+    assert_eq!(log.apply(2), 1_500);
+    assert_eq!(log.apply(1_023), 10_000);
+    assert_eq!(log.apply(u64::MAX), 64_000);
+    
+    let mut rng = Rng(7);
+    for _ in 0..10_000 {
+        let (a, b) = (rng.next() >> rng.below(64), rng.next() >> rng.below(64));
+        let (low, high) = (a.min(b), a.max(b));
+        for transform in [sqrt, log] {
+            assert!(transform.apply(low) <= transform.apply(high));
+        }
+    }
+    
+    // Caps apply to the raw count, before damping
+    let capped = MetricTransform { damping: Damping::Sqrt, cap: 10_000 };
+    assert_eq!(capped.apply(1_000_000), 100);
+    assert_eq!(capped.apply(2_500), 50);
+}
+
+#[test]
+fn one_agent_cannot_take_more_than_the_limit() {
+    let mut fixture = Fixture::new();
+    let viral = fixture.agent;
+    let second = fixture.add_agent("second-agent", Pubkey::new_unique());
+    let third = fixture.add_agent("third-agent", Pubkey::new_unique());
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.set_score_transforms(limited(5_000)).unwrap();
+    
+    // Scores 9_000, 300 and 100: pro rata the viral agent would take 93.75%
+    fixture.update_performance(viral, 9_000, 0, 0, 0).unwrap();
+    fixture.update_performance(second, 300, 0, 0, 0).unwrap();
+    fixture.update_performance(third, 100, 0, 0, 0).unwrap();
+    let epoch = fixture.run_epoch(&[viral, second, third]);
+    
+    let state = fixture.epoch_state(epoch);
+    assert_eq!(state.capped_agents, 1);
+    assert_eq!(state.share_cap, 100 * USDT);
+    
+    // Capped at half of the pool; the other half split 3:1
+    assert_eq!(fixture.agent_state_of(&viral).total_rewards_earned, 100 * USDT);
+    assert_eq!(fixture.agent_state_of(&second).total_rewards_earned, 75 * USDT);
+    assert_eq!(fixture.agent_state_of(&third).total_rewards_earned, 25 * USDT);
+    assert_eq!(state.total_credited, 200 * USDT);
+    assert_eq!(fixture.platform_state().total_rewards_allocated, 200 * USDT);
+}
+
+#[test]
+fn excess_nobody_can_take_returns_to_the_pool() {
+    let mut fixture = Fixture::new();
+    let first = fixture.agent;
+    let second = fixture.add_agent("second-agent", Pubkey::new_unique());
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.set_score_transforms(limited(2_500)).unwrap();
+    
+    fixture.update_performance(first, 100, 0, 0, 0).unwrap();
+    fixture.update_performance(second, 50, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[first, second]);
+    
+    // Both agents are held to a quarter of the pool; the remaining half is not spent
+    assert_eq!(fixture.agent_state_of(&first).total_rewards_earned, 50 * USDT);
+    assert_eq!(fixture.agent_state_of(&second).total_rewards_earned, 50 * USDT);
+    let platform = fixture.platform_state();
+    assert_eq!(platform.total_rewards_allocated, 100 * USDT);
+    assert_eq!(platform.unallocated_rewards, 900 * USDT);
+}
+
+#[test]
+fn transforms_damp_and_cap_metrics_from_the_next_epoch() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    
+    // Bought views count as their square root, likes stop counting past 500
+    let transforms = ScoreTransforms {
+        likes: MetricTransform { damping: Damping::Linear, cap: 500 },
+        views: MetricTransform { damping: Damping::Sqrt, cap: 0 },
+        ..ScoreTransforms::DEFAULT
+    };
+    
+    fixture.begin_epoch().unwrap();
+    fixture.set_score_transforms(transforms).unwrap();
+    fixture.update_performance(agent, 1_000, 1_000_000, 0, 0).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    assert_eq!(fixture.epoch_state(1).score_transforms, ScoreTransforms::DEFAULT);
+    assert_eq!(fixture.agent_state().epoch_score, 1_000 + 100_000);
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(agent, 1).unwrap();
+    
+    // 500 capped likes + sqrt(1_000_000) / 10 views
+    fixture.update_performance(agent, 1_000, 1_000_000, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert_eq!(fixture.epoch_state(2).score_transforms, transforms);
+    assert_eq!(fixture.agent_state().epoch_score, 500 + 100);
+}
+
+#[test]
+fn set_score_transforms_is_validated_and_authority_only() {
+    let mut fixture = Fixture::new();
+    assert_eq!(fixture.platform_state().score_transforms, ScoreTransforms::DEFAULT);
+    
+    for max_agent_share_bps in [0, ScoreTransforms::MIN_AGENT_SHARE_BPS - 1, BPS_DENOMINATOR as u16 + 1] {
+        assert_eq!(
+            fixture.set_score_transforms(limited(max_agent_share_bps)),
+            Err(custom_error(XGrowthError::InvalidScoreTransforms))
+        );
+    }
+    
+    let platform = fixture.platform;
+    let outsider = fixture.new_trader(0);
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetScoreTransforms { platform, authority: outsider.key },
+            x_growth::instruction::SetScoreTransforms { score_transforms: limited(5_000) },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    
+    fixture.set_score_transforms(limited(ScoreTransforms::MIN_AGENT_SHARE_BPS)).unwrap();
+    assert_eq!(fixture.platform_state().score_transforms.max_agent_share_bps, ScoreTransforms::MIN_AGENT_SHARE_BPS);
+}
@@ -0,0 +1,119 @@
+// Platform configurable score weights, snapshotted by each epoch when it begins
+mod common;
+
+use anchor_lang::prelude::*;
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::{PerformanceMetrics, Platform, ScoreTransforms, ScoreWeights};
+
+// Likes only, each worth 2.0
+const LIKES_ONLY: ScoreWeights = ScoreWeights {
+    likes: 2 * ScoreWeights::PRECISION,
+    views: 0,
+    comments: 0,
+    followers: 0,
+};
+
+fn metrics(likes: u64, views: u64, comments: u64, new_followers: u64) -> PerformanceMetrics {
+    let mut performance = Fixture::new().agent_state().performance;
+    performance.daily_likes = likes;
+    performance.daily_views = views;
+    performance.daily_comments = comments;
+    performance.daily_new_followers = new_followers;
+    performance
+}
+
+#[test]
+fn default_weights_match_the_original_formula() {
+    let fixture = Fixture::new();
+    assert_eq!(fixture.platform_state().score_weights, ScoreWeights::DEFAULT);
+    
+    // 100 + 1_000 / 10 + 50 * 2 + 20 * 5
+    let performance = metrics(100, 1_000, 50, 20);
+    assert_eq!(performance.calculate_score(&ScoreWeights::DEFAULT, &ScoreTransforms::DEFAULT).unwrap(), 400);
+    
+    // Fractional weights round the total down, not each term
+    let performance = metrics(0, 15, 0, 0);
+    let weights = ScoreWeights { views: 150, ..ScoreWeights::DEFAULT };
+    assert_eq!(performance.calculate_score(&weights, &ScoreTransforms::DEFAULT).unwrap(), 2);
+}
+
+#[test]
+fn weighted_scores_that_overflow_are_rejected() {
+    let performance = metrics(u64::MAX, 0, 0, u64::MAX);
+    let weights = ScoreWeights {
+        likes: ScoreWeights::MAX_WEIGHT,
+        views: 0,
+        comments: 0,
+        followers: ScoreWeights::MAX_WEIGHT,
+    };
+    assert_eq!(
+        performance.calculate_score(&weights, &ScoreTransforms::DEFAULT).unwrap_err(),
+        error!(XGrowthError::MathOverflow)
+    );
+}
+
+#[test]
+fn new_weights_apply_from_the_next_epoch() {
+    let mut fixture = Fixture::new();
+    let first = fixture.agent;
+    let second = fixture.add_agent("second-agent", Pubkey::new_unique());
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    
+    // Epoch 1 is under way when the weights change, so it keeps the defaults
+    fixture.begin_epoch().unwrap();
+    fixture.set_score_weights(LIKES_ONLY).unwrap();
+    fixture.update_performance(first, 100, 0, 0, 0).unwrap();
+    fixture.update_performance(second, 0, 0, 0, 20).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(first, 1).unwrap();
+    fixture.record_agent_score(second, 1).unwrap();
+    assert_eq!(fixture.epoch_state(1).score_weights, ScoreWeights::DEFAULT);
+    assert_eq!(fixture.agent_state_of(&first).epoch_score, 100);
+    assert_eq!(fixture.agent_state_of(&second).epoch_score, 100);
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(first, 1).unwrap();
+    fixture.distribute_rewards(second, 1).unwrap();
+    
+    // Epoch 2 scores with the new weights
+    fixture.begin_epoch().unwrap();
+    fixture.update_performance(first, 100, 0, 0, 0).unwrap();
+    fixture.update_performance(second, 0, 0, 0, 20).unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(first, 2).unwrap();
+    fixture.record_agent_score(second, 2).unwrap();
+    assert_eq!(fixture.epoch_state(2).score_weights, LIKES_ONLY);
+    assert_eq!(fixture.agent_state_of(&first).epoch_score, 200);
+    assert_eq!(fixture.agent_state_of(&second).epoch_score, 0);
+    assert_eq!(fixture.epoch_state(2).total_score, 200);
+}
+
+#[test]
+fn set_score_weights_is_validated_and_authority_only() {
+    let mut fixture = Fixture::new();
+    
+    let all_zero = ScoreWeights { likes: 0, views: 0, comments: 0, followers: 0 };
+    assert_eq!(
+        fixture.set_score_weights(all_zero),
+        Err(custom_error(XGrowthError::InvalidScoreWeights))
+    );
+    let too_heavy = ScoreWeights { comments: ScoreWeights::MAX_WEIGHT + 1, ..ScoreWeights::DEFAULT };
+    assert_eq!(
+        fixture.set_score_weights(too_heavy),
+        Err(custom_error(XGrowthError::InvalidScoreWeights))
+    );
+    
+    let platform = fixture.platform;
+    let outsider = fixture.new_trader(0);
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetScoreWeights { platform, authority: outsider.key },
+            x_growth::instruction::SetScoreWeights { score_weights: LIKES_ONLY },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+    assert_eq!(fixture.platform_state().score_weights, ScoreWeights::DEFAULT);
+    
+    fixture.set_score_weights(LIKES_ONLY).unwrap();
+    assert_eq!(fixture.platform_state().score_weights, LIKES_ONLY);
+}
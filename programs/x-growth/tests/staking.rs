@@ -0,0 +1,245 @@
+// Credited agent rewards accrue to stakers by staked amount; unstaking can be delayed by unbonding
+mod common;
+
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::Platform;
+
+const UNBONDING: i64 = 3 * 86_400;
+
+// A trader holding agent tokens bought with `usdt`
+fn holder(fixture: &mut Fixture, usdt: u64) -> (Trader, u64) {
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, usdt, None).unwrap();
+    let tokens = fixture.bank.token_balance(&trader.token_account);
+    (trader, tokens)
+}
+
+#[test]
+fn stakers_earn_in_proportion_to_stake_over_time() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+    let (bob, bob_tokens) = holder(&mut fixture, 200 * USDT);
+
+    // Epoch 1: Alice is the only staker
+    fixture.stake(&alice, alice_tokens).unwrap();
+    assert_eq!(fixture.bank.token_balance(&fixture.stake_vault()), alice_tokens);
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    // Epoch 2: Bob stakes as many tokens as Alice, they split evenly
+    fixture.stake(&bob, alice_tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    // Alice's unstake settles her rewards before her stake drops
+    fixture.unstake(&alice, alice_tokens).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).pending_rewards, 300 * USDT);
+    assert_eq!(fixture.user_rewards_state(&alice.key).shares, 0);
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), alice_tokens);
+
+    // Epoch 3: only Bob is staked
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    let alice_before = fixture.bank.token_balance(&alice.usdt_account);
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.bank.token_balance(&alice.usdt_account) - alice_before, 300 * USDT);
+
+    let bob_before = fixture.bank.token_balance(&bob.usdt_account);
+    fixture.claim_rewards(bob.key, bob.usdt_account).unwrap();
+    assert_close(fixture.bank.token_balance(&bob.usdt_account) - bob_before, 300 * USDT);
+    assert_eq!(fixture.bank.token_balance(&bob.token_account), bob_tokens - alice_tokens);
+}
+
+#[test]
+fn holders_who_do_not_stake_earn_nothing() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, _) = holder(&mut fixture, 500 * USDT);
+    let (bob, bob_tokens) = holder(&mut fixture, 100 * USDT);
+
+    fixture.stake(&bob, bob_tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    fixture.claim_rewards(bob.key, bob.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&bob.key).claimed_rewards, 200 * USDT);
+    assert!(!fixture.bank.exists(&Fixture::user_rewards_pda(&alice.key, &agent)));
+}
+
+#[test]
+fn rewards_credited_without_stakers_wait_for_the_first_staker() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert_eq!(fixture.agent_state().undistributed_rewards, 200 * USDT);
+
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+    fixture.stake(&alice, alice_tokens).unwrap();
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 400 * USDT);
+    assert!(fixture.agent_state().undistributed_rewards <= 1);
+}
+
+#[test]
+fn unbonding_stake_earns_nothing_and_waits_for_the_period() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    fixture.set_unbonding_period(UNBONDING).unwrap();
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+    let start = unix_timestamp();
+    fixture.stake(&alice, alice_tokens).unwrap();
+    assert_eq!(fixture.user_stake_state(&alice.key).start_time, start);
+
+    fixture.unstake(&alice, alice_tokens).unwrap();
+    let stake = fixture.user_stake_state(&alice.key);
+    assert_eq!(stake.amount, 0);
+    assert_eq!(stake.unbonding_amount, alice_tokens);
+    assert_eq!(stake.unbonding_end, start + UNBONDING);
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), 0);
+    assert_eq!(
+        fixture.withdraw_stake(&alice),
+        Err(custom_error(XGrowthError::UnbondingNotComplete))
+    );
+
+    // A distribution during unbonding skips the unstaked tokens
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    assert_eq!(fixture.agent_state().undistributed_rewards, 200 * USDT);
+    assert_eq!(fixture.user_rewards_state(&alice.key).pending_rewards, 0);
+
+    set_unix_timestamp(start + UNBONDING);
+    fixture.withdraw_stake(&alice).unwrap();
+    assert_eq!(fixture.bank.token_balance(&alice.token_account), alice_tokens);
+    assert_eq!(fixture.bank.token_balance(&fixture.stake_vault()), 0);
+    assert_eq!(
+        fixture.withdraw_stake(&alice),
+        Err(custom_error(XGrowthError::NothingToWithdraw))
+    );
+}
+
+#[test]
+fn stake_sits_out_the_epoch_it_was_staked_in() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+    let (mallory, _) = holder(&mut fixture, 200 * USDT);
+    fixture.stake(&alice, alice_tokens).unwrap();
+
+    // Mallory stakes once epoch 1 is scored, just ahead of its credit
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.begin_epoch().unwrap();
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    fixture.stake(&mallory, alice_tokens).unwrap();
+    let stake = fixture.user_stake_state(&mallory.key);
+    assert_eq!(stake.amount, alice_tokens);
+    assert_eq!(stake.warming_amount, alice_tokens);
+    assert_eq!(stake.warming_epoch, 1);
+    assert_eq!(fixture.user_rewards_state(&mallory.key).shares, 0);
+    assert_eq!(
+        fixture.activate_stake(&mallory.key),
+        Err(custom_error(XGrowthError::StakeWarmingUp))
+    );
+
+    // Epoch 1's credit goes to Alice alone
+    fixture.distribute_rewards(agent, 1).unwrap();
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 200 * USDT);
+
+    // Once epoch 1 is credited anyone can start Mallory's stake earning
+    fixture.activate_stake(&mallory.key).unwrap();
+    assert_eq!(fixture.user_stake_state(&mallory.key).warming_amount, 0);
+    let rewards = fixture.user_rewards_state(&mallory.key);
+    assert_eq!(rewards.shares, alice_tokens);
+    assert_eq!(rewards.pending_rewards, 0);
+    assert_eq!(
+        fixture.activate_stake(&mallory.key),
+        Err(custom_error(XGrowthError::StakeWarmingUp))
+    );
+
+    // Stake added between a credit and the next epoch earns in that epoch
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    fixture.claim_rewards(mallory.key, mallory.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&mallory.key).claimed_rewards, 100 * USDT);
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 300 * USDT);
+}
+
+#[test]
+fn warming_stake_is_unstaked_first_and_joins_on_the_next_stake() {
+    let mut fixture = Fixture::new();
+    let agent = fixture.agent;
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+    let earning = alice_tokens / 2;
+    fixture.stake(&alice, earning).unwrap();
+
+    fixture.begin_epoch().unwrap();
+    fixture.stake(&alice, earning / 2).unwrap();
+    assert_eq!(fixture.user_rewards_state(&alice.key).shares, earning);
+
+    // Unstaking takes the warming stake before the earning stake
+    fixture.unstake(&alice, earning / 4).unwrap();
+    let stake = fixture.user_stake_state(&alice.key);
+    assert_eq!(stake.amount, earning + earning / 2 - earning / 4);
+    assert_eq!(stake.warming_amount, earning / 2 - earning / 4);
+    assert_eq!(fixture.user_rewards_state(&alice.key).shares, earning);
+
+    // After the credit, the next stake brings the warmed-up stake in with it
+    set_unix_timestamp(unix_timestamp() + Platform::EPOCH_DURATION);
+    fixture.record_agent_score(agent, 1).unwrap();
+    fixture.finalize_epoch(1).unwrap();
+    fixture.distribute_rewards(agent, 1).unwrap();
+    fixture.stake(&alice, 1).unwrap();
+    let stake = fixture.user_stake_state(&alice.key);
+    assert_eq!(stake.warming_amount, 0);
+    assert_eq!(fixture.user_rewards_state(&alice.key).shares, stake.amount);
+    assert_eq!(fixture.agent_state().total_shares, stake.amount);
+}
+
+#[test]
+fn stake_rejects_invalid_amounts_and_settings() {
+    let mut fixture = Fixture::new();
+    let (alice, alice_tokens) = holder(&mut fixture, 100 * USDT);
+
+    assert_eq!(
+        fixture.stake(&alice, 0),
+        Err(custom_error(XGrowthError::InvalidAmount))
+    );
+    fixture.stake(&alice, alice_tokens / 2).unwrap();
+    assert_eq!(
+        fixture.unstake(&alice, alice_tokens / 2 + 1),
+        Err(custom_error(XGrowthError::InsufficientStake))
+    );
+
+    assert_eq!(
+        fixture.set_unbonding_period(Platform::MAX_UNBONDING_PERIOD + 1),
+        Err(custom_error(XGrowthError::InvalidUnbondingPeriod))
+    );
+    assert_eq!(
+        fixture.set_unbonding_period(-1),
+        Err(custom_error(XGrowthError::InvalidUnbondingPeriod))
+    );
+    let platform = fixture.platform;
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetUnbondingPeriod { platform, authority: alice.key },
+            x_growth::instruction::SetUnbondingPeriod { unbonding_period: UNBONDING },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+}
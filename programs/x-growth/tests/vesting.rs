@@ -0,0 +1,131 @@
+// Credited epoch rewards vest linearly to stakers over the platform's vesting period
+mod common;
+
+use common::*;
+use x_growth::errors::XGrowthError;
+use x_growth::state::Platform;
+
+const EPOCH: i64 = Platform::EPOCH_DURATION;
+
+// A staker holding agent tokens bought with `usdt`, all of them staked
+fn staker(fixture: &mut Fixture, usdt: u64) -> (Trader, u64) {
+    let trader = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&trader, usdt, None).unwrap();
+    let tokens = fixture.bank.token_balance(&trader.token_account);
+    fixture.stake(&trader, tokens).unwrap();
+    (trader, tokens)
+}
+
+// Credits one scored epoch to the fixture agent and returns when it was credited
+fn credit_epoch(fixture: &mut Fixture) -> i64 {
+    let agent = fixture.agent;
+    fixture.update_performance(agent, 10, 0, 0, 0).unwrap();
+    fixture.run_epoch(&[agent]);
+    unix_timestamp()
+}
+
+// Claims what has vested so far and returns the user's total claimed
+fn claimed(fixture: &mut Fixture, trader: &Trader) -> u64 {
+    fixture.claim_rewards(trader.key, trader.usdt_account).unwrap();
+    fixture.user_rewards_state(&trader.key).claimed_rewards
+}
+
+#[test]
+fn credited_rewards_stream_out_over_the_period() {
+    let mut fixture = Fixture::new();
+    fixture.set_reward_vesting_period(EPOCH).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, _) = staker(&mut fixture, 100 * USDT);
+
+    let credited_at = credit_epoch(&mut fixture);
+    let agent = fixture.agent_state();
+    assert_eq!(agent.unvested_rewards, 200 * USDT);
+    assert_eq!(agent.reward_vesting_end, credited_at + EPOCH);
+
+    // Nothing has vested at the moment of the credit
+    assert_eq!(
+        fixture.claim_rewards(alice.key, alice.usdt_account),
+        Err(custom_error(XGrowthError::NoRewardsToClaim))
+    );
+
+    set_unix_timestamp(credited_at + EPOCH / 4);
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 50 * USDT);
+
+    // Whatever is left is released in full once the period is over
+    set_unix_timestamp(credited_at + 2 * EPOCH);
+    fixture.claim_rewards(alice.key, alice.usdt_account).unwrap();
+    assert_close(fixture.user_rewards_state(&alice.key).claimed_rewards, 200 * USDT);
+    assert_eq!(fixture.agent_state().unvested_rewards, 0);
+}
+
+#[test]
+fn a_new_credit_restarts_vesting_with_the_unvested_remainder() {
+    let mut fixture = Fixture::new();
+    fixture.set_reward_vesting_period(2 * EPOCH).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, _) = staker(&mut fixture, 100 * USDT);
+
+    // Half of the first credit has vested when the second one lands
+    let first = credit_epoch(&mut fixture);
+    let second = credit_epoch(&mut fixture);
+    assert_eq!(second, first + EPOCH);
+    assert_close(claimed(&mut fixture, &alice), 100 * USDT);
+    let agent = fixture.agent_state();
+    assert_close(agent.unvested_rewards, 300 * USDT);
+    assert_eq!(agent.reward_vesting_start, second);
+    assert_eq!(agent.reward_vesting_end, second + 2 * EPOCH);
+
+    set_unix_timestamp(second + EPOCH);
+    assert_close(claimed(&mut fixture, &alice), 250 * USDT);
+
+    set_unix_timestamp(second + 2 * EPOCH);
+    assert_close(claimed(&mut fixture, &alice), 400 * USDT);
+}
+
+#[test]
+fn late_stakers_only_share_what_vests_after_they_join() {
+    let mut fixture = Fixture::new();
+    fixture.set_reward_vesting_period(EPOCH).unwrap();
+    fixture.fund_reward_pool(1_000 * USDT).unwrap();
+    let (alice, tokens) = staker(&mut fixture, 100 * USDT);
+    let bob = fixture.new_trader(1_000 * USDT);
+    fixture.buy(&bob, 200 * USDT, None).unwrap();
+
+    let credited_at = credit_epoch(&mut fixture);
+
+    // Bob stakes as much as Alice halfway through the vesting
+    set_unix_timestamp(credited_at + EPOCH / 2);
+    fixture.stake(&bob, tokens).unwrap();
+
+    set_unix_timestamp(credited_at + EPOCH);
+    assert_close(claimed(&mut fixture, &alice), 150 * USDT);
+    assert_close(claimed(&mut fixture, &bob), 50 * USDT);
+}
+
+#[test]
+fn set_reward_vesting_period_is_bounded_and_authority_only() {
+    let mut fixture = Fixture::new();
+
+    assert_eq!(
+        fixture.set_reward_vesting_period(Platform::MAX_REWARD_VESTING_PERIOD + 1),
+        Err(custom_error(XGrowthError::InvalidVestingPeriod))
+    );
+    assert_eq!(
+        fixture.set_reward_vesting_period(-1),
+        Err(custom_error(XGrowthError::InvalidVestingPeriod))
+    );
+
+    let platform = fixture.platform;
+    let outsider = fixture.new_trader(0);
+    assert_eq!(
+        fixture.bank.process(
+            x_growth::accounts::SetRewardVestingPeriod { platform, authority: outsider.key },
+            x_growth::instruction::SetRewardVestingPeriod { reward_vesting_period: EPOCH },
+        ),
+        Err(custom_error(XGrowthError::Unauthorized))
+    );
+
+    fixture.set_reward_vesting_period(Platform::MAX_REWARD_VESTING_PERIOD).unwrap();
+    assert_eq!(fixture.platform_state().reward_vesting_period, Platform::MAX_REWARD_VESTING_PERIOD);
+}
@@ -1,6 +1,6 @@
 import { AnchorProvider, BN } from "@coral-xyz/anchor"
 import { Connection, Keypair, PublicKey } from "@solana/web3.js"
-import { getXGrowthSDK } from "../lib/solana/x-growth-sdk"
+import { getXGrowthSDK, type BondingCurveParams } from "../lib/solana/x-growth-sdk"
 
 async function main() {
   console.log("🚀 Starting X-Growth deployment...")
@@ -43,10 +43,14 @@ async function main() {
       uri: "https://x-growth.com/agents/crypto-whale",
       initialSupply: 1_000_000_000_000_000, // 1M tokens with 9 decimals
       bondingCurve: {
-        basePrice: new BN(10_000), // 0.01 USDT
-        curveFactor: new BN(1_000_000),
-        maxSupply: new BN(300_000_000_000_000), // 300K tokens
-      },
+        quadratic: {
+          0: {
+            basePrice: new BN(10_000), // 0.01 USDT
+            curveFactor: new BN(1_000_000),
+            maxSupply: new BN(300_000_000_000_000), // 300K tokens
+          },
+        },
+      } as BondingCurveParams,
     },
     {
       id: "tech-guru-02",
@@ -55,10 +59,14 @@ async function main() {
       uri: "https://x-growth.com/agents/tech-guru",
       initialSupply: 1_000_000_000_000_000,
       bondingCurve: {
-        basePrice: new BN(10_000),
-        curveFactor: new BN(1_000_000),
-        maxSupply: new BN(300_000_000_000_000),
-      },
+        linear: {
+          0: {
+            basePrice: new BN(10_000),
+            finalPrice: new BN(50_000),
+            maxSupply: new BN(300_000_000_000_000),
+          },
+        },
+      } as BondingCurveParams,
     },
   ]
 
@@ -70,8 +78,9 @@ async function main() {
       agent.name,
       agent.symbol,
       agent.uri,
-      agent.initialSupply.toNumber(),
+      agent.initialSupply,
       agent.bondingCurve,
+      usdtMint,
     )
     console.log(`✅ Agent created: ${agent.name}`)
     console.log(`   Agent PDA: ${agentPDA.toString()}`)